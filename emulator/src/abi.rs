@@ -0,0 +1,131 @@
+use {
+    rome_evm::{
+        error::{Result, RomeProgramError::*},
+        vm::RevertInfo,
+    },
+    serde_json::Value,
+    solana_program::keccak::hash,
+};
+
+// resolves the signature of the custom error using the contract ABI in the JSON format
+pub fn decode_custom_error(abi: &str, info: &mut RevertInfo) -> Result<()> {
+    let selector = match info.selector {
+        Some(selector) => selector,
+        None => return Ok(()),
+    };
+
+    let abi: Value = serde_json::from_str(abi)
+        .map_err(|e| Custom(format!("invalid ABI: {}", e)))?;
+    let items = abi
+        .as_array()
+        .ok_or(Custom("invalid ABI: array expected".to_string()))?;
+
+    for item in items.iter().filter(|item| item["type"] == "error") {
+        let signature = signature(item)?;
+
+        if hash(signature.as_bytes()).to_bytes()[..4] == selector {
+            info.custom_error = Some(signature);
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+fn signature(item: &Value) -> Result<String> {
+    let name = item["name"]
+        .as_str()
+        .ok_or(Custom("invalid ABI: error name expected".to_string()))?;
+
+    Ok(format!("{}({})", name, canonical_types(&item["inputs"])?))
+}
+
+fn canonical_types(params: &Value) -> Result<String> {
+    let params = match params.as_array() {
+        Some(params) => params,
+        None => return Ok(String::new()),
+    };
+
+    let types = params
+        .iter()
+        .map(canonical_type)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(types.join(","))
+}
+
+// tuple[2] -> (uint256,address)[2]
+fn canonical_type(param: &Value) -> Result<String> {
+    let typ = param["type"]
+        .as_str()
+        .ok_or(Custom("invalid ABI: parameter type expected".to_string()))?;
+
+    match typ.strip_prefix("tuple") {
+        Some(suffix) => Ok(format!("({}){}", canonical_types(&param["components"])?, suffix)),
+        None => Ok(typ.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::decode_custom_error,
+        rome_evm::vm::RevertInfo,
+        solana_program::keccak::hash,
+    };
+
+    const ABI: &str = r#"[
+        {"type": "function", "name": "transfer", "inputs": [{"name": "to", "type": "address"}]},
+        {"type": "error", "name": "Unauthorized", "inputs": []},
+        {
+            "type": "error",
+            "name": "Rejected",
+            "inputs": [
+                {
+                    "name": "orders",
+                    "type": "tuple[]",
+                    "components": [
+                        {"name": "amount", "type": "uint256"},
+                        {"name": "owner", "type": "address"}
+                    ]
+                },
+                {"name": "ids", "type": "bytes32[2]"}
+            ]
+        }
+    ]"#;
+
+    fn revert(signature: &str) -> RevertInfo {
+        let mut data = hash(signature.as_bytes()).to_bytes()[..4].to_vec();
+        data.extend([0_u8; 32]);
+        RevertInfo::decode(&data)
+    }
+
+    #[test]
+    fn tuple_and_array_params() {
+        let signature = "Rejected((uint256,address)[],bytes32[2])";
+        let mut info = revert(signature);
+        decode_custom_error(ABI, &mut info).unwrap();
+        assert_eq!(info.custom_error.as_deref(), Some(signature));
+
+        let mut info = revert("Unauthorized()");
+        decode_custom_error(ABI, &mut info).unwrap();
+        assert_eq!(info.custom_error.as_deref(), Some("Unauthorized()"));
+    }
+
+    #[test]
+    fn unknown_selector() {
+        // the function is not the error
+        let mut info = revert("transfer(address)");
+        decode_custom_error(ABI, &mut info).unwrap();
+        assert!(info.custom_error.is_none());
+    }
+
+    #[test]
+    fn invalid_abi() {
+        let mut info = revert("Unauthorized()");
+        assert!(decode_custom_error("[{", &mut info).is_err());
+        assert!(decode_custom_error(r#"{"type": "error"}"#, &mut info).is_err());
+        assert!(decode_custom_error(r#"[{"type": "error", "inputs": []}]"#, &mut info).is_err());
+        assert!(info.custom_error.is_none());
+    }
+}
@@ -1,12 +1,11 @@
 use solana_program::address_lookup_table::state::LOOKUP_TABLE_MAX_ADDRESSES;
 use {
     super::Emulation,
-    crate::state::State,
+    crate::{source::AccountSource, state::State},
     rome_evm::{
         api::alt_alloc::{args, get_alloc_actions, Alt, Action::{self, *}, },
         error::Result, AltId, AltSlots, Data,
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{
         address_lookup_table::state::{AddressLookupTable, LookupTableMeta,},
         account_info::IntoAccountInfo, msg, pubkey::Pubkey, 
@@ -18,12 +17,12 @@ pub fn alt_alloc<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     msg!("Instruction: alt_alloc");
 
     let (holder, chain, session, recent_slot, total, keys) = args(data)?;
-    let state = State::new(program_id, Some(*signer), client, chain)?;
+    let state = State::new(program_id, Some(*signer), source, chain)?;
 
     let mut bind = state.info_alt_slots(holder, true)?;
     let info = bind.into_account_info();
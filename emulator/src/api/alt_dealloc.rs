@@ -1,13 +1,12 @@
 use {
     super::Emulation,
-    crate::state::State,
+    crate::{source::AccountSource, state::State},
     rome_evm::{
         api::{
             alt_dealloc::{args, get_dealloc_actions, }
         },
         error::Result,
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{
         account_info::IntoAccountInfo, msg, pubkey::Pubkey,
     },
@@ -19,12 +18,12 @@ pub fn alt_dealloc<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     msg!("Instruction: alt_dealloc");
 
     let (holder, chain, session) = args(data)?;
-    let state = State::new(program_id, Some(*signer), client, chain)?;
+    let state = State::new(program_id, Some(*signer), source, chain)?;
 
     let mut bind = state.info_alt_slots(holder, true)?;
     let info = bind.into_account_info();
@@ -1,7 +1,6 @@
 use {
-    crate::state::State,
+    crate::{source::AccountSource, state::State},
     rome_evm::{error::Result, StateHolder, H256},
-    solana_client::rpc_client::RpcClient,
     solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey},
     std::sync::Arc,
 };
@@ -11,12 +10,12 @@ pub fn confirm_tx_iterative(
     holder: u64,
     hash: H256,
     signer: &Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
     chain: u64,
     session: u64,
 ) -> Result<bool> {
     msg!("confirmation of iterative tx");
-    let state = State::new(program_id, Some(*signer), client, chain)?;
+    let state = State::new(program_id, Some(*signer), source, chain)?;
     let mut bind = state.info_state_holder(holder, false)?;
     let info = bind.into_account_info();
 
@@ -0,0 +1,30 @@
+use {
+    super::{do_tx::do_tx_with, eth_call::eth_call_with, Emulation},
+    crate::{source::AccountSource, struct_logger::StructLoggerConfig},
+    rome_evm::{error::Result, tx::legacy::Legacy},
+    solana_program::{msg, pubkey::Pubkey},
+    std::sync::Arc,
+};
+
+// eth_call with the geth structLogger, the steps are reported in Emulation::struct_logs
+pub fn debug_trace_call(
+    program_id: &Pubkey,
+    legacy: Legacy,
+    source: Arc<dyn AccountSource>,
+    config: StructLoggerConfig,
+) -> Result<Emulation> {
+    msg!("debug_traceCall");
+    eth_call_with(program_id, legacy, source, |state| state.enable_struct_logger(config))
+}
+
+// atomic transaction with the geth structLogger, data has the format of DoTx instruction
+pub fn debug_trace_tx(
+    program_id: &Pubkey,
+    data: &[u8],
+    signer: &Pubkey,
+    source: Arc<dyn AccountSource>,
+    config: StructLoggerConfig,
+) -> Result<Emulation> {
+    msg!("debug_traceTransaction");
+    do_tx_with(program_id, data, signer, source, |state| state.enable_struct_logger(config))
+}
@@ -1,11 +1,10 @@
 use {
     super::Emulation,
-    crate::{state::State, context::ContextAt,},
+    crate::{source::AccountSource, state::State, context::ContextAt,},
     rome_evm::{
         api::deposit::{args, mint, from_rlp, spl_transfer},
         error::Result,
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{msg, pubkey::Pubkey},
     std::sync::Arc,
 };
@@ -14,12 +13,12 @@ pub fn deposit<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     msg!("Instruction: deposit");
 
     let (chain, rlp) = args(data)?;
-    let state = State::new(program_id, Some(*signer), client, chain)?;
+    let state = State::new(program_id, Some(*signer), source, chain)?;
     let context = ContextAt::new(&state);
 
     let tx = from_rlp(rlp)?;
@@ -0,0 +1,38 @@
+use {
+    super::Emulation,
+    crate::{source::AccountSource, state::State},
+    rome_evm::{
+        api::{
+            dereg_owner::{check, dereg},
+            reg_owner::args,
+        },
+        error::Result,
+    },
+    solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey},
+    std::sync::Arc,
+};
+pub fn dereg_owner<'a>(
+    program_id: &'a Pubkey,
+    data: &'a [u8],
+    signer: &'a Pubkey,
+    source: Arc<dyn AccountSource>,
+) -> Result<Emulation> {
+    let chain = args(data)?;
+    msg!("Instruction: chain_id deregistration {}", chain);
+
+    let state = State::new(program_id, Some(*signer), source, chain)?;
+
+    let (len, key) = {
+        let mut bind = state.info_owner_reg(true)?;
+        let info = bind.into_account_info();
+        check(&info, signer, chain)?;
+        let len = dereg(&info, chain)?;
+        let key = bind.0;
+        state.update(bind);
+        (len, key)
+    };
+
+    state.realloc(&key, len)?;
+
+    Emulation::without_vm(&state)
+}
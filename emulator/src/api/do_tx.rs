@@ -1,6 +1,6 @@
 use {
     super::Emulation,
-    crate::{state::State, ContextAt},
+    crate::{overrides::InvokeHints, source::AccountSource, state::State, ContextAt},
     rome_evm::{
         api::split_fee,
         error::Result,
@@ -8,7 +8,6 @@ use {
         vm::{vm_atomic::{VmAt, MachineAt}, Execute},
         H160,
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{msg, pubkey::Pubkey},
     std::sync::Arc,
 };
@@ -17,16 +16,52 @@ pub fn do_tx<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     msg!("Instruction: Atomic transaction");
+    do_tx_with(program_id, data, signer, source, |_| {})
+}
+
+// emulates the transaction with the callTracer and prestateTracer
+pub fn do_tx_traced<'a>(
+    program_id: &'a Pubkey,
+    data: &'a [u8],
+    signer: &'a Pubkey,
+    source: Arc<dyn AccountSource>,
+) -> Result<Emulation> {
+    msg!("Instruction: Atomic transaction, traced");
+    do_tx_with(program_id, data, signer, source, |state| state.enable_tracer())
+}
+
+// emulates the transaction with the declared effects of the programs called by the generic CPI
+pub fn do_tx_hinted<'a>(
+    program_id: &'a Pubkey,
+    data: &'a [u8],
+    signer: &'a Pubkey,
+    hints: InvokeHints,
+    source: Arc<dyn AccountSource>,
+) -> Result<Emulation> {
+    msg!("Instruction: Atomic transaction, hinted");
+    do_tx_with(program_id, data, signer, source, |state| state.set_invoke_hints(hints))
+}
+
+// setup installs the tracers into the state
+pub fn do_tx_with(
+    program_id: &Pubkey,
+    data: &[u8],
+    signer: &Pubkey,
+    source: Arc<dyn AccountSource>,
+    setup: impl FnOnce(&mut State),
+) -> Result<Emulation> {
     let (fee_addr, rlp) = split_fee(data)?;
     let chain = Tx::chain_id_from_rlp(rlp)?;
-    let state = State::new(program_id, Some(*signer), client, chain)?;
+    let mut state = State::new(program_id, Some(*signer), source, chain)?;
+    setup(&mut state);
     atomic_transaction(state, rlp, fee_addr)
 }
 
 pub fn atomic_transaction(state: State, rlp: &[u8], fee_addr: Option<H160>) -> Result<Emulation> {
+    state.prefetch_tx(rlp)?;
     let context = ContextAt::new(&state);
     let mut vm = VmAt::new(&state, rlp, fee_addr, &context)?;
     vm.consume(MachineAt::Lock)?;
@@ -46,9 +81,184 @@ pub fn atomic_transaction(state: State, rlp: &[u8], fee_addr: Option<H160>) -> R
         state.syscall.count(),
         fee,
         refund,
+        vm.vm.handler.gas.used,
         false,
         None,
     );
 
     report
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{do_tx, do_tx_hinted},
+        crate::{
+            api::{
+                eth_get_balance::eth_get_balance, eth_get_code::eth_get_code,
+                eth_get_tx_count::eth_get_tx_count,
+            },
+            fixture::{address, authorization, legacy, sign, sign_set_code, wallet, Fixture, CHAIN},
+            fork::Fork,
+            overrides::InvokeHints,
+        },
+        rome_evm::{state::aux::Account, H160, MIN_GAS_PRICE, U256},
+        solana_program::{instruction::AccountMeta, pubkey::Pubkey},
+        std::collections::BTreeMap,
+    };
+
+    const SECRET: [u8; 32] = [7; 32];
+    // invoke(bytes32,(bytes32,bool,bool)[],bytes)
+    const INVOKE_ID: [u8; 4] = [0x74, 0x80, 0xcb, 0x86];
+
+    fn word(value: usize) -> Vec<u8> {
+        let mut buf = vec![0_u8; 32];
+        U256::from(value).to_big_endian(&mut buf);
+        buf
+    }
+
+    fn invoke_abi(program_id: &Pubkey, metas: &[AccountMeta], data: &[u8]) -> Vec<u8> {
+        let mut abi = INVOKE_ID.to_vec();
+        abi.extend(program_id.to_bytes());
+        abi.extend(word(32 * 3));
+        abi.extend(word(32 * 4 + metas.len() * 32 * 3));
+
+        abi.extend(word(metas.len()));
+        for meta in metas {
+            abi.extend(meta.pubkey.to_bytes());
+            abi.extend(word(meta.is_signer as usize));
+            abi.extend(word(meta.is_writable as usize));
+        }
+
+        abi.extend(word(data.len()));
+        let mut data = data.to_vec();
+        data.resize((data.len() + 31) / 32 * 32, 0);
+        abi.extend(data);
+        abi
+    }
+
+    // the tx calls the generic CPI precompile directly
+    fn invoke(
+        fixture: &mut Fixture,
+        program_id: &Pubkey,
+        account: &Pubkey,
+        hints: InvokeHints,
+    ) -> crate::Emulation {
+        let from = address(&SECRET);
+        fixture.balance(&from, U256::zero());
+
+        let mut cpi = [0_u8; 20];
+        cpi[0] = 0xff;
+        cpi[19] = 0x08;
+        let metas = [AccountMeta::new(*account, false)];
+
+        let mut tx = legacy(from, H160(cpi), invoke_abi(program_id, &metas, b"memo"));
+        tx.gas_limit = 10_000_000.into();
+        let mut data = vec![0];
+        data.extend(sign(&tx, &SECRET));
+
+        do_tx_hinted(&fixture.program_id, &data, &fixture.signer, hints, fixture.source()).unwrap()
+    }
+
+    #[test]
+    fn hint_applied() {
+        let mut fixture = Fixture::new();
+        let program_id = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        fixture.dump.insert(program_id, Account::new_executable());
+        fixture.dump.insert(account, wallet(1_000_000));
+
+        let hint = Account {
+            data: vec![1, 2, 3],
+            owner: program_id,
+            ..wallet(1_000_000)
+        };
+        let hints = BTreeMap::from([(program_id, BTreeMap::from([(account, hint)]))]);
+
+        let emulation = invoke(&mut fixture, &program_id, &account, hints);
+        assert!(emulation.vm.as_ref().unwrap().exit_reason.is_succeed());
+
+        let item = emulation.accounts.get(&account).unwrap();
+        assert!(item.account.writable);
+        assert_eq!(item.account.data, vec![1, 2, 3]);
+        assert_eq!(item.account.owner, program_id);
+    }
+
+    #[test]
+    fn not_executable() {
+        let mut fixture = Fixture::new();
+        let program_id = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        fixture.dump.insert(program_id, wallet(1_000_000));
+
+        let emulation = invoke(&mut fixture, &program_id, &account, InvokeHints::new());
+        assert!(emulation.vm.as_ref().unwrap().exit_reason.is_revert());
+        assert!(emulation.accounts.get(&account).is_none());
+    }
+
+    #[test]
+    fn out_of_gas() {
+        let mut fixture = Fixture::new();
+        let from = address(&SECRET);
+        let balance = U256::exp10(18);
+        fixture.balance(&from, balance);
+        // JUMPDEST PUSH1 0 JUMP
+        let contract = H160::repeat_byte(0xcc);
+        fixture.contract(&contract, U256::zero(), &[0x5b, 0x60, 0x00, 0x56]);
+
+        let mut tx = legacy(from, contract, vec![]);
+        tx.gas_limit = 100_000.into();
+        tx.gas_price = MIN_GAS_PRICE.into();
+        let fee_addr = H160::repeat_byte(0xfe);
+        let mut data = vec![1];
+        data.extend(fee_addr.as_bytes());
+        data.extend(sign(&tx, &SECRET));
+
+        let emulation = do_tx(&fixture.program_id, &data, &fixture.signer, fixture.source()).unwrap();
+        assert!(emulation.vm.as_ref().unwrap().exit_reason.is_error());
+
+        // the failed tx is committed: the nonce is incremented, all the gas is charged
+        let fork = Fork::new(fixture.source());
+        fork.commit(&emulation);
+        let fee = tx.gas_limit * tx.gas_price;
+        assert_eq!(eth_get_tx_count(&fixture.program_id, &from, fork.clone(), CHAIN).unwrap(), 1);
+        assert_eq!(
+            eth_get_balance(&fixture.program_id, &from, fork.clone(), CHAIN).unwrap(),
+            balance - fee
+        );
+        assert_eq!(eth_get_balance(&fixture.program_id, &fee_addr, fork, CHAIN).unwrap(), fee);
+    }
+
+    #[test]
+    fn revert_keeps_authorization() {
+        let mut fixture = Fixture::new();
+        let from = address(&SECRET);
+        fixture.balance(&from, U256::zero());
+        let secret = [9; 32];
+        let authority = address(&secret);
+        fixture.balance(&authority, U256::zero());
+        // PUSH1 0 PUSH1 0 REVERT
+        let contract = H160::repeat_byte(0xcc);
+        fixture.contract(&contract, U256::zero(), &[0x60, 0x00, 0x60, 0x00, 0xfd]);
+        let delegate = H160::repeat_byte(0xdd);
+
+        let mut tx = legacy(from, contract, vec![]);
+        tx.gas_limit = 100_000.into();
+        let auth = authorization(delegate, 0, &secret);
+        let mut data = vec![0];
+        data.extend(sign_set_code(&tx, vec![auth.clone()], &SECRET));
+
+        let emulation = do_tx(&fixture.program_id, &data, &fixture.signer, fixture.source()).unwrap();
+        assert!(emulation.vm.as_ref().unwrap().exit_reason.is_revert());
+
+        // the journal of the call is reverted, the authorization is applied before the call
+        let fork = Fork::new(fixture.source());
+        fork.commit(&emulation);
+        assert_eq!(eth_get_tx_count(&fixture.program_id, &from, fork.clone(), CHAIN).unwrap(), 1);
+        assert_eq!(eth_get_tx_count(&fixture.program_id, &authority, fork.clone(), CHAIN).unwrap(), 1);
+        assert_eq!(
+            eth_get_code(&fixture.program_id, &authority, fork, CHAIN).unwrap(),
+            auth.designator()
+        );
+    }
+}
@@ -1,8 +1,7 @@
 use {
     super::{do_tx::atomic_transaction, Emulation},
-    crate::state::State,
+    crate::{source::AccountSource, state::State},
     rome_evm::{api::do_tx_holder::{args, add_transmit_fee}, error::Result, Holder,},
-    solana_client::rpc_client::RpcClient,
     solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey},
     std::sync::Arc,
 };
@@ -11,12 +10,12 @@ pub fn do_tx_holder<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     msg!("Instruction: Atomic transaction from holder");
 
     let (holder, hash, chain, fee_addr) = args(data)?;
-    let state = State::new(program_id, Some(*signer), client.clone(), chain)?;
+    let state = State::new(program_id, Some(*signer), source.clone(), chain)?;
 
     let mut bind = state.info_tx_holder(holder, false)?;
     let info = bind.into_account_info();
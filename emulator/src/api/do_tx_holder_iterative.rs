@@ -1,12 +1,11 @@
 use {
     super::{do_tx_iterative::iterative_tx, Emulation},
-    crate::{context::ContextIt, state::State},
+    crate::{context::ContextIt, source::AccountSource, state::State},
     rome_evm::{
         api::{split_fee, split_hash, split_u64},
         error::Result,
         Holder, H160, H256,
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey},
     std::sync::Arc,
 };
@@ -26,16 +25,17 @@ pub fn do_tx_holder_iterative<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     msg!("Instruction: Iterative transaction from holder");
 
     let (session, holder, hash, chain, fee_addr) = args(data)?;
-    let state = State::new(program_id, Some(*signer), Arc::clone(&client), chain)?;
+    let state = State::new(program_id, Some(*signer), Arc::clone(&source), chain)?;
 
     let mut bind = state.info_tx_holder(holder, false)?;
     let info = bind.into_account_info();
     let rlp = Holder::rlp(&info, hash, chain)?;
+    state.prefetch_tx(&rlp)?;
 
     let context = ContextIt::new(&state, holder, hash, session, fee_addr, &rlp, true)?;
     iterative_tx(&state, context, false)
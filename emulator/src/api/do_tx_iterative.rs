@@ -2,6 +2,7 @@ use {
     super::Emulation,
     crate::{
         context::ContextIt,
+        source::AccountSource,
         state::State,
     },
     rome_evm::{
@@ -12,7 +13,6 @@ use {
         vm::{self, vm_iterative::MachineIt, Execute},
         H160, H256,
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{keccak, msg, pubkey::Pubkey},
     std::sync::Arc,
 };
@@ -68,6 +68,7 @@ pub fn iterative_tx(
                     syscalls,
                     lmp_fee,
                     lmp_refund,
+                    vm.vm.handler.gas.used,
                     is_gas_estimate,
                     Some(&context)
                 );
@@ -90,14 +91,38 @@ pub fn do_tx_iterative<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     msg!("Instruction: Iterative transaction");
+    do_tx_iterative_with(program_id, data, signer, source, |_| {})
+}
+
+// emulates the transaction with the callTracer and prestateTracer
+pub fn do_tx_iterative_traced<'a>(
+    program_id: &'a Pubkey,
+    data: &'a [u8],
+    signer: &'a Pubkey,
+    source: Arc<dyn AccountSource>,
+) -> Result<Emulation> {
+    msg!("Instruction: Iterative transaction, traced");
+    do_tx_iterative_with(program_id, data, signer, source, |state| state.enable_tracer())
+}
+
+// setup installs the tracers into the state
+pub fn do_tx_iterative_with(
+    program_id: &Pubkey,
+    data: &[u8],
+    signer: &Pubkey,
+    source: Arc<dyn AccountSource>,
+    setup: impl FnOnce(&mut State),
+) -> Result<Emulation> {
     let (session, holder, fee_addr, rlp) = args(data)?;
     let hash = H256::from(keccak::hash(rlp).to_bytes());
     let chain = Tx::chain_id_from_rlp(rlp)?;
 
-    let state = State::new(program_id, Some(*signer), Arc::clone(&client), chain)?;
+    let mut state = State::new(program_id, Some(*signer), Arc::clone(&source), chain)?;
+    setup(&mut state);
+    state.prefetch_tx(rlp)?;
     let context = ContextIt::new(&state, holder, hash, session, fee_addr, rlp, false)?;
     iterative_tx(&state, context, false)
 }
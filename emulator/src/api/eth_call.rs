@@ -1,24 +1,78 @@
 use {
     super::Emulation,
-    crate::{state::State, VmCall, MachineEthCall},
+    crate::{
+        overrides::{check_overrides, BlockOverrides, InvokeHints, StateOverride},
+        source::AccountSource, state::State, VmCall, MachineEthCall,
+    },
     rome_evm::{
         error::Result,
         tx::legacy::Legacy,
+        BLOCK_GAS_LIMIT,
         vm::{Execute,},
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{msg, pubkey::Pubkey},
     std::sync::Arc,
 };
 
-pub fn eth_call(program_id: &Pubkey, legacy: Legacy, client: Arc<RpcClient>) -> Result<Emulation> {
+pub fn eth_call(program_id: &Pubkey, legacy: Legacy, source: Arc<dyn AccountSource>) -> Result<Emulation> {
     msg!("eth_call");
-    let state = State::new(program_id, None, client, legacy.chain_id.as_u64())?;
-    let mut vm = VmCall::new(&state, legacy)?;
+    eth_call_with(program_id, legacy, source, |_| {})
+}
+
+// eth_call with the geth-style stateOverride and blockOverrides
+pub fn eth_call_overridden(
+    program_id: &Pubkey,
+    legacy: Legacy,
+    overrides: StateOverride,
+    block_overrides: BlockOverrides,
+    source: Arc<dyn AccountSource>,
+) -> Result<Emulation> {
+    msg!("eth_call, overridden");
+    check_overrides(&overrides)?;
+    eth_call_with(program_id, legacy, source, |state| {
+        state.set_overrides(overrides, block_overrides)
+    })
+}
+
+// eth_call with the declared effects of the programs called by the generic CPI
+pub fn eth_call_hinted(
+    program_id: &Pubkey,
+    legacy: Legacy,
+    hints: InvokeHints,
+    source: Arc<dyn AccountSource>,
+) -> Result<Emulation> {
+    msg!("eth_call, hinted");
+    eth_call_with(program_id, legacy, source, |state| state.set_invoke_hints(hints))
+}
+
+// eth_call with the callTracer and prestateTracer
+pub fn eth_call_traced(program_id: &Pubkey, legacy: Legacy, source: Arc<dyn AccountSource>) -> Result<Emulation> {
+    msg!("eth_call, traced");
+    eth_call_with(program_id, legacy, source, |state| state.enable_tracer())
+}
+
+// setup installs the tracers into the state
+pub fn eth_call_with(
+    program_id: &Pubkey,
+    mut legacy: Legacy,
+    source: Arc<dyn AccountSource>,
+    setup: impl FnOnce(&mut State),
+) -> Result<Emulation> {
+    if legacy.gas_limit.is_zero() {
+        legacy.gas_limit = BLOCK_GAS_LIMIT.into();
+    }
+    let mut state = State::new(program_id, None, source, legacy.chain_id.as_u64())?;
+    setup(&mut state);
+    call(&state, legacy)
+}
+
+pub fn call(state: &State, legacy: Legacy) -> Result<Emulation> {
+    let mut vm = VmCall::new(state, legacy)?;
+    state.block_overrides.apply(&mut vm.vm.handler);
     vm.consume(MachineEthCall::Init)?;
 
     let report = Emulation::with_vm(
-        &state,
+        state,
         vm.vm.exit_reason,
         vm.vm.return_value,
         vm.vm.steps_executed,
@@ -31,9 +85,200 @@ pub fn eth_call(program_id: &Pubkey, legacy: Legacy, client: Arc<RpcClient>) ->
         state.syscall.count(),
         0,
         0,
+        vm.vm.handler.gas.used,
         false,
         None,
     );
 
     report
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{eth_call, eth_call_overridden},
+        crate::{
+            fixture::{legacy, Fixture, CHAIN},
+            overrides::{AccountOverride, BlockOverrides, StateOverride},
+            state::State,
+        },
+        rome_evm::{origin::Origin, H160, U256},
+        std::collections::BTreeMap,
+    };
+
+    // SELFBALANCE PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+    const SELF_BALANCE: [u8; 9] = [0x47, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+    // returns the slots 1 and 2
+    const LOAD_TWO: [u8; 17] = [
+        0x60, 0x01, 0x54, 0x60, 0x00, 0x52, 0x60, 0x02, 0x54, 0x60, 0x20, 0x52, 0x60, 0x40, 0x60,
+        0x00, 0xf3,
+    ];
+    // returns NUMBER, TIMESTAMP and COINBASE
+    const BLOCK: [u8; 17] = [
+        0x43, 0x60, 0x00, 0x52, 0x42, 0x60, 0x20, 0x52, 0x41, 0x60, 0x40, 0x52, 0x60, 0x60, 0x60,
+        0x00, 0xf3,
+    ];
+
+    fn word(value: usize) -> Vec<u8> {
+        let mut buf = vec![0_u8; 32];
+        U256::from(value).to_big_endian(&mut buf);
+        buf
+    }
+
+    fn call_overridden(
+        fixture: &Fixture,
+        to: H160,
+        overrides: StateOverride,
+        block_overrides: BlockOverrides,
+    ) -> Vec<u8> {
+        let call = legacy(H160::repeat_byte(1), to, vec![]);
+        let emulation =
+            eth_call_overridden(&fixture.program_id, call, overrides, block_overrides, fixture.source())
+                .unwrap();
+        let vm = emulation.vm.unwrap();
+        assert!(vm.exit_reason.is_succeed());
+        vm.return_value.unwrap_or_default()
+    }
+
+    // the contract with the slots 1 and 2 set to 10 and 20
+    fn stored(fixture: &mut Fixture, to: &H160) {
+        fixture.contract(to, U256::zero(), &LOAD_TWO);
+        fixture.storage(to, &U256::from(1), &U256::from(10));
+        fixture.storage(to, &U256::from(2), &U256::from(20));
+    }
+
+    #[test]
+    fn precompile_error() {
+        let fixture = Fixture::new();
+        // modexp: the base length exceeds the limit of the syscall
+        let mut input = word(1_000);
+        input.extend(word(0));
+        input.extend(word(1));
+
+        let mut call = legacy(H160::repeat_byte(1), H160::from_low_u64_be(5), input);
+        call.gas_limit = 100_000.into();
+        let emulation = eth_call(&fixture.program_id, call, fixture.source()).unwrap();
+
+        // the failed precompile does not revert, the forwarded gas is consumed
+        let vm = emulation.vm.unwrap();
+        assert!(vm.exit_reason.is_error());
+        assert!(vm.return_value.unwrap_or_default().is_empty());
+        assert_eq!(vm.gas_used, 100_000);
+    }
+
+    #[test]
+    fn balance_and_code_override() {
+        let fixture = Fixture::new();
+        let to = H160::repeat_byte(2);
+        let overrides = StateOverride::from([(
+            to,
+            AccountOverride {
+                balance: Some(U256::from(7)),
+                code: Some(SELF_BALANCE.to_vec()),
+                ..AccountOverride::default()
+            },
+        )]);
+
+        let value = call_overridden(&fixture, to, overrides, BlockOverrides::default());
+        assert_eq!(value, word(7));
+    }
+
+    #[test]
+    fn nonce_override() {
+        let mut fixture = Fixture::new();
+        let address = H160::repeat_byte(1);
+        fixture.balance(&address, U256::one());
+        let overrides = StateOverride::from([(
+            address,
+            AccountOverride {
+                nonce: Some(5),
+                ..AccountOverride::default()
+            },
+        )]);
+
+        let mut state = State::new(&fixture.program_id, None, fixture.source(), CHAIN).unwrap();
+        assert_eq!(state.nonce(&address).unwrap(), 0);
+        state.set_overrides(overrides, BlockOverrides::default());
+        assert_eq!(state.nonce(&address).unwrap(), 5);
+    }
+
+    #[test]
+    fn state_override_zeroes_unlisted_slots() {
+        let mut fixture = Fixture::new();
+        let to = H160::repeat_byte(2);
+        stored(&mut fixture, &to);
+        let value = call_overridden(&fixture, to, StateOverride::new(), BlockOverrides::default());
+        assert_eq!(value, [word(10), word(20)].concat());
+
+        let overrides = StateOverride::from([(
+            to,
+            AccountOverride {
+                state: Some(BTreeMap::from([(U256::from(1), U256::from(11))])),
+                ..AccountOverride::default()
+            },
+        )]);
+        let value = call_overridden(&fixture, to, overrides, BlockOverrides::default());
+        assert_eq!(value, [word(11), word(0)].concat());
+    }
+
+    #[test]
+    fn state_diff_override() {
+        let mut fixture = Fixture::new();
+        let to = H160::repeat_byte(2);
+        stored(&mut fixture, &to);
+
+        let overrides = StateOverride::from([(
+            to,
+            AccountOverride {
+                state_diff: Some(BTreeMap::from([(U256::from(1), U256::from(11))])),
+                ..AccountOverride::default()
+            },
+        )]);
+        let value = call_overridden(&fixture, to, overrides, BlockOverrides::default());
+        assert_eq!(value, [word(11), word(20)].concat());
+    }
+
+    #[test]
+    fn block_overrides() {
+        let mut fixture = Fixture::new();
+        let to = H160::repeat_byte(2);
+        fixture.contract(&to, U256::zero(), &BLOCK);
+        let coinbase = H160::repeat_byte(0xcb);
+        let block_overrides = BlockOverrides {
+            number: Some(U256::from(12_345)),
+            time: Some(U256::from(1_800_000_000)),
+            coinbase: Some(coinbase),
+            chain_id: None,
+        };
+
+        let value = call_overridden(&fixture, to, StateOverride::new(), block_overrides);
+        let mut expected_coinbase = vec![0_u8; 12];
+        expected_coinbase.extend(coinbase.as_bytes());
+        assert_eq!(value, [word(12_345), word(1_800_000_000), expected_coinbase].concat());
+    }
+
+    #[test]
+    fn state_and_state_diff_conflict() {
+        let fixture = Fixture::new();
+        let to = H160::repeat_byte(2);
+        let slots = BTreeMap::from([(U256::from(1), U256::from(11))]);
+        let overrides = StateOverride::from([(
+            to,
+            AccountOverride {
+                state: Some(slots.clone()),
+                state_diff: Some(slots),
+                ..AccountOverride::default()
+            },
+        )]);
+
+        let call = legacy(H160::repeat_byte(1), to, vec![]);
+        let res = eth_call_overridden(
+            &fixture.program_id,
+            call,
+            overrides,
+            BlockOverrides::default(),
+            fixture.source(),
+        );
+        assert!(res.is_err());
+    }
+}
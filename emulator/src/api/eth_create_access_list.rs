@@ -0,0 +1,110 @@
+use {
+    super::{eth_call::call, Emulation},
+    crate::{source::AccountSource, state::State},
+    rome_evm::{
+        error::Result,
+        precompile::non_evm_program,
+        tx::{eip2930::{AccessList, AccessListItem}, legacy::Legacy},
+        BLOCK_GAS_LIMIT, H160, H256,
+    },
+    solana_program::{msg, pubkey::Pubkey},
+    std::{collections::BTreeMap, sync::Arc},
+};
+
+// computes the EIP-2930 access list of the call from the touched accounts and storage slots
+pub fn eth_create_access_list(
+    program_id: &Pubkey,
+    mut legacy: Legacy,
+    source: Arc<dyn AccountSource>,
+) -> Result<(AccessList, Emulation)> {
+    msg!("eth_createAccessList");
+    if legacy.gas_limit.is_zero() {
+        legacy.gas_limit = BLOCK_GAS_LIMIT.into();
+    }
+    let state = State::new(program_id, None, source, legacy.chain_id.as_u64())?;
+    let (from, to) = (legacy.from, legacy.to);
+    let emulation = call(&state, legacy)?;
+
+    // the sender, the recipient and the precompiles are warm without the access list,
+    // they are listed only with the storage slots
+    let excluded = |address: &H160| {
+        *address == from || Some(*address) == to || non_evm_program(address, &state).is_some()
+    };
+
+    let mut touched = BTreeMap::<H160, Vec<H256>>::new();
+    for address in emulation.accounts.values().filter_map(|item| item.address) {
+        touched.entry(address).or_default();
+    }
+    for (address, slots) in emulation.storage.iter() {
+        let keys = touched.entry(*address).or_default();
+        for slot in slots.keys() {
+            let mut key = H256::zero();
+            slot.to_big_endian(key.as_bytes_mut());
+            keys.push(key);
+        }
+    }
+
+    let list = touched
+        .into_iter()
+        .filter(|(address, keys)| !keys.is_empty() || !excluded(address))
+        .map(|(address, storage_keys)| AccessListItem { address, storage_keys })
+        .collect();
+
+    Ok((AccessList(list), emulation))
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::eth_create_access_list,
+        crate::fixture::{legacy, Fixture},
+        rome_evm::{H160, H256, U256},
+    };
+
+    // PUSH1 1 PUSH1 5 SSTORE,
+    // STATICCALL(GAS, 0x04, 0, 0, 0, 0) POP: the identity precompile without the slots,
+    // PUSH1 0x33 BALANCE POP, STOP
+    const TOUCH: [u8; 23] = [
+        0x60, 0x01, 0x60, 0x05, 0x55, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x04,
+        0x5a, 0xfa, 0x50, 0x60, 0x33, 0x31, 0x50, 0x00,
+    ];
+
+    #[test]
+    fn touched_slots_and_accounts() {
+        let mut fixture = Fixture::new();
+        let from = H160::repeat_byte(1);
+        let to = H160::repeat_byte(2);
+        let other = H160::from_low_u64_be(0x33);
+        fixture.contract(&to, U256::zero(), &TOUCH);
+        fixture.balance(&other, U256::one());
+
+        let (list, emulation) =
+            eth_create_access_list(&fixture.program_id, legacy(from, to, vec![]), fixture.source())
+                .unwrap();
+        assert!(emulation.vm.unwrap().exit_reason.is_succeed());
+
+        let addresses = list.0.iter().map(|item| item.address).collect::<Vec<_>>();
+        // the recipient is listed with the touched slot
+        let item = list.0.iter().find(|item| item.address == to).unwrap();
+        assert_eq!(item.storage_keys, vec![H256::from_low_u64_be(5)]);
+        // the touched account without the slots
+        assert!(addresses.contains(&other));
+        // the sender and the precompile are warm without the access list
+        assert!(!addresses.contains(&from));
+        assert!(!addresses.contains(&H160::from_low_u64_be(4)));
+    }
+
+    #[test]
+    fn sender_without_slots_excluded() {
+        let mut fixture = Fixture::new();
+        let from = H160::repeat_byte(1);
+        let to = H160::repeat_byte(2);
+        fixture.balance(&from, U256::exp10(18));
+        fixture.contract(&to, U256::zero(), &[0x00]);
+
+        let (list, _) =
+            eth_create_access_list(&fixture.program_id, legacy(from, to, vec![]), fixture.source())
+                .unwrap();
+        assert!(list.0.is_empty());
+    }
+}
@@ -1,24 +1,26 @@
 use {
     super::{do_tx_iterative::iterative_tx, fake},
-    crate::{context::ContextIt, state::State, Emulation},
+    crate::{context::ContextIt, source::AccountSource, state::State, Emulation},
     rome_evm::{
-        error::Result, tx::legacy::Legacy,
+        error::Result, tx::legacy::Legacy, BLOCK_GAS_LIMIT,
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{msg, pubkey::Pubkey},
     std::sync::Arc,
 };
 
 pub fn eth_estimate_gas(
     program_id: &Pubkey,
-    legacy: Legacy,
-    client: Arc<RpcClient>,
+    mut legacy: Legacy,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     msg!(">> eth_estimateGas emulator started ..");
+    if legacy.gas_limit.is_zero() {
+        legacy.gas_limit = BLOCK_GAS_LIMIT.into();
+    }
     let state = State::new(
         program_id,
         Some(fake::ID),
-        Arc::clone(&client),
+        Arc::clone(&source),
         legacy.chain_id.as_u64(),
     )?;
     
@@ -1,7 +1,6 @@
 use {
-    crate::state::State,
+    crate::{source::AccountSource, state::State},
     rome_evm::{error::Result, Code, Data, H160},
-    solana_client::rpc_client::RpcClient,
     solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey},
     std::sync::Arc,
 };
@@ -9,11 +8,11 @@ use {
 pub fn eth_get_code<'a>(
     program_id: &'a Pubkey,
     address: &'a H160,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
     chain: u64,
 ) -> Result<Vec<u8>> {
     msg!("eth_getCode");
-    let state = State::new(program_id, None, client, chain)?;
+    let state = State::new(program_id, None, source, chain)?;
     let code = if let Ok(mut bind) = state.info_addr(address, false) {
         let info = bind.into_account_info();
         let code = Code::from_account(&info)?.to_vec();
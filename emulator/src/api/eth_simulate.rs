@@ -0,0 +1,191 @@
+use {
+    super::fake,
+    crate::{
+        logs::Log, source::AccountSource, state::State, ContextAt, MachineEthCall, VmCall,
+    },
+    rome_evm::{
+        error::{Result, RomeProgramError::*},
+        origin::Origin,
+        tx::legacy::Legacy,
+        vm::Execute,
+        Diff, ExitReason, BLOCK_GAS_LIMIT, U256,
+    },
+    solana_program::{msg, pubkey::Pubkey},
+    std::sync::Arc,
+};
+
+/// The result of the call of the eth_simulateV1 request
+#[derive(Clone, Debug)]
+pub struct SimulatedCall {
+    pub exit_reason: ExitReason,
+    pub return_value: Vec<u8>,
+    pub gas_used: u64,
+    pub logs: Vec<Log>,
+}
+
+// executes the calls one by one, the changes of the succeeded and reverted calls are seen by the next ones.
+// the failed calls increment the nonce of the sender and pay the fee only.
+// validation: the nonce of the sender is checked, the balance must cover the value and the gas,
+// the fee of the gas used is charged from the sender.
+// the log_index of the logs is the position in the block of the simulated calls
+pub fn eth_simulate(
+    program_id: &Pubkey,
+    calls: Vec<Legacy>,
+    validation: bool,
+    source: Arc<dyn AccountSource>,
+) -> Result<Vec<SimulatedCall>> {
+    msg!("eth_simulateV1");
+    let chain = calls
+        .first()
+        .ok_or(Custom("eth_simulateV1: empty list of calls".to_string()))?
+        .chain_id;
+
+    if calls.iter().any(|call| call.chain_id != chain) {
+        return Err(Custom("eth_simulateV1: calls of the different chains".to_string()));
+    }
+
+    // the fake signer pays for the accounts created by the calls
+    let state = State::new(program_id, Some(fake::ID), source, chain.as_u64())?;
+    let context = ContextAt::new(&state);
+    let mut results = Vec::with_capacity(calls.len());
+    let mut log_index = 0;
+
+    for mut legacy in calls {
+        if legacy.gas_limit.is_zero() {
+            legacy.gas_limit = BLOCK_GAS_LIMIT.into();
+        }
+        if validation {
+            check_balance(&state, &legacy)?;
+        }
+        let (from, gas_price) = (legacy.from, legacy.gas_price);
+
+        let mut vm = VmCall::new(&state, legacy)?;
+        vm.check_nonce = validation;
+        vm.consume(MachineEthCall::Init)?;
+
+        let exit_reason = vm
+            .vm
+            .exit_reason
+            .ok_or(VmFault("exit_reason expected".to_string()))?;
+
+        // the journal of the failed call is reverted by the vm, the nonce is incremented
+        // by the vm unless the error is fatal
+        if exit_reason.is_fatal() {
+            vm.vm.inc_origin_nonce();
+        }
+
+        let gas_used = vm.vm.handler.gas.used;
+        if validation {
+            let fee = U256::from(gas_used)
+                .checked_mul(gas_price)
+                .ok_or(CalculationOverflow)?;
+            if !fee.is_zero() {
+                vm.vm.handler.journal.get_mut(&from).push(Diff::TransferFrom { balance: fee });
+            }
+        }
+
+        vm.vm.handler.alloc_slots_unchecked()?;
+        vm.vm.handler.commit(&context)?;
+
+        let mut logs = state.logs.logs();
+        logs.iter_mut().for_each(|log| log.log_index += log_index);
+        log_index += logs.iter().filter(|log| !log.removed).count() as u64;
+
+        results.push(SimulatedCall {
+            exit_reason,
+            return_value: vm.vm.return_value.take().unwrap_or_default(),
+            gas_used,
+            logs,
+        });
+    }
+
+    Ok(results)
+}
+
+fn check_balance(state: &State, legacy: &Legacy) -> Result<()> {
+    let required = legacy
+        .gas_limit
+        .checked_mul(legacy.gas_price)
+        .and_then(|fee| fee.checked_add(legacy.value))
+        .ok_or(CalculationOverflow)?;
+
+    let balance = state.balance(&legacy.from).unwrap_or_default();
+    if balance < required {
+        return Err(InsufficientFunds(legacy.from, required));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::eth_simulate,
+        crate::fixture::{legacy, Fixture},
+        rome_evm::{tx::legacy::Legacy, H160, U256},
+    };
+
+    // PUSH1 0 PUSH1 0 LOG0, twice
+    const LOG_TWICE: [u8; 10] = [0x60, 0x00, 0x60, 0x00, 0xa0, 0x60, 0x00, 0x60, 0x00, 0xa0];
+    const INVALID: [u8; 1] = [0xfe];
+    // LOG_TWICE, PUSH1 0 PUSH1 0 REVERT
+    const LOG_REVERT: [u8; 15] = [
+        0x60, 0x00, 0x60, 0x00, 0xa0, 0x60, 0x00, 0x60, 0x00, 0xa0, 0x60, 0x00, 0x60, 0x00, 0xfd,
+    ];
+
+    #[test]
+    fn failed_call_increments_nonce() {
+        let mut fixture = Fixture::new();
+        let from = H160::repeat_byte(1);
+        let to = H160::repeat_byte(2);
+        fixture.balance(&from, U256::exp10(18));
+        fixture.contract(&to, U256::zero(), &INVALID);
+
+        // the nonce of the second call is validated
+        let calls = vec![
+            legacy(from, to, vec![]),
+            Legacy {
+                nonce: 1,
+                ..legacy(from, to, vec![])
+            },
+        ];
+
+        let results = eth_simulate(&fixture.program_id, calls, true, fixture.source()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|call| call.exit_reason.is_error()));
+    }
+
+    #[test]
+    fn block_wide_log_index() {
+        let mut fixture = Fixture::new();
+        let from = H160::repeat_byte(1);
+        let to = H160::repeat_byte(2);
+        fixture.contract(&to, U256::zero(), &LOG_TWICE);
+
+        let calls = vec![legacy(from, to, vec![]), legacy(from, to, vec![])];
+        let results = eth_simulate(&fixture.program_id, calls, false, fixture.source()).unwrap();
+
+        let indexes = results
+            .iter()
+            .flat_map(|call| call.logs.iter().map(|log| log.log_index))
+            .collect::<Vec<_>>();
+        assert_eq!(indexes, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn removed_logs_not_counted() {
+        let mut fixture = Fixture::new();
+        let from = H160::repeat_byte(1);
+        let reverted = H160::repeat_byte(2);
+        let to = H160::repeat_byte(3);
+        fixture.contract(&reverted, U256::zero(), &LOG_REVERT);
+        fixture.contract(&to, U256::zero(), &LOG_TWICE);
+
+        let calls = vec![legacy(from, reverted, vec![]), legacy(from, to, vec![])];
+        let results = eth_simulate(&fixture.program_id, calls, false, fixture.source()).unwrap();
+
+        assert!(results[0].logs.iter().all(|log| log.removed));
+        let indexes = results[1].logs.iter().map(|log| log.log_index).collect::<Vec<_>>();
+        assert_eq!(indexes, vec![0, 1]);
+    }
+}
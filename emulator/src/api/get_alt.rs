@@ -1,10 +1,9 @@
 use {
-    crate::state::State,
+    crate::{source::AccountSource, state::State},
     rome_evm::{
         api::split_u64,
         error::{Result, RomeProgramError::*}, AltSlots, Data, origin::Origin,
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{
         account_info::IntoAccountInfo, msg, pubkey::Pubkey,
         address_lookup_table::instruction::derive_lookup_table_address,
@@ -27,12 +26,12 @@ pub fn get_alt<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Option<Pubkey>> {
     msg!("get_alt");
 
     let (holder, chain) = args(data)?;
-    let state = State::new(program_id, Some(*signer), client, chain)?;
+    let state = State::new(program_id, Some(*signer), source, chain)?;
 
     let mut bind = state.info_alt_slots(holder, false)?;
     let info = bind.into_account_info();
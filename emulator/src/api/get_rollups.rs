@@ -1,13 +1,16 @@
 use {
-    crate::state::State,
-    rome_evm::{accounts::OwnerInfo, error::Result, Data},
-    solana_client::rpc_client::RpcClient,
+    crate::{source::AccountSource, state::State},
+    rome_evm::{
+        accounts::{ChainConfig, OwnerInfo},
+        error::Result,
+        Data,
+    },
     solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey},
     std::sync::Arc,
 };
-pub fn get_rollups(program_id: &Pubkey, client: Arc<RpcClient>) -> Result<Vec<OwnerInfo>> {
+pub fn get_rollups(program_id: &Pubkey, source: Arc<dyn AccountSource>) -> Result<Vec<OwnerInfo>> {
     msg!("Get rollups");
-    let state = State::new_unchecked(program_id, None, client, 0)?;
+    let state = State::new_unchecked(program_id, None, source, 0)?;
 
     if let Ok(mut bind) = state.info_owner_reg(false) {
         let info = bind.into_account_info();
@@ -17,3 +20,21 @@ pub fn get_rollups(program_id: &Pubkey, client: Arc<RpcClient>) -> Result<Vec<Ow
 
     Ok(vec![])
 }
+
+// the key signing the privileged instructions of the rollup
+pub fn get_rollup_owner(program_id: &Pubkey, chain: u64, source: Arc<dyn AccountSource>) -> Result<Pubkey> {
+    msg!("Get rollup owner {}", chain);
+    let state = State::new_unchecked(program_id, None, source, chain)?;
+
+    let mut bind = state.info_owner_reg(false)?;
+    let info = bind.into_account_info();
+    OwnerInfo::authority(&info, chain)
+}
+
+// the defaults are returned if the config account is not created
+pub fn get_rollup_config(program_id: &Pubkey, chain: u64, source: Arc<dyn AccountSource>) -> Result<ChainConfig> {
+    msg!("Get rollup config {}", chain);
+    let state = State::new(program_id, None, source, chain)?;
+
+    Ok(state.config.clone())
+}
@@ -0,0 +1,90 @@
+use {
+    super::Emulation,
+    crate::{source::AccountSource, state::State},
+    rome_evm::{
+        api::migrate::{args, check, upgrade},
+        error::{Result, RomeProgramError::*},
+        CURRENT_VER, MIGRATIONS,
+    },
+    solana_program::{
+        account_info::{AccountInfo, IntoAccountInfo},
+        entrypoint::MAX_PERMITTED_DATA_INCREASE,
+        msg,
+        pubkey::Pubkey,
+    },
+    std::{slice, sync::Arc},
+};
+
+pub fn migrate<'a>(
+    program_id: &'a Pubkey,
+    data: &'a [u8],
+    signer: &'a Pubkey,
+    source: Arc<dyn AccountSource>,
+) -> Result<Emulation> {
+    let (chain, keys) = args(data)?;
+    msg!("Instruction: migrate {} accounts", keys.len());
+
+    let state = State::new(program_id, Some(*signer), source, chain)?;
+    let now = state.clock()?.unix_timestamp;
+
+    for key in keys {
+        let ver = {
+            let mut bind = state.info_external(&key, true)?;
+            let info = bind.into_account_info();
+            check(&info, program_id, state.config.lock_duration, now)?
+        };
+
+        let (_, account) = state.info_sys(&key)?;
+        let len = account.data.len();
+        // the runtime buffer of the account: the data can grow by MAX_PERMITTED_DATA_INCREASE
+        let mut buffer = account.data.clone();
+        buffer.resize(len + MAX_PERMITTED_DATA_INCREASE, 0);
+        let capacity = buffer.len();
+        let mut lamports = account.lamports;
+
+        let data = {
+            let info = AccountInfo::new(
+                &key,
+                false,
+                true,
+                &mut lamports,
+                &mut buffer[..len],
+                &account.owner,
+                account.executable,
+                account.rent_epoch,
+            );
+            // the rent and the space counters are tracked by the state
+            upgrade(&info, ver, CURRENT_VER, MIGRATIONS, |info, len| {
+                state.realloc(&key, len)?;
+                resize(info, len, capacity)
+            })?;
+            let data = info.data.borrow().to_vec();
+            data
+        };
+
+        let mut bind = state.info_sys(&key)?;
+        bind.1.data = data;
+        state.update(bind);
+        msg!("account {} layout version {} -> {}", key, ver, CURRENT_VER);
+    }
+
+    Emulation::without_vm(&state)
+}
+
+// resizes the data of the account info within the buffer as AccountInfo::realloc does in the runtime
+fn resize(info: &AccountInfo, len: usize, capacity: usize) -> Result<()> {
+    if len > capacity {
+        return Err(AllocationError(format!("account {} is resized to {} bytes", info.key, len)));
+    }
+
+    let mut data = info.data.borrow_mut();
+    let old = data.len();
+    let ptr = data.as_mut_ptr();
+    // the buffer of the info is at least capacity bytes long
+    *data = unsafe { slice::from_raw_parts_mut(ptr, len) };
+    if len > old {
+        data[old..].fill(0);
+    }
+
+    Ok(())
+}
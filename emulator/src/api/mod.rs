@@ -1,11 +1,14 @@
 mod confirm_tx_iterative;
+mod debug_trace;
 mod deposit;
 mod do_tx;
 pub mod do_tx_holder;
 pub mod do_tx_holder_iterative;
 pub mod do_tx_iterative;
 mod eth_call;
+mod eth_create_access_list;
 mod eth_estimate_gas;
+mod eth_simulate;
 mod eth_get_balance;
 mod eth_get_code;
 mod eth_get_storage_at;
@@ -16,20 +19,27 @@ mod transmit_tx;
 mod alt_alloc;
 mod alt_dealloc;
 mod get_alt;
+mod migrate;
+mod dereg_owner;
+mod set_owner_key;
+mod set_rollup_config;
 
 pub use confirm_tx_iterative::confirm_tx_iterative;
+pub use debug_trace::{debug_trace_call, debug_trace_tx};
 pub use deposit::deposit;
-pub use do_tx::do_tx;
+pub use do_tx::{do_tx, do_tx_hinted, do_tx_traced};
 pub use do_tx_holder::do_tx_holder;
 pub use do_tx_holder_iterative::do_tx_holder_iterative;
-pub use do_tx_iterative::do_tx_iterative;
-pub use eth_call::eth_call;
+pub use do_tx_iterative::{do_tx_iterative, do_tx_iterative_traced};
+pub use eth_call::{eth_call, eth_call_hinted, eth_call_overridden, eth_call_traced};
+pub use eth_create_access_list::eth_create_access_list;
 pub use eth_estimate_gas::eth_estimate_gas;
+pub use eth_simulate::{eth_simulate, SimulatedCall};
 pub use eth_get_balance::eth_get_balance;
 pub use eth_get_code::eth_get_code;
 pub use eth_get_storage_at::eth_get_storage_at;
 pub use eth_get_tx_count::eth_get_tx_count;
-pub use get_rollups::get_rollups;
+pub use get_rollups::{get_rollup_config, get_rollup_owner, get_rollups};
 pub use reg_owner::reg_owner;
 use solana_program::entrypoint::MAX_PERMITTED_DATA_INCREASE;
 use solana_program::instruction::AccountMeta;
@@ -37,18 +47,24 @@ pub use transmit_tx::transmit_tx;
 pub use alt_alloc::alt_alloc;
 pub use alt_dealloc::alt_dealloc;
 pub use get_alt::get_alt;
+pub use migrate::migrate;
+pub use dereg_owner::dereg_owner;
+pub use set_owner_key::set_owner_key;
+pub use set_rollup_config::set_rollup_config;
 
 use {
     crate::{
-        state::{Item, Slots, State}, context::ContextIt,
+        abi::decode_custom_error,
+        state::{Item, Slots, State}, context::ContextIt, logs::Log, tracer::Trace, struct_logger::StructLog,
     },
     rome_evm::{
         accounts::{AccountState, AccountType, Data},
         error::{Result, RomeProgramError::*},
-        ExitReason, H160, SIG_VERIFY_COST, NUMBER_OPCODES_PER_TX, StateHolder,
+        vm::RevertInfo,
+        ExitReason, H160, SIG_VERIFY_COST, StateHolder,
     },
     solana_program::{
-        account_info::IntoAccountInfo, msg, pubkey::Pubkey,
+        account_info::IntoAccountInfo, clock::Slot, msg, pubkey::Pubkey,
     },
     std::collections::BTreeMap,
 };
@@ -58,6 +74,8 @@ pub struct Vm {
     pub return_value: Option<Vec<u8>>,
     pub steps_executed: u64,
     pub iteration_count: u64,
+    pub gas_used: u64,
+    pub revert: Option<RevertInfo>,
 }
 
 pub struct Emulation {
@@ -72,6 +90,11 @@ pub struct Emulation {
     pub lock_overrides: Vec<u8>,
     pub syscalls: u64,
     pub is_atomic: bool,
+    pub trace: Option<Trace>,
+    pub struct_logs: Option<Vec<StructLog>>,
+    pub logs: Vec<Log>,
+    // the slot of the chain state the emulation is executed at, None for the sources without the slots
+    pub slot: Option<Slot>,
 }
 
 impl Emulation {
@@ -90,10 +113,11 @@ impl Emulation {
         syscalls: u64,
         lamports_fee: u64,
         lamports_refund: u64,
+        gas_used: u64,
         is_gas_estimate: bool,
         context: Option<&ContextIt>
     ) -> Result<Self> {
-        let is_atomic = steps_executed <= NUMBER_OPCODES_PER_TX
+        let is_atomic = steps_executed <= state.config.opcodes_per_iteration
             && alloc <= MAX_PERMITTED_DATA_INCREASE
             && syscalls < 64;
 
@@ -103,6 +127,7 @@ impl Emulation {
             is_gas_estimate,
             lamports_fee,
             lamports_refund,
+            gas_used,
             context,
         )?;
 
@@ -120,16 +145,27 @@ impl Emulation {
         msg!("syscalls: {}", syscalls);
         msg!("lamports_fee: {}", lamports_fee);
         msg!("lamports_refund: {}", lamports_refund);
+        msg!("gas_used: {}", gas_used);
         msg!("gas: {:?}", gas);
         msg!("is_atomic: {}", is_atomic);
+        msg!("slot: {:?}", state.slot());
 
         Emulation::log_accounts(state)?;
 
+        let exit_reason = exit_reason.ok_or(VmFault("exit_reason expected".to_string()))?;
+        let revert = if exit_reason.is_revert() {
+            Some(RevertInfo::decode(return_value.as_deref().unwrap_or_default()))
+        } else {
+            None
+        };
+
         let vm = Vm {
-            exit_reason: exit_reason.ok_or(VmFault("exit_reason expected".to_string()))?,
+            exit_reason,
             return_value,
             steps_executed,
             iteration_count: iter_count,
+            gas_used,
+            revert,
         };
 
         Ok(Self {
@@ -144,6 +180,10 @@ impl Emulation {
             lock_overrides,
             syscalls,
             is_atomic,
+            trace: state.tracer.as_ref().map(|tracer| tracer.trace(gas_used)),
+            struct_logs: state.struct_logger.as_ref().map(|logger| logger.logs()),
+            logs: state.logs.logs(),
+            slot: state.slot(),
         })
     }
 
@@ -217,6 +257,10 @@ impl Emulation {
             lock_overrides: vec![],
             syscalls: state.pda.syscall.count(),
             is_atomic: true,
+            trace: None,
+            struct_logs: None,
+            logs: vec![],
+            slot: state.slot(),
         })
     }
 
@@ -237,7 +281,16 @@ impl Emulation {
             .collect::<Result<Vec<_>>>()
     }
 
-    pub fn gas(state: &State, is_atomic: bool, is_gas_estimate: bool, fee: u64, refund: u64, context: Option<&ContextIt>) -> Result<u64> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn gas(
+        state: &State,
+        is_atomic: bool,
+        is_gas_estimate: bool,
+        fee: u64,
+        refund: u64,
+        gas_used: u64,
+        context: Option<&ContextIt>,
+    ) -> Result<u64> {
 
         let actual_fee = if is_gas_estimate {
             let context = context.unwrap();
@@ -258,7 +311,17 @@ impl Emulation {
 
         let gas = actual_fee.saturating_sub(refund);
 
-        Ok(21_000.max(gas))
+        // the tx gas_limit must cover both the lamports fee and the evm execution
+        Ok(21_000.max(gas).max(gas_used))
+    }
+
+    // resolves the custom error of the reverted transaction using the contract ABI
+    pub fn decode_revert(&mut self, abi: &str) -> Result<()> {
+        if let Some(revert) = self.vm.as_mut().and_then(|vm| vm.revert.as_mut()) {
+            decode_custom_error(abi, revert)?;
+        }
+
+        Ok(())
     }
 
     pub fn get_account_metas(&self) -> Vec<AccountMeta> {
@@ -1,12 +1,11 @@
 use {
     super::Emulation,
-    crate::state::State,
+    crate::{source::AccountSource, state::State},
     rome_evm::{
         accounts::OwnerInfo,
         api::reg_owner::{args, check, reg},
         error::Result,
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey},
     std::{mem::size_of, sync::Arc},
 };
@@ -14,12 +13,12 @@ pub fn reg_owner<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     let chain = args(data)?;
     msg!("Instruction: chain_id registration {}", chain);
 
-    let state = State::new_unchecked(program_id, Some(*signer), client, chain)?;
+    let state = State::new_unchecked(program_id, Some(*signer), source, chain)?;
 
     let (len, key) = {
         let mut bind = state.info_owner_reg(true)?;
@@ -33,7 +32,7 @@ pub fn reg_owner<'a>(
 
     let mut bind = state.info_owner_reg(false)?;
     let info = bind.into_account_info();
-    reg(&info, chain)?;
+    reg(&info, chain, state.clock()?.slot)?;
     state.update(bind);
 
     // create rome-evm SOL wallet
@@ -0,0 +1,29 @@
+use {
+    super::Emulation,
+    crate::{source::AccountSource, state::State},
+    rome_evm::{
+        api::set_owner_key::{args, check, set},
+        error::Result,
+    },
+    solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey},
+    std::sync::Arc,
+};
+pub fn set_owner_key<'a>(
+    program_id: &'a Pubkey,
+    data: &'a [u8],
+    signer: &'a Pubkey,
+    source: Arc<dyn AccountSource>,
+) -> Result<Emulation> {
+    let (chain, key) = args(data)?;
+    msg!("Instruction: set owner key of chain_id {}: {}", chain, key);
+
+    let state = State::new(program_id, Some(*signer), source, chain)?;
+
+    let mut bind = state.info_owner_reg(true)?;
+    let info = bind.into_account_info();
+    check(&info, signer, chain)?;
+    set(&info, chain, &key)?;
+    state.update(bind);
+
+    Emulation::without_vm(&state)
+}
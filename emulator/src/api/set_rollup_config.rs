@@ -0,0 +1,41 @@
+use {
+    super::Emulation,
+    crate::{source::AccountSource, state::State},
+    rome_evm::{
+        api::set_rollup_config::{args, check},
+        error::Result,
+    },
+    solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey},
+    std::sync::Arc,
+};
+pub fn set_rollup_config<'a>(
+    program_id: &'a Pubkey,
+    data: &'a [u8],
+    signer: &'a Pubkey,
+    source: Arc<dyn AccountSource>,
+) -> Result<Emulation> {
+    let (chain, config) = args(data)?;
+    msg!("Instruction: set rollup config of chain_id {}: {:?}", chain, config);
+
+    let state = State::new(program_id, Some(*signer), source, chain)?;
+    {
+        let mut bind = state.info_owner_reg(false)?;
+        let info = bind.into_account_info();
+        check(&info, signer, chain)?;
+    }
+
+    let (len, key) = {
+        let mut bind = state.info_rollup_config(true)?;
+        let info = bind.into_account_info();
+        (config.len(&info), bind.0)
+    };
+
+    state.realloc(&key, len)?;
+
+    let mut bind = state.info_rollup_config(true)?;
+    let info = bind.into_account_info();
+    config.store(&info)?;
+    state.update(bind);
+
+    Emulation::without_vm(&state)
+}
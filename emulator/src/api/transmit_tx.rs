@@ -1,8 +1,7 @@
 use {
     super::Emulation,
-    crate::state::State,
+    crate::{source::AccountSource, state::State},
     rome_evm::{api::transmit_tx::args, error::Result, Data, Holder, TxHolder},
-    solana_client::rpc_client::RpcClient,
     solana_program::{account_info::IntoAccountInfo, msg, pubkey::Pubkey,},
     std::sync::Arc,
 };
@@ -11,12 +10,12 @@ pub fn transmit_tx<'a>(
     program_id: &'a Pubkey,
     data: &'a [u8],
     signer: &'a Pubkey,
-    client: Arc<RpcClient>,
+    source: Arc<dyn AccountSource>,
 ) -> Result<Emulation> {
     msg!("Instruction: Transmit tx");
 
     let (holder, from, ix_hash, chain, tx) = args(data)?;
-    let state = State::new(program_id, Some(*signer), client, chain)?;
+    let state = State::new(program_id, Some(*signer), source, chain)?;
 
     // TODO: the client side should implement the holder filling with taking into account holder header allocation
     let len = from + tx.len();
@@ -20,13 +20,14 @@ impl<'a, 'b> ContextAt<'a, 'b> {
 
 impl AccountLock for ContextAt<'_, '_> {
     fn lock(&self) -> Result<()> {
+        let now = self.state.clock()?.unix_timestamp;
         let accounts = self.state.accounts.borrow();
         for (key, item) in accounts.iter() {
             let mut bind = (*key, item.account.clone());
             let mut info = bind.into_account_info();
             info.is_writable = item.account.writable;
 
-            lock_impl(&info, self.state.program_id)?;
+            lock_impl(&info, self.state.program_id, self.state.config.lock_duration, now)?;
         }
 
         Ok(())
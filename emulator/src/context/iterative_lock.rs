@@ -13,9 +13,10 @@ use {
 
 #[allow(dead_code)]
 pub fn add_ro_lock(state: &State, info: &AccountInfo, state_holder: &Bind) -> Result<()> {
+    let now = state.clock()?.unix_timestamp;
     let mut lock = Lock::from_account_mut(info)?;
 
-    let len = match lock.get()? {
+    let len = match lock.get(state.config.lock_duration, now)? {
         Some(LockType::Ro) => {
             // allocate ro-lock-info
             let mut bind = state.info_ro_lock(info.key, true)?;
@@ -45,22 +46,25 @@ pub fn add_ro_lock(state: &State, info: &AccountInfo, state_holder: &Bind) -> Re
         state.update(bind);
     }
     // add ro-lock
-    lock.ro_lock()
+    lock.ro_lock(now);
+    Ok(())
 }
 
-pub fn add_rw_lock(info: &AccountInfo, state_holder: &Bind) -> Result<()> {
+pub fn add_rw_lock(info: &AccountInfo, state_holder: &Bind, duration: i64, now: i64) -> Result<()> {
     let mut lock = Lock::from_account_mut(info)?;
 
-    if let Some(lock) = lock.get()? {
+    if let Some(lock) = lock.get(duration, now)? {
         return Err(AccountLocked(*info.key, Some(lock)));
     }
     // add rw-lock
-    lock.rw_lock(&state_holder.0)
+    lock.rw_lock(&state_holder.0, now);
+    Ok(())
 }
 
 pub fn iterative_lock(state: &State, holder: u64) -> Result<Vec<Pubkey>> {
     let state_holder = state.info_state_holder(holder, false)?;
     let lock_overrides = vec![];
+    let now = state.clock()?.unix_timestamp;
 
     let accounts = state.accounts.borrow().clone();
 
@@ -70,7 +74,7 @@ pub fn iterative_lock(state: &State, holder: u64) -> Result<Vec<Pubkey>> {
 
         if Lock::is_managed(&info, state.program_id)? {
             // TODO: enable ro-lock after the ALT is implemented
-            add_rw_lock(&info, &state_holder)?;
+            add_rw_lock(&info, &state_holder, state.config.lock_duration, now)?;
             // if item.writable {
             //     add_rw_lock(&info, &state_holder)?;
             // } else {
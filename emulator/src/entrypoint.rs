@@ -11,8 +11,8 @@ macro_rules! entrypoint {
                     },
                 },
                 $crate::api::Emulation,
+                $crate::source::{retry_on_slot_mismatch, AccountSource},
                 solana_program::{pubkey::Pubkey, msg},
-                solana_client::rpc_client::RpcClient,
                 std::sync::Arc,
             };
 
@@ -22,7 +22,7 @@ macro_rules! entrypoint {
                     use super::*;
 
                     #[inline(never)]
-                    pub fn execute<'a>(p: &'a Pubkey, d: &'a [u8], s: &'a Pubkey, c: Arc<RpcClient>) -> Result<Emulation> {
+                    pub fn execute<'a>(p: &'a Pubkey, d: &'a [u8], s: &'a Pubkey, c: Arc<dyn AccountSource>) -> Result<Emulation> {
                         $fn(p, d, s, c)
                     }
                 }
@@ -34,7 +34,7 @@ macro_rules! entrypoint {
                 $($row,)*
             }
 
-            pub fn dispatch<'a>(p: &'a Pubkey, d: &'a [u8], s: &'a Pubkey, c: Arc<RpcClient>) -> Result<Emulation> {
+            pub fn dispatch<'a>(p: &'a Pubkey, d: &'a [u8], s: &'a Pubkey, c: Arc<dyn AccountSource>) -> Result<Emulation> {
                 match d[0] {
                     $(
                         n if n == Instruction::$row as u8 => $row::execute(p, &d[1..], s, c),
@@ -46,9 +46,9 @@ macro_rules! entrypoint {
                 }
             }
 
-            pub fn emulate<'a>(p: &'a Pubkey, d: &'a [u8], s: &'a Pubkey, c: Arc<RpcClient>) -> Result<Emulation> {
+            pub fn emulate<'a>(p: &'a Pubkey, d: &'a [u8], s: &'a Pubkey, c: Arc<dyn AccountSource>) -> Result<Emulation> {
                 msg!(">> emulator started ..");
-                let res = dispatch(p, d, s, c)?;
+                let res = retry_on_slot_mismatch(|| dispatch(p, d, s, Arc::clone(&c)))?;
                 msg!(">> emulator finished");
                 Ok(res)
             }
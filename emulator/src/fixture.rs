@@ -0,0 +1,310 @@
+use {
+    crate::source::{AccountDump, AccountSource},
+    rome_evm::{
+        error::Result,
+        state::{aux::Account, pda::Pda},
+        tx::{
+            eip7702::{Authorization, AuthorizationList},
+            legacy::Legacy,
+        },
+        AccountState, AccountType, Data, EvmValids, OwnerInfo, Slot, Storage, CURRENT_VER, H160, U256,
+    },
+    rlp::RlpStream,
+    solana_program::{
+        account_info::AccountInfo,
+        address_lookup_table,
+        clock::{Clock, Slot},
+        keccak,
+        native_token::LAMPORTS_PER_SOL,
+        pubkey::Pubkey,
+        rent::Rent,
+        slot_hashes::SlotHashes,
+        system_program, sysvar,
+    },
+    std::{mem::size_of, sync::Arc},
+};
+
+pub const CHAIN: u64 = 1001;
+pub const SLOT: Slot = 100;
+pub const TIMESTAMP: i64 = 1_700_000_000;
+
+// PUSH1 0x2a PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN: returns 42
+pub const RETURN_42: [u8; 10] = [0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+
+/// The accounts of the chain registered in rome-evm, the emulator runs offline on them
+pub struct Fixture {
+    pub program_id: Pubkey,
+    pub signer: Pubkey,
+    pub dump: AccountDump,
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        let mut fixture = Self {
+            program_id: Pubkey::new_unique(),
+            signer: Pubkey::new_unique(),
+            dump: AccountDump::new(),
+        };
+
+        fixture.set_clock(SLOT, TIMESTAMP);
+        let rent = bincode::serialize(&Rent::default()).unwrap();
+        fixture.dump.insert(sysvar::rent::ID, sysvar_account(rent));
+        let slot_hashes = bincode::serialize(&SlotHashes::new(&[])).unwrap();
+        fixture.dump.insert(sysvar::slot_hashes::ID, sysvar_account(slot_hashes));
+        fixture.dump.insert(system_program::ID, Account::new_executable());
+        fixture.dump.insert(address_lookup_table::program::ID, Account::new_executable());
+        fixture.dump.insert(fixture.signer, wallet(1_000 * LAMPORTS_PER_SOL));
+
+        let (key, _) = fixture.pda().owner_info_key();
+        let account = fixture.owner_info();
+        fixture.dump.insert(key, account);
+
+        fixture
+    }
+
+    pub fn source(&self) -> Arc<dyn AccountSource> {
+        Arc::new(self.dump.clone())
+    }
+
+    pub fn pda(&self) -> Pda<'_> {
+        Pda::new_(&self.program_id, CHAIN)
+    }
+
+    pub fn set_clock(&mut self, slot: Slot, unix_timestamp: i64) {
+        let clock = Clock {
+            slot,
+            unix_timestamp,
+            ..Clock::default()
+        };
+        let clock = bincode::serialize(&clock).unwrap();
+        self.dump.insert(sysvar::clock::ID, sysvar_account(clock));
+    }
+
+    pub fn balance(&mut self, address: &H160, balance: U256) -> Pubkey {
+        self.contract(address, balance, &[])
+    }
+
+    // the balance account of the address, the account is the contract if the code is not empty
+    pub fn contract(&mut self, address: &H160, balance: U256, code: &[u8]) -> Pubkey {
+        let (key, _) = self.pda().balance_key(address);
+        let account = self.balance_account(balance, code);
+        self.dump.insert(key, account);
+        key
+    }
+
+    // the value of the storage slot of the contract
+    pub fn storage(&mut self, address: &H160, slot: &U256, value: &U256) -> Pubkey {
+        let (base, _) = self.pda().balance_key(address);
+        let (index_be, sub_ix) = Pda::storage_index(slot);
+        let (key, _) = self.pda().storage_key(&base, index_be);
+
+        let mut data = match self.dump.accounts.get(&key) {
+            Some(account) => account.data.clone(),
+            None => {
+                let len = self.with_info(&mut [], |info| Ok(Storage::offset(info) + Storage::size(info)));
+                let mut data = vec![0; len];
+                self.with_info(&mut data, |info| Storage::init(info));
+                data
+            }
+        };
+        data.extend(vec![0; size_of::<Slot>()]);
+        self.with_info(&mut data, |info| Storage::set(info, value, sub_ix));
+
+        self.dump.insert(key, self.pda_account(data));
+        key
+    }
+
+    pub fn balance_account(&self, balance: U256, code: &[u8]) -> Account {
+        let len = self.with_info(&mut [], |info| {
+            Ok(AccountState::offset(info) + AccountState::size(info))
+        });
+
+        let mut data = vec![0; len];
+        self.with_info(&mut data, |info| {
+            AccountState::init(info)?;
+            let mut state = AccountState::from_account_mut(info)?;
+            state.balance = balance;
+            state.is_contract = !code.is_empty();
+            Ok(())
+        });
+        data.extend(code);
+        data.extend(EvmValids::compute(code));
+
+        self.pda_account(data)
+    }
+
+    fn owner_info(&self) -> Account {
+        let mut data = vec![0; 2 + size_of::<OwnerInfo>()];
+        data[0] = AccountType::OwnerInfo as u8;
+        data[1] = CURRENT_VER;
+
+        self.with_info(&mut data, |info| {
+            let mut owner_info = OwnerInfo::from_account_mut(info)?;
+            owner_info[0].chain = CHAIN;
+            owner_info[0].slot = SLOT;
+            Ok(())
+        });
+
+        self.pda_account(data)
+    }
+
+    fn pda_account(&self, data: Vec<u8>) -> Account {
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: self.program_id,
+            executable: false,
+            rent_epoch: 0,
+            writable: false,
+        }
+    }
+
+    // the layouts are written by the program
+    fn with_info<T>(&self, data: &mut [u8], f: impl FnOnce(&AccountInfo) -> Result<T>) -> T {
+        let key = Pubkey::default();
+        let mut lamports = 0;
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, data, &self.program_id, false, 0,
+        );
+        f(&info).unwrap()
+    }
+}
+
+pub fn wallet(lamports: u64) -> Account {
+    Account {
+        lamports,
+        data: vec![],
+        owner: system_program::ID,
+        executable: false,
+        rent_epoch: 0,
+        writable: false,
+    }
+}
+
+// the bincode serialized sysvar
+pub fn sysvar_account(data: Vec<u8>) -> Account {
+    Account {
+        lamports: 1,
+        data,
+        owner: sysvar::ID,
+        executable: false,
+        rent_epoch: 0,
+        writable: false,
+    }
+}
+
+// the unsigned transaction of eth_call
+pub fn legacy(from: H160, to: H160, data: Vec<u8>) -> Legacy {
+    Legacy {
+        to: Some(to),
+        data: Some(data),
+        chain_id: CHAIN.into(),
+        from,
+        ..Legacy::default()
+    }
+}
+
+// the address of the secp256k1 secret key
+pub fn address(secret: &[u8; 32]) -> H160 {
+    let secret = libsecp256k1::SecretKey::parse(secret).unwrap();
+    let public = libsecp256k1::PublicKey::from_secret_key(&secret).serialize();
+    H160::from_slice(&keccak::hash(&public[1..]).to_bytes()[12..])
+}
+
+// the rlp of the EIP-155 signed legacy transaction
+pub fn sign(legacy: &Legacy, secret: &[u8; 32]) -> Vec<u8> {
+    let fields = |stream: &mut RlpStream| {
+        stream.append(&legacy.nonce);
+        stream.append(&legacy.gas_price);
+        stream.append(&legacy.gas_limit);
+        match legacy.to.as_ref() {
+            Some(to) => stream.append(&to.as_bytes().to_vec()),
+            None => stream.append_empty_data(),
+        };
+        stream.append(&legacy.value);
+        stream.append(&legacy.data.clone().unwrap_or_default());
+    };
+
+    let mut unsigned = RlpStream::new_list(9);
+    fields(&mut unsigned);
+    unsigned.append(&legacy.chain_id);
+    unsigned.append_empty_data();
+    unsigned.append_empty_data();
+    let hash = keccak::hash(&unsigned.out()).to_bytes();
+    let (recovery_id, r, s) = signature(&hash, secret);
+
+    let mut signed = RlpStream::new_list(9);
+    fields(&mut signed);
+    let v = legacy.chain_id * U256::from(2) + U256::from(35 + recovery_id);
+    signed.append(&v);
+    signed.append(&r);
+    signed.append(&s);
+    signed.out().to_vec()
+}
+
+// the EIP-7702 authorization signed by the authority
+pub fn authorization(address: H160, nonce: u64, secret: &[u8; 32]) -> Authorization {
+    let mut auth = Authorization {
+        chain_id: CHAIN.into(),
+        address,
+        nonce,
+        y_parity: 0,
+        r: U256::zero(),
+        s: U256::zero(),
+    };
+    let (y_parity, r, s) = signature(auth.hash_unsign().as_fixed_bytes(), secret);
+    auth.y_parity = y_parity;
+    auth.r = r;
+    auth.s = s;
+    auth
+}
+
+// the rlp of the EIP-7702 set-code transaction, the gas price of the legacy is used as both fees
+pub fn sign_set_code(legacy: &Legacy, list: Vec<Authorization>, secret: &[u8; 32]) -> Vec<u8> {
+    let list = AuthorizationList(list);
+    let fields = |stream: &mut RlpStream| {
+        stream.append(&legacy.chain_id);
+        stream.append(&legacy.nonce);
+        stream.append(&legacy.gas_price);
+        stream.append(&legacy.gas_price);
+        stream.append(&legacy.gas_limit);
+        stream.append(&legacy.to.unwrap().as_bytes().to_vec());
+        stream.append(&legacy.value);
+        stream.append(&legacy.data.clone().unwrap_or_default());
+        stream.begin_list(0);
+        stream.append(&list);
+    };
+
+    let mut unsigned = RlpStream::new_list(10);
+    fields(&mut unsigned);
+    let hash = keccak::hashv(&[&[4], &unsigned.out()]).to_bytes();
+    let (y_parity, r, s) = signature(&hash, secret);
+
+    let mut signed = RlpStream::new_list(13);
+    fields(&mut signed);
+    signed.append(&y_parity);
+    signed.append(&r);
+    signed.append(&s);
+
+    let mut rlp = vec![4];
+    rlp.extend(signed.out());
+    rlp
+}
+
+fn signature(hash: &[u8; 32], secret: &[u8; 32]) -> (u8, U256, U256) {
+    let secret = libsecp256k1::SecretKey::parse(secret).unwrap();
+    let message = libsecp256k1::Message::parse(hash);
+    let (signature, recovery_id) = libsecp256k1::sign(&message, &secret);
+    let rs = signature.serialize();
+    (
+        recovery_id.serialize(),
+        U256::from_big_endian(&rs[..32]),
+        U256::from_big_endian(&rs[32..]),
+    )
+}
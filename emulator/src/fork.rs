@@ -0,0 +1,236 @@
+use {
+    crate::{
+        api::fake,
+        source::{AccountSource, SYSVARS},
+        Emulation,
+    },
+    rome_evm::{error::Result, state::aux::Account},
+    solana_program::pubkey::Pubkey,
+    std::{collections::BTreeMap, sync::{Arc, RwLock}},
+};
+
+/// The local copy of the chain state. The accounts are loaded from the source once,
+/// the results of the emulations are applied on top of them and seen by the next requests.
+/// The sysvars are not cached, the clock of the fork follows the source.
+/// Nothing is sent to Solana.
+pub struct Fork {
+    source: Arc<dyn AccountSource>,
+    // None: the account does not exist
+    overlay: RwLock<BTreeMap<Pubkey, Option<Account>>>,
+}
+
+impl Fork {
+    pub fn new(source: Arc<dyn AccountSource>) -> Arc<Self> {
+        Arc::new(Self {
+            source,
+            overlay: RwLock::new(BTreeMap::new()),
+        })
+    }
+
+    // applies the writable accounts of the emulation to the overlay
+    pub fn commit(&self, emulation: &Emulation) {
+        let mut overlay = self.overlay.write().unwrap();
+
+        for (key, item) in emulation.accounts.iter() {
+            // the fake signer is used by eth_estimateGas only
+            if !item.account.writable || *key == fake::ID {
+                continue;
+            }
+
+            let account = if item.account.lamports == 0 {
+                None
+            } else {
+                Some(Account {
+                    writable: false,
+                    ..item.account.clone()
+                })
+            };
+            overlay.insert(*key, account);
+        }
+    }
+
+    // drops the local changes, the accounts will be reloaded from the source
+    pub fn reset(&self) {
+        self.overlay.write().unwrap().clear();
+    }
+
+    pub fn overlay(&self) -> BTreeMap<Pubkey, Option<Account>> {
+        self.overlay.read().unwrap().clone()
+    }
+}
+
+impl AccountSource for Fork {
+    fn account(&self, key: &Pubkey) -> Result<Option<Account>> {
+        let mut accounts = self.accounts(std::slice::from_ref(key))?;
+        Ok(accounts.pop().flatten())
+    }
+
+    fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let missing = {
+            let overlay = self.overlay.read().unwrap();
+            keys.iter()
+                .filter(|key| SYSVARS.contains(key) || !overlay.contains_key(key))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let mut sysvars = BTreeMap::new();
+        if !missing.is_empty() {
+            let accounts = self.source.accounts(&missing)?;
+            let mut overlay = self.overlay.write().unwrap();
+
+            for (key, account) in missing.into_iter().zip(accounts) {
+                if SYSVARS.contains(&key) {
+                    sysvars.insert(key, account);
+                } else {
+                    overlay.entry(key).or_insert(account);
+                }
+            }
+        }
+
+        let overlay = self.overlay.read().unwrap();
+        let accounts = keys
+            .iter()
+            .map(|key| sysvars.get(key).or_else(|| overlay.get(key)).cloned().flatten())
+            .collect();
+
+        Ok(accounts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::Fork,
+        crate::{
+            api::{eth_call, Emulation},
+            fixture::{legacy, wallet, Fixture, RETURN_42, SLOT},
+            source::{AccountDump, AccountSource},
+            state::Item,
+        },
+        rome_evm::{state::aux::Account, H160, U256},
+        solana_program::pubkey::Pubkey,
+        std::{
+            collections::BTreeMap,
+            sync::{Arc, RwLock},
+        },
+    };
+
+    // the chain state changing between the requests
+    struct Chain(RwLock<AccountDump>);
+
+    impl AccountSource for Chain {
+        fn account(&self, key: &Pubkey) -> rome_evm::error::Result<Option<Account>> {
+            self.0.read().unwrap().account(key)
+        }
+    }
+
+    const H256_42: [u8; 32] = {
+        let mut word = [0_u8; 32];
+        word[31] = 42;
+        word
+    };
+
+    fn emulation(accounts: Vec<(Pubkey, Account, bool)>) -> Emulation {
+        let accounts = accounts
+            .into_iter()
+            .map(|(key, account, writable)| {
+                let item = Item {
+                    account: Account { writable, ..account },
+                    signer: false,
+                    address: None,
+                };
+                (key, item)
+            })
+            .collect();
+
+        Emulation {
+            accounts,
+            storage: BTreeMap::new(),
+            vm: None,
+            alloc: 0,
+            dealloc: 0,
+            alloc_payed: 0,
+            dealloc_payed: 0,
+            gas: 0,
+            lock_overrides: vec![],
+            syscalls: 0,
+            is_atomic: true,
+            trace: None,
+            struct_logs: None,
+            logs: vec![],
+            slot: None,
+        }
+    }
+
+    #[test]
+    fn commit() {
+        let fixture = Fixture::new();
+        let fork = Fork::new(fixture.source());
+        let new = Pubkey::new_unique();
+        let read_only = Pubkey::new_unique();
+
+        fork.commit(&emulation(vec![(new, wallet(5), true), (read_only, wallet(7), false)]));
+
+        let account = fork.account(&new).unwrap().unwrap();
+        assert_eq!(account.lamports, 5);
+        assert!(!account.writable);
+        // the read-only accounts of the emulation are not applied
+        assert!(fork.account(&read_only).unwrap().is_none());
+        assert!(fork.overlay().contains_key(&new));
+    }
+
+    #[test]
+    fn deleted_account() {
+        let fixture = Fixture::new();
+        let fork = Fork::new(fixture.source());
+
+        assert!(fork.account(&fixture.signer).unwrap().is_some());
+        fork.commit(&emulation(vec![(fixture.signer, wallet(0), true)]));
+        assert!(fork.account(&fixture.signer).unwrap().is_none());
+        assert!(matches!(fork.overlay().get(&fixture.signer), Some(None)));
+
+        // the accounts are reloaded from the source
+        fork.reset();
+        assert!(fork.overlay().is_empty());
+        assert_eq!(fork.account(&fixture.signer).unwrap().unwrap().lamports, 1_000_000_000_000);
+    }
+
+    #[test]
+    fn deploy_then_call() {
+        let fixture = Fixture::new();
+        let contract = H160::repeat_byte(0xcc);
+        let (key, _) = fixture.pda().balance_key(&contract);
+        let account = fixture.balance_account(U256::zero(), &RETURN_42);
+        let fork = Fork::new(fixture.source());
+
+        // the contract is deployed by the previous emulation
+        fork.commit(&emulation(vec![(key, account, true)]));
+
+        let call = legacy(H160::repeat_byte(1), contract, vec![]);
+        let emulation = eth_call(&fixture.program_id, call, fork.clone()).unwrap();
+        let vm = emulation.vm.unwrap();
+        assert!(vm.exit_reason.is_succeed());
+        assert_eq!(vm.return_value.unwrap(), H256_42.to_vec());
+
+        // the source does not know the contract
+        fork.reset();
+        let call = legacy(H160::repeat_byte(1), contract, vec![]);
+        let emulation = eth_call(&fixture.program_id, call, fork).unwrap();
+        assert!(emulation.vm.unwrap().return_value.unwrap_or_default().is_empty());
+    }
+
+    #[test]
+    fn sysvars_follow_source() {
+        let mut fixture = Fixture::new();
+        let chain = Arc::new(Chain(RwLock::new(fixture.dump.clone())));
+        let fork = Fork::new(chain.clone());
+
+        assert_eq!(fork.sysvars().unwrap().clock.slot, SLOT);
+
+        fixture.set_clock(SLOT + 10, 0);
+        *chain.0.write().unwrap() = fixture.dump.clone();
+        assert_eq!(fork.sysvars().unwrap().clock.slot, SLOT + 10);
+        assert!(!fork.overlay().keys().any(|key| super::SYSVARS.contains(key)));
+    }
+}
@@ -1,18 +1,34 @@
 // pub mod emulator;
+pub mod abi;
 mod allocate;
 #[allow(clippy::let_and_return)]
 pub mod api;
 mod context;
 pub mod entrypoint;
+#[cfg(any(test, feature = "fixture"))]
+pub mod fixture;
+pub mod fork;
+pub mod logs;
 mod origin;
+pub mod overrides;
+pub mod planner;
+pub mod source;
 mod state;
-mod stubs;
+pub mod struct_logger;
+mod sysvars;
+pub mod tracer;
 mod vm_eth_call;
 mod alt_program;
 
 pub use api::*;
 pub use context::*;
+pub use fork::Fork;
+pub use logs::Log;
+pub use overrides::{AccountOverride, BlockOverrides, InvokeHints, StateOverride};
+pub use planner::{plan_tx, Plan, PlanConfig, Planner};
+pub use source::{retry_on_slot_mismatch, AccountDump, AccountSource, Pinned};
 pub use state::{Bind, Item};
+pub use sysvars::Sysvars;
 pub use vm_eth_call::*;
 
 entrypoint! {
@@ -25,4 +41,8 @@ entrypoint! {
     RegOwner => reg_owner,
     AltAlloc => alt_alloc,
     AltDealloc => alt_dealloc,
+    Migrate => migrate,
+    DeregOwner => dereg_owner,
+    SetOwnerKey => set_owner_key,
+    SetRollupConfig => set_rollup_config,
 }
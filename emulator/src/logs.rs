@@ -0,0 +1,140 @@
+use {
+    rome_evm::{tracer::LogTracer, H160, H256},
+    std::cell::RefCell,
+};
+
+/// The Ethereum log emitted by the LOG0..LOG4 opcodes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Log {
+    pub address: H160,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+    // the position of the log among the non-removed logs of the transaction,
+    // the removed log shares the index with the next non-removed log
+    pub log_index: u64,
+    // the log is emitted by the reverted call, it is not stored on-chain
+    pub removed: bool,
+}
+
+/// Collects the logs in the order of the execution, the logs of the failed frames are marked as removed
+#[derive(Default)]
+pub struct LogCollector {
+    logs: RefCell<Vec<Log>>,
+    // the number of the logs at the beginning of the frame
+    frames: RefCell<Vec<usize>>,
+}
+
+impl LogCollector {
+    pub fn logs(&self) -> Vec<Log> {
+        let mut log_index = 0;
+        let mut logs = self.logs.borrow().clone();
+        for log in logs.iter_mut() {
+            log.log_index = log_index;
+            if !log.removed {
+                log_index += 1;
+            }
+        }
+        logs
+    }
+}
+
+impl LogTracer for LogCollector {
+    fn reset(&self) {
+        self.logs.borrow_mut().clear();
+        self.frames.borrow_mut().clear();
+    }
+
+    fn enter(&self) {
+        let len = self.logs.borrow().len();
+        self.frames.borrow_mut().push(len);
+    }
+
+    fn log(&self, address: H160, topics: &[H256], data: &[u8]) {
+        // the log_index is assigned once the frames are exited
+        self.logs.borrow_mut().push(Log {
+            address,
+            topics: topics.to_vec(),
+            data: data.to_vec(),
+            log_index: 0,
+            removed: false,
+        });
+    }
+
+    fn exit(&self, succeed: bool) {
+        let start = self.frames.borrow_mut().pop().unwrap_or_default();
+        if !succeed {
+            self.logs
+                .borrow_mut()
+                .iter_mut()
+                .skip(start)
+                .for_each(|log| log.removed = true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::LogCollector,
+        rome_evm::{tracer::LogTracer, H160, H256},
+    };
+
+    fn log(collector: &LogCollector, n: u8) {
+        collector.log(H160::repeat_byte(n), &[H256::repeat_byte(n)], &[n]);
+    }
+
+    #[test]
+    fn order() {
+        let collector = LogCollector::default();
+        collector.enter();
+        log(&collector, 1);
+        collector.enter();
+        log(&collector, 2);
+        collector.exit(true);
+        log(&collector, 3);
+        collector.exit(true);
+
+        let logs = collector.logs();
+        let addresses = logs.iter().map(|x| x.address).collect::<Vec<_>>();
+        assert_eq!(addresses, (1..=3).map(H160::repeat_byte).collect::<Vec<_>>());
+        assert_eq!(logs.iter().map(|x| x.log_index).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(logs.iter().all(|x| !x.removed));
+    }
+
+    #[test]
+    fn reverted_inner_call() {
+        let collector = LogCollector::default();
+        collector.enter();
+        log(&collector, 1);
+        collector.enter();
+        log(&collector, 2);
+        collector.enter();
+        log(&collector, 3);
+        collector.exit(true);
+        // the revert of the frame removes the logs of the nested frames
+        collector.exit(false);
+        log(&collector, 4);
+        collector.exit(true);
+
+        let logs = collector.logs();
+        assert_eq!(logs.iter().map(|x| x.removed).collect::<Vec<_>>(), vec![false, true, true, false]);
+    }
+
+    #[test]
+    fn index_after_revert() {
+        let collector = LogCollector::default();
+        collector.enter();
+        log(&collector, 1);
+        collector.enter();
+        log(&collector, 2);
+        log(&collector, 3);
+        collector.exit(false);
+        log(&collector, 4);
+        collector.exit(true);
+
+        let logs = collector.logs();
+        assert_eq!(logs.iter().map(|x| x.log_index).collect::<Vec<_>>(), vec![0, 1, 1, 1]);
+        assert_eq!(logs[3].address, H160::repeat_byte(4));
+        assert_eq!(logs[3].log_index, 1);
+    }
+}
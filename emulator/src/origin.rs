@@ -5,8 +5,9 @@ use {
         error::{Result, RomeProgramError::*},
         info::Info,
         origin::Origin,
-        Base, Code, Data, Account, H160, H256, U256, pda::Seed,
-        non_evm::{ASplToken, Program, SplToken, System, Bind as Bind_,
+        AccountState, Base, Code, Data, Account, EvmValids, H160, H256, U256, pda::Seed,
+        tracer::{LogTracer, StepTracer, Tracer},
+        non_evm::{ASplToken, Invoke, Program, SplToken, System, Bind as Bind_,
                   non_evm_state::filter_accounts},
     },
     solana_program::{
@@ -23,26 +24,46 @@ impl Info for State<'_> {}
 
 impl Origin for State<'_> {
     fn nonce(&self, address: &H160) -> Result<u64> {
+        if let Some(nonce) = self.overrides.get(address).and_then(|x| x.nonce) {
+            return Ok(nonce);
+        }
+
         let mut bind = self.info_addr(address, false)?;
         let info = bind.into_account_info();
         Info::nonce(self, &info)
     }
     fn balance(&self, address: &H160) -> Result<U256> {
+        if let Some(balance) = self.overrides.get(address).and_then(|x| x.balance) {
+            return Ok(balance);
+        }
+
         let mut bind = self.info_addr(address, false)?;
         let info = bind.into_account_info();
         Info::balance(self, &info)
     }
     fn code(&self, address: &H160) -> Result<Vec<u8>> {
+        if let Some(code) = self.overrides.get(address).and_then(|x| x.code.clone()) {
+            return Ok(code);
+        }
+
         let mut bind = self.info_addr(address, false)?;
         let info = bind.into_account_info();
         Info::code(self, &info)
     }
     fn valids(&self, address: &H160) -> Result<Vec<u8>> {
+        if let Some(code) = self.overrides.get(address).and_then(|x| x.code.as_ref()) {
+            return Ok(EvmValids::compute(code));
+        }
+
         let mut bind = self.info_addr(address, false)?;
         let info = bind.into_account_info();
         Info::valids(self, &info)
     }
     fn storage(&self, address: &H160, slot: &U256) -> Result<Option<U256>> {
+        if let Some(value) = self.overrides.get(address).and_then(|x| x.storage(slot)) {
+            return Ok(Some(value));
+        }
+
         let (mut bind, sub_ix) = self.info_slot(address, slot, false)?;
         let info = bind.into_account_info();
         Info::storage(self, &info, sub_ix)
@@ -88,13 +109,21 @@ impl Origin for State<'_> {
         let mut bind = self.info_addr(address, true)?;
 
         let len = bind.1.data.len();
-        let offset = Code::offset(&bind.into_account_info());
+        let (offset, is_delegated) = {
+            let info = bind.into_account_info();
+            AccountState::check_set_code(&info, address, code)?;
+            (Code::offset(&info), AccountState::is_delegated(&info)?)
+        };
         let required = offset + code.len() + valids.len();
 
         match len.cmp(&required) {
             Less => {
                 self.realloc(&bind.0, required)?;
             }
+            // EIP-7702: the delegation designator is cleared
+            Greater if is_delegated => {
+                self.realloc(&bind.0, required)?;
+            }
             Greater => {
                 // TODO: implement deallocation of the unused contract space
                 return Err(Unimplemented(
@@ -158,7 +187,8 @@ impl Origin for State<'_> {
         let mut binds = filter(&mut accs, ix)?;
         let len_old = data_len(&binds);
 
-        let program = non_evm_program(ix, self)?;
+        let invoked = self.invoked.borrow().contains(&ix.program_id);
+        let program = non_evm_program(ix, self, invoked)?;
 
         if refund_to_signer {
             program.emulate(ix, &mut binds)?;
@@ -187,10 +217,26 @@ impl Origin for State<'_> {
     fn signer(&self) -> Pubkey {
         self.signer.unwrap()
     }
+    fn invoke_hint(&self, program_id: &Pubkey, key: &Pubkey) -> Option<Account> {
+        self.invoke_hints.get(program_id).and_then(|accounts| accounts.get(key)).cloned()
+    }
+    fn track_invoke(&self, program_id: &Pubkey) {
+        self.invoked.borrow_mut().insert(*program_id);
+    }
+    fn tracer(&self) -> Option<&dyn Tracer> {
+        self.tracer.as_ref().map(|tracer| tracer as &dyn Tracer)
+    }
+    fn step_tracer(&self) -> Option<&dyn StepTracer> {
+        self.struct_logger.as_ref().map(|logger| logger as &dyn StepTracer)
+    }
+    fn log_tracer(&self) -> Option<&dyn LogTracer> {
+        Some(&self.logs)
+    }
 }
 
 
-fn non_evm_program<'a, T: Origin>(ix: &Instruction, state: &'a T) -> Result<Box<dyn Program + 'a>> {
+// invoked: the instruction is created by the generic CPI precompile
+fn non_evm_program<'a, T: Origin>(ix: &Instruction, state: &'a T, invoked: bool) -> Result<Box<dyn Program + 'a>> {
     use solana_program::system_program;
 
     let box_: Box<dyn Program + 'a> = match ix.program_id {
@@ -198,6 +244,8 @@ fn non_evm_program<'a, T: Origin>(ix: &Instruction, state: &'a T) -> Result<Box<
         spl_associated_token_account::ID => Box::new(ASplToken::new(state)),
         system_program::ID => Box::new(System::new(state)),
         address_lookup_table::program::ID => Box::new(AltProgram{}),
+        // the generic CPI, the effects are declared by the invoke hints
+        _ if invoked => Box::new(Invoke::new(state)),
         _ => return Err(Unimplemented(format!("non-evm program is not supported : {:?}", ix.program_id)))
     };
     
@@ -0,0 +1,76 @@
+use {
+    crate::state::State,
+    rome_evm::{
+        error::{Result, RomeProgramError::*},
+        state::{aux::Account, JournaledState},
+        H160, U256,
+    },
+    solana_program::pubkey::Pubkey,
+    std::collections::BTreeMap,
+};
+
+/// geth-style override of the account, applied on top of the loaded state
+#[derive(Clone, Debug, Default)]
+pub struct AccountOverride {
+    pub balance: Option<U256>,
+    pub nonce: Option<u64>,
+    pub code: Option<Vec<u8>>,
+    // replaces the whole storage of the account
+    pub state: Option<BTreeMap<U256, U256>>,
+    // replaces the listed slots only
+    pub state_diff: Option<BTreeMap<U256, U256>>,
+}
+
+pub type StateOverride = BTreeMap<H160, AccountOverride>;
+
+/// The declared effect of the generic CPI: the emulator does not execute the invoked program,
+/// the post-state of the accounts is provided by the caller. program_id => key => account
+pub type InvokeHints = BTreeMap<Pubkey, BTreeMap<Pubkey, Account>>;
+
+#[derive(Clone, Debug, Default)]
+pub struct BlockOverrides {
+    pub number: Option<U256>,
+    pub time: Option<U256>,
+    pub coinbase: Option<H160>,
+    pub chain_id: Option<u64>,
+}
+
+impl AccountOverride {
+    pub fn storage(&self, slot: &U256) -> Option<U256> {
+        if let Some(state) = self.state.as_ref() {
+            return Some(state.get(slot).cloned().unwrap_or_default());
+        }
+
+        self.state_diff.as_ref().and_then(|diff| diff.get(slot).cloned())
+    }
+}
+
+pub fn check_overrides(overrides: &StateOverride) -> Result<()> {
+    for (address, item) in overrides.iter() {
+        if item.state.is_some() && item.state_diff.is_some() {
+            return Err(Custom(format!(
+                "account {} has both state and stateDiff overrides",
+                address
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+impl BlockOverrides {
+    pub fn apply(&self, handler: &mut JournaledState<State>) {
+        if let Some(number) = self.number {
+            handler.block_number = number;
+        }
+        if let Some(time) = self.time {
+            handler.block_timestamp = time;
+        }
+        if let Some(coinbase) = self.coinbase {
+            handler.block_coinbase = coinbase;
+        }
+        if let Some(chain_id) = self.chain_id {
+            handler.chain_id = chain_id.into();
+        }
+    }
+}
@@ -0,0 +1,746 @@
+use {
+    crate::{
+        api::{
+            alt_alloc, do_tx, do_tx_holder, do_tx_holder_iterative, do_tx_iterative, get_alt,
+            transmit_tx, Emulation,
+        },
+        fork::Fork,
+        source::AccountSource,
+    },
+    rome_evm::{
+        client::{
+            self, AltAllocArgs, DoTxArgs, DoTxHolderArgs, DoTxHolderIterativeArgs, DoTxIterativeArgs,
+            TransmitTxArgs,
+        },
+        error::{Result, RomeProgramError::*},
+        tx::tx::Tx,
+        H160, H256,
+    },
+    solana_program::{
+        address_lookup_table::{
+            instruction::derive_lookup_table_address,
+            state::{AddressLookupTable, LOOKUP_TABLE_MAX_ADDRESSES},
+            AddressLookupTableAccount,
+        },
+        clock::Slot,
+        hash::Hash,
+        instruction::Instruction,
+        keccak,
+        message::{v0, VersionedMessage},
+        msg,
+        pubkey::{Pubkey, PUBKEY_BYTES},
+    },
+    std::sync::Arc,
+};
+
+// the limit of the serialized Solana transaction
+pub const MAX_TX_SIZE: usize = 1232;
+const SIGNATURE_SIZE: usize = 64;
+
+pub mod compute_budget {
+    solana_program::declare_id!("ComputeBudget111111111111111111111111111111");
+}
+
+/// The settings of the transaction planner
+#[derive(Clone, Debug)]
+pub struct PlanConfig {
+    // the index of the tx_holder and state_holder accounts of the signer
+    pub holder: u64,
+    // the session of the iterative transaction
+    pub session: u64,
+    // the index of the alt_slots account of the signer
+    pub alt_holder: u64,
+    pub alt_session: u64,
+    pub fee_addr: Option<H160>,
+    pub recent_blockhash: Hash,
+    // the slot used to derive the new address lookup table
+    pub recent_slot: Slot,
+    pub compute_unit_limit: u32,
+    pub compute_unit_price: Option<u64>,
+    pub heap_frame: Option<u32>,
+}
+
+impl PlanConfig {
+    pub fn new(recent_blockhash: Hash, recent_slot: Slot) -> Self {
+        Self {
+            holder: 0,
+            session: 0,
+            alt_holder: 0,
+            alt_session: 0,
+            fee_addr: None,
+            recent_blockhash,
+            recent_slot,
+            compute_unit_limit: 1_400_000,
+            compute_unit_price: None,
+            heap_frame: Some(256 * 1024),
+        }
+    }
+}
+
+/// The rome-evm instruction executing the Ethereum transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Path {
+    DoTx,
+    DoTxHolder,
+    DoTxIterative,
+    DoTxHolderIterative,
+}
+
+/// Why the path is chosen
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reason {
+    // the transaction fits into the limits of one Solana transaction
+    Atomic,
+    // the limits of one Solana transaction are exceeded, the execution is split into the iterations
+    NotAtomic {
+        steps: u64,
+        alloc: usize,
+        syscalls: u64,
+    },
+    // the instruction exceeds the transaction size, the rlp is transmitted to the holder account
+    TxTooLarge { size: usize },
+    // the account keys exceed the transaction size, the accounts are loaded by the address lookup table
+    TooManyAccounts { accounts: usize },
+}
+
+/// The kind of the planned Solana transaction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StepKind {
+    // creates or extends the address lookup table, the table is available in the next slot
+    AltAlloc,
+    // writes the chunk of the rlp to the holder account
+    TransmitTx { offset: usize },
+    // the atomic transaction
+    Execute,
+    Iteration(u64),
+}
+
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub kind: StepKind,
+    pub message: v0::Message,
+}
+
+/// The ordered list of the ready-to-sign messages. The steps must be confirmed in order,
+/// the iterations of the same transaction can be sent together
+pub struct Plan {
+    pub path: Path,
+    pub reasons: Vec<Reason>,
+    pub alt: Option<AddressLookupTableAccount>,
+    pub steps: Vec<Step>,
+    pub emulation: Emulation,
+}
+
+pub fn plan_tx(
+    program_id: &Pubkey,
+    rlp: &[u8],
+    signer: &Pubkey,
+    source: Arc<dyn AccountSource>,
+    config: PlanConfig,
+) -> Result<Plan> {
+    Planner::new(program_id, signer, source, config).plan(rlp)
+}
+
+/// Chooses between DoTx, DoTxHolder, DoTxIterative and DoTxHolderIterative by the emulation result
+/// and builds the Solana messages of the chosen path
+pub struct Planner<'a> {
+    program_id: &'a Pubkey,
+    signer: &'a Pubkey,
+    source: Arc<dyn AccountSource>,
+    config: PlanConfig,
+}
+
+impl<'a> Planner<'a> {
+    pub fn new(
+        program_id: &'a Pubkey,
+        signer: &'a Pubkey,
+        source: Arc<dyn AccountSource>,
+        config: PlanConfig,
+    ) -> Self {
+        Self {
+            program_id,
+            signer,
+            source,
+            config,
+        }
+    }
+
+    pub fn plan(&self, rlp: &[u8]) -> Result<Plan> {
+        msg!(">> transaction planner");
+        let chain = Tx::chain_id_from_rlp(rlp)?;
+
+        let emulation = do_tx(self.program_id, &self.do_tx_args(rlp).encode(), self.signer, self.source.clone())?;
+        let (iterative, reason) = if emulation.is_atomic {
+            (false, Reason::Atomic)
+        } else {
+            let steps = emulation.vm.as_ref().map(|vm| vm.steps_executed).unwrap_or_default();
+            let reason = Reason::NotAtomic {
+                steps,
+                alloc: emulation.alloc,
+                syscalls: emulation.syscalls,
+            };
+            (true, reason)
+        };
+        let mut reasons = vec![reason];
+
+        // the rlp is passed in the instruction data
+        let emulation = if iterative {
+            do_tx_iterative(self.program_id, &self.do_tx_iterative_args(rlp), self.signer, self.source.clone())?
+        } else {
+            emulation
+        };
+        let ix = self.execute_ix(&emulation, iterative, rlp, chain, None)?;
+        let message = self.compile(vec![ix.clone()], None)?;
+        let size = tx_size(&message)?;
+
+        if size <= MAX_TX_SIZE {
+            let steps = self.execute_steps(&emulation, ix, iterative, None)?;
+            return Ok(self.result(iterative, false, reasons, None, steps, emulation));
+        }
+        reasons.push(Reason::TxTooLarge { size });
+
+        // the rlp is transmitted to the holder account
+        let fork = Fork::new(self.source.clone());
+        let transmit = self.transmit_steps(&fork, rlp, chain)?;
+        let holder_emulation = self.emulate_holder(&fork, iterative, rlp, chain)?;
+        let holder_ix = self.execute_ix(&holder_emulation, iterative, rlp, chain, Some(keccak_hash(rlp)))?;
+        let message = self.compile(vec![holder_ix.clone()], None)?;
+
+        if tx_size(&message)? <= MAX_TX_SIZE {
+            let mut steps = transmit;
+            steps.extend(self.execute_steps(&holder_emulation, holder_ix, iterative, None)?);
+            return Ok(self.result(iterative, true, reasons, None, steps, holder_emulation));
+        }
+        reasons.push(Reason::TooManyAccounts {
+            accounts: holder_emulation.accounts.len(),
+        });
+
+        // the accounts are loaded by the address lookup table
+        let (alt, mut steps) = self.alt_steps(&emulation, chain)?;
+        let message = self.compile(vec![ix.clone()], Some(&alt))?;
+
+        if tx_size(&message)? <= MAX_TX_SIZE {
+            steps.extend(self.execute_steps(&emulation, ix, iterative, Some(&alt))?);
+            return Ok(self.result(iterative, false, reasons, Some(alt), steps, emulation));
+        }
+
+        let (alt, mut steps) = self.alt_steps(&holder_emulation, chain)?;
+        let message = self.compile(vec![holder_ix.clone()], Some(&alt))?;
+        let size = tx_size(&message)?;
+
+        if size <= MAX_TX_SIZE {
+            steps.extend(transmit);
+            steps.extend(self.execute_steps(&holder_emulation, holder_ix, iterative, Some(&alt))?);
+            return Ok(self.result(iterative, true, reasons, Some(alt), steps, holder_emulation));
+        }
+
+        Err(Custom(format!(
+            "the transaction does not fit into the Solana transaction: {} bytes, {} accounts",
+            size,
+            holder_emulation.accounts.len()
+        )))
+    }
+
+    fn result(
+        &self,
+        iterative: bool,
+        holder: bool,
+        reasons: Vec<Reason>,
+        alt: Option<AddressLookupTableAccount>,
+        steps: Vec<Step>,
+        emulation: Emulation,
+    ) -> Plan {
+        let path = match (iterative, holder) {
+            (false, false) => Path::DoTx,
+            (false, true) => Path::DoTxHolder,
+            (true, false) => Path::DoTxIterative,
+            (true, true) => Path::DoTxHolderIterative,
+        };
+        msg!("path: {:?}, reasons: {:?}, steps: {}", path, reasons, steps.len());
+
+        Plan {
+            path,
+            reasons,
+            alt,
+            steps,
+            emulation,
+        }
+    }
+
+    // the instruction of the path, hash is the hash of the rlp transmitted to the holder
+    fn execute_ix(
+        &self,
+        emulation: &Emulation,
+        iterative: bool,
+        rlp: &[u8],
+        chain: u64,
+        hash: Option<H256>,
+    ) -> Result<Instruction> {
+        let (program_id, signer) = (self.program_id, self.signer);
+        let metas = emulation.get_account_metas();
+        let lock_overrides = emulation.lock_overrides.clone();
+
+        let ix = match (iterative, hash) {
+            (false, None) => client::do_tx(program_id, signer, chain, &self.do_tx_args(rlp), &metas),
+            (false, Some(hash)) => client::do_tx_holder(program_id, signer, &self.holder_args(hash, chain), &metas),
+            (true, None) => {
+                let args = DoTxIterativeArgs {
+                    unique: 0,
+                    session: self.config.session,
+                    holder: self.config.holder,
+                    fee_addr: self.config.fee_addr,
+                    lock_overrides,
+                    rlp: rlp.to_vec(),
+                };
+                client::do_tx_iterative(program_id, signer, chain, &args, &metas)
+            }
+            (true, Some(hash)) => {
+                let args = DoTxHolderIterativeArgs {
+                    unique: 0,
+                    session: self.config.session,
+                    holder: self.config.holder,
+                    hash,
+                    chain,
+                    fee_addr: self.config.fee_addr,
+                    lock_overrides,
+                };
+                client::do_tx_holder_iterative(program_id, signer, &args, &metas)
+            }
+        };
+
+        Ok(ix)
+    }
+
+    // the iterations differ by the unique field, otherwise the signatures of the transactions match
+    fn execute_steps(
+        &self,
+        emulation: &Emulation,
+        ix: Instruction,
+        iterative: bool,
+        alt: Option<&AddressLookupTableAccount>,
+    ) -> Result<Vec<Step>> {
+        if !iterative {
+            let message = self.compile(vec![ix], alt)?;
+            return Ok(vec![Step { kind: StepKind::Execute, message }]);
+        }
+
+        let count = emulation.vm.as_ref().map(|vm| vm.iteration_count).unwrap_or(1).max(1);
+
+        (0..count)
+            .map(|unique| {
+                // the unique field follows the instruction tag
+                let mut ix = ix.clone();
+                ix.data[1..9].copy_from_slice(&unique.to_le_bytes());
+                let message = self.compile(vec![ix], alt)?;
+                Ok(Step { kind: StepKind::Iteration(unique), message })
+            })
+            .collect()
+    }
+
+    // fills the holder account on the fork, the chunk size is limited by the transaction size
+    fn transmit_steps(&self, fork: &Arc<Fork>, rlp: &[u8], chain: u64) -> Result<Vec<Step>> {
+        let hash = keccak_hash(rlp);
+        let source: Arc<dyn AccountSource> = fork.clone();
+
+        let args = |offset: usize, tx: &[u8]| TransmitTxArgs {
+            holder: self.config.holder,
+            offset,
+            hash,
+            chain,
+            tx: tx.to_vec(),
+        };
+        let emulation = transmit_tx(self.program_id, &args(0, rlp).encode(), self.signer, source)?;
+        fork.commit(&emulation);
+
+        let ix = |offset: usize, chunk: &[u8]| client::transmit_tx(self.program_id, self.signer, &args(offset, chunk));
+
+        // the compact length of the instruction data takes one more byte for the chunk
+        let empty = self.compile(vec![ix(0, &[])], None)?;
+        let len = MAX_TX_SIZE
+            .checked_sub(tx_size(&empty)? + 1)
+            .filter(|len| *len > 0)
+            .ok_or(Custom("TransmitTx: too many accounts".to_string()))?;
+
+        rlp.chunks(len)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let offset = i * len;
+                let message = self.compile(vec![ix(offset, chunk)], None)?;
+                Ok(Step { kind: StepKind::TransmitTx { offset }, message })
+            })
+            .collect()
+    }
+
+    fn emulate_holder(&self, fork: &Arc<Fork>, iterative: bool, rlp: &[u8], chain: u64) -> Result<Emulation> {
+        let hash = keccak_hash(rlp);
+        let source: Arc<dyn AccountSource> = fork.clone();
+
+        if iterative {
+            let mut data = self.config.session.to_le_bytes().to_vec();
+            data.extend(self.holder_args(hash, chain).encode());
+            do_tx_holder_iterative(self.program_id, &data, self.signer, source)
+        } else {
+            do_tx_holder(self.program_id, &self.holder_args(hash, chain).encode(), self.signer, source)
+        }
+    }
+
+    // allocates the accounts of the emulation in the address lookup table of the signer
+    fn alt_steps(&self, emulation: &Emulation, chain: u64) -> Result<(AddressLookupTableAccount, Vec<Step>)> {
+        let keys = emulation
+            .accounts
+            .keys()
+            .filter(|key| *key != self.signer)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if keys.len() > LOOKUP_TABLE_MAX_ADDRESSES {
+            return Err(TooManyAccounts(keys.len() as u64));
+        }
+
+        let mut alt_args = self.config.alt_holder.to_le_bytes().to_vec();
+        alt_args.extend(chain.to_le_bytes());
+
+        // the latest table of the signer is extended if it has enough space
+        let latest = match get_alt(self.program_id, &alt_args, self.signer, self.source.clone()) {
+            Err(PdaAccountNotFound(..)) => None,
+            res => res?,
+        };
+        let current = latest
+            .map(|key| self.lookup_table(&key).map(|x| x.map(|addresses| (key, addresses))))
+            .transpose()?
+            .flatten();
+
+        let (key, addresses, new) = match current {
+            Some((key, addresses)) => {
+                let new = keys
+                    .iter()
+                    .filter(|key| !addresses.contains(key))
+                    .cloned()
+                    .collect::<Vec<_>>();
+
+                if addresses.len() + new.len() <= LOOKUP_TABLE_MAX_ADDRESSES {
+                    (key, addresses, new)
+                } else {
+                    self.new_table(keys)
+                }
+            }
+            None => self.new_table(keys),
+        };
+
+        let mut addresses = addresses;
+        addresses.extend(new.iter().cloned());
+        let alt = AddressLookupTableAccount { key, addresses };
+
+        if new.is_empty() {
+            return Ok((alt, vec![]));
+        }
+
+        let args = |keys: &[Pubkey]| AltAllocArgs {
+            holder: self.config.alt_holder,
+            chain,
+            session: self.config.alt_session,
+            recent_slot: self.config.recent_slot,
+            total: new.len() as u64,
+            keys: keys.to_vec(),
+        };
+        let ix = |keys: &[Pubkey]| client::alt_alloc(self.program_id, self.signer, &args(keys), latest);
+
+        let empty = self.compile(vec![ix(&[])], None)?;
+        let len = MAX_TX_SIZE
+            .checked_sub(tx_size(&empty)? + 1)
+            .map(|len| len / PUBKEY_BYTES)
+            .filter(|len| *len > 0)
+            .ok_or(Custom("AltAlloc: too many accounts".to_string()))?;
+
+        // the first instruction of the session creates the table or extends the latest one
+        let fork = Fork::new(self.source.clone());
+        let mut steps = vec![];
+        for chunk in new.chunks(len) {
+            let source: Arc<dyn AccountSource> = fork.clone();
+            let emulation = alt_alloc(self.program_id, &args(chunk).encode(), self.signer, source)?;
+            fork.commit(&emulation);
+
+            let message = self.compile(vec![ix(chunk)], None)?;
+            steps.push(Step { kind: StepKind::AltAlloc, message });
+        }
+
+        let used = get_alt(self.program_id, &alt_args, self.signer, fork)?;
+        if used != Some(alt.key) {
+            return Err(Custom(format!(
+                "AltAlloc: unexpected address lookup table {:?}, expected {}",
+                used, alt.key
+            )));
+        }
+
+        Ok((alt, steps))
+    }
+
+    fn new_table(&self, keys: Vec<Pubkey>) -> (Pubkey, Vec<Pubkey>, Vec<Pubkey>) {
+        let (key, _) = derive_lookup_table_address(self.signer, self.config.recent_slot);
+        (key, vec![], keys)
+    }
+
+    // None: the table does not exist
+    fn lookup_table(&self, key: &Pubkey) -> Result<Option<Vec<Pubkey>>> {
+        let Some(account) = self.source.account(key)? else {
+            return Ok(None);
+        };
+        let alt = AddressLookupTable::deserialize(&account.data)?;
+
+        Ok(Some(alt.addresses.to_vec()))
+    }
+
+    fn compile(&self, ixs: Vec<Instruction>, alt: Option<&AddressLookupTableAccount>) -> Result<v0::Message> {
+        let mut all = self.compute_budget();
+        all.extend(ixs);
+        let alts = alt.cloned().into_iter().collect::<Vec<_>>();
+
+        v0::Message::try_compile(self.signer, &all, &alts, self.config.recent_blockhash)
+            .map_err(|e| Custom(format!("message compilation error: {:?}", e)))
+    }
+
+    // the borsh encoding of ComputeBudgetInstruction
+    fn compute_budget(&self) -> Vec<Instruction> {
+        let mut ixs = vec![];
+
+        if let Some(bytes) = self.config.heap_frame {
+            let mut data = vec![1];
+            data.extend(bytes.to_le_bytes());
+            ixs.push(Instruction::new_with_bytes(compute_budget::ID, &data, vec![]));
+        }
+
+        let mut data = vec![2];
+        data.extend(self.config.compute_unit_limit.to_le_bytes());
+        ixs.push(Instruction::new_with_bytes(compute_budget::ID, &data, vec![]));
+
+        if let Some(price) = self.config.compute_unit_price {
+            let mut data = vec![3];
+            data.extend(price.to_le_bytes());
+            ixs.push(Instruction::new_with_bytes(compute_budget::ID, &data, vec![]));
+        }
+
+        ixs
+    }
+
+    fn do_tx_args(&self, rlp: &[u8]) -> DoTxArgs {
+        DoTxArgs {
+            fee_addr: self.config.fee_addr,
+            rlp: rlp.to_vec(),
+        }
+    }
+
+    // the emulator format: session | holder_index | Option<fee_recipient> | tx
+    fn do_tx_iterative_args(&self, rlp: &[u8]) -> Vec<u8> {
+        let mut data = self.config.session.to_le_bytes().to_vec();
+        data.extend(self.config.holder.to_le_bytes());
+        data.extend(self.do_tx_args(rlp).encode());
+        data
+    }
+
+    fn holder_args(&self, hash: H256, chain: u64) -> DoTxHolderArgs {
+        DoTxHolderArgs {
+            holder: self.config.holder,
+            hash,
+            chain,
+            fee_addr: self.config.fee_addr,
+        }
+    }
+}
+
+fn keccak_hash(rlp: &[u8]) -> H256 {
+    H256::from(keccak::hash(rlp).to_bytes())
+}
+
+// the size of the signed transaction
+pub fn tx_size(message: &v0::Message) -> Result<usize> {
+    let signatures = message.header.num_required_signatures as usize;
+    let message = bincode::serialize(&VersionedMessage::V0(message.clone()))?;
+
+    Ok(1 + signatures * SIGNATURE_SIZE + message.len())
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{plan_tx, tx_size, Path, Plan, PlanConfig, Reason, Step, StepKind, MAX_TX_SIZE},
+        crate::fixture::{address, legacy, sign, Fixture, CHAIN, RETURN_42, SLOT},
+        rome_evm::{state::pda::Pda, H160, U256},
+        solana_program::{hash::Hash, pubkey::Pubkey},
+    };
+
+    const SECRET: [u8; 32] = [7; 32];
+    // PUSH2 0x0200 JUMPDEST PUSH1 1 SWAP1 SUB DUP1 PUSH1 3 JUMPI STOP, the loop exceeds the opcodes of the iteration
+    const LOOP: [u8; 13] = [0x61, 0x02, 0x00, 0x5b, 0x60, 0x01, 0x90, 0x03, 0x80, 0x60, 0x03, 0x57, 0x00];
+
+    fn plan(fixture: &mut Fixture, to: H160, data: Vec<u8>) -> Plan {
+        let from = address(&SECRET);
+        fixture.balance(&from, U256::zero());
+
+        let mut tx = legacy(from, to, data);
+        tx.gas_limit = 10_000_000.into();
+        let rlp = sign(&tx, &SECRET);
+
+        let config = PlanConfig::new(Hash::default(), SLOT);
+        let plan = plan_tx(&fixture.program_id, &rlp, &fixture.signer, fixture.source(), config).unwrap();
+
+        // every rome-evm instruction loads the rollup config
+        let (config, _) = Pda::new_(&fixture.program_id, CHAIN).rollup_config_key();
+        let alt = plan.alt.as_ref().map(|alt| alt.addresses.clone()).unwrap_or_default();
+        for step in plan.steps.iter() {
+            assert!(tx_size(&step.message).unwrap() <= MAX_TX_SIZE, "{:?}", step.kind);
+            // the address lookup table is used by the execution steps only
+            let loaded = step.message.account_keys.contains(&config)
+                || (step.kind != StepKind::AltAlloc && alt.contains(&config));
+            assert!(loaded, "{:?}", step.kind);
+        }
+
+        plan
+    }
+
+    // the instruction data of the rome-evm instruction of the step
+    fn rome_ix_data(fixture: &Fixture, step: &Step) -> Vec<u8> {
+        let keys = &step.message.account_keys;
+        step.message
+            .instructions
+            .iter()
+            .find(|ix| keys[ix.program_id_index as usize] == fixture.program_id)
+            .unwrap()
+            .data
+            .clone()
+    }
+
+    // the iterations differ by the unique field following the instruction tag
+    fn check_iterations(fixture: &Fixture, plan: &Plan) -> u64 {
+        let iterations = plan
+            .steps
+            .iter()
+            .filter_map(|step| match step.kind {
+                StepKind::Iteration(unique) => Some((unique, rome_ix_data(fixture, step))),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(iterations.len() > 1);
+        assert_eq!(plan.steps.last().unwrap().kind, StepKind::Iteration(iterations.len() as u64 - 1));
+
+        for (i, (unique, data)) in iterations.iter().enumerate() {
+            assert_eq!(*unique, i as u64);
+            assert_eq!(data[1..9], unique.to_le_bytes());
+            // the rest of the instruction is the same
+            assert_eq!(data[9..], iterations[0].1[9..]);
+        }
+
+        iterations.len() as u64
+    }
+
+    fn deploy(fixture: &mut Fixture, code: &[u8]) -> (H160, Pubkey) {
+        let contract = H160::repeat_byte(0xcc);
+        let key = fixture.contract(&contract, U256::zero(), code);
+        (contract, key)
+    }
+
+    #[test]
+    fn do_tx() {
+        let mut fixture = Fixture::new();
+        let contract = H160::repeat_byte(0xcc);
+        fixture.contract(&contract, U256::zero(), &RETURN_42);
+
+        let plan = plan(&mut fixture, contract, vec![]);
+        assert_eq!(plan.path, Path::DoTx);
+        assert_eq!(plan.reasons, vec![Reason::Atomic]);
+        assert!(plan.alt.is_none());
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(plan.steps[0].kind, StepKind::Execute);
+    }
+
+    #[test]
+    fn do_tx_iterative() {
+        let mut fixture = Fixture::new();
+        let (contract, key) = deploy(&mut fixture, &LOOP);
+
+        let plan = plan(&mut fixture, contract, vec![]);
+        assert_eq!(plan.path, Path::DoTxIterative);
+        assert!(matches!(plan.reasons[..], [Reason::NotAtomic { steps, .. }] if steps > 500));
+        assert!(plan.alt.is_none());
+
+        let count = check_iterations(&fixture, &plan);
+        assert_eq!(plan.steps.len() as u64, count);
+        assert_eq!(count, plan.emulation.vm.as_ref().unwrap().iteration_count);
+        assert!(plan.emulation.accounts.contains_key(&key));
+    }
+
+    #[test]
+    fn do_tx_holder_iterative() {
+        let mut fixture = Fixture::new();
+        let (contract, _) = deploy(&mut fixture, &LOOP);
+
+        // the calldata exceeds the transaction size
+        let plan = plan(&mut fixture, contract, vec![1; 2_000]);
+        assert_eq!(plan.path, Path::DoTxHolderIterative);
+        assert!(matches!(plan.reasons[..], [Reason::NotAtomic { .. }, Reason::TxTooLarge { .. }]));
+        assert!(plan.alt.is_none());
+
+        let transmit = plan
+            .steps
+            .iter()
+            .take_while(|step| matches!(step.kind, StepKind::TransmitTx { .. }))
+            .count();
+        assert!(transmit > 1);
+        let count = check_iterations(&fixture, &plan);
+        assert_eq!(plan.steps.len() as u64, transmit as u64 + count);
+    }
+
+    #[test]
+    fn holder_split() {
+        let mut fixture = Fixture::new();
+        let contract = H160::repeat_byte(0xcc);
+        fixture.contract(&contract, U256::zero(), &RETURN_42);
+
+        // the calldata exceeds the transaction size
+        let plan = plan(&mut fixture, contract, vec![1; 2_000]);
+        assert_eq!(plan.path, Path::DoTxHolder);
+        assert!(matches!(plan.reasons[..], [Reason::Atomic, Reason::TxTooLarge { .. }]));
+        assert!(plan.alt.is_none());
+
+        let offsets = plan
+            .steps
+            .iter()
+            .filter_map(|step| match step.kind {
+                StepKind::TransmitTx { offset } => Some(offset),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert!(offsets.len() > 1);
+        assert_eq!(offsets[0], 0);
+        assert_eq!(plan.steps.len(), offsets.len() + 1);
+        assert_eq!(plan.steps.last().unwrap().kind, StepKind::Execute);
+    }
+
+    #[test]
+    fn alt_fallback() {
+        let mut fixture = Fixture::new();
+        let contract = H160::repeat_byte(0xcc);
+
+        // PUSH20 <address> BALANCE POP for 40 addresses, the balance accounts exceed the transaction size
+        let mut code = vec![];
+        for i in 1..=40_u8 {
+            code.push(0x73);
+            code.extend(H160::repeat_byte(i).as_bytes());
+            code.extend([0x31, 0x50]);
+        }
+        fixture.contract(&contract, U256::zero(), &code);
+
+        let plan = plan(&mut fixture, contract, vec![]);
+        assert_eq!(plan.path, Path::DoTx);
+        assert!(matches!(
+            plan.reasons[..],
+            [Reason::Atomic, Reason::TxTooLarge { .. }, Reason::TooManyAccounts { .. }]
+        ));
+
+        let alt = plan.alt.as_ref().unwrap();
+        assert!(alt.addresses.len() > 40);
+        assert!(!alt.addresses.contains(&fixture.signer));
+        assert_eq!(plan.steps[0].kind, StepKind::AltAlloc);
+        assert_eq!(plan.steps.last().unwrap().kind, StepKind::Execute);
+        assert_eq!(plan.steps.len() - 1, plan.steps.iter().filter(|step| step.kind == StepKind::AltAlloc).count());
+    }
+}
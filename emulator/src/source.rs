@@ -0,0 +1,368 @@
+use {
+    crate::sysvars::Sysvars,
+    borsh::{BorshDeserialize, BorshSerialize},
+    rome_evm::{
+        error::{Result, RomeProgramError::*},
+        state::aux::Account,
+    },
+    solana_account_decoder::UiAccountEncoding,
+    solana_client::{rpc_client::RpcClient, rpc_config::RpcAccountInfoConfig},
+    solana_program::{clock::Slot, msg, pubkey::Pubkey, sysvar},
+    std::{
+        collections::BTreeMap,
+        fs,
+        path::Path,
+        sync::{Arc, Mutex},
+    },
+};
+
+// the limit of the getMultipleAccounts request
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+// the number of the emulation attempts if the chain state has moved on during the emulation
+const MAX_SLOT_RETRIES: usize = 3;
+
+pub const SYSVARS: [Pubkey; 3] = [sysvar::clock::ID, sysvar::rent::ID, sysvar::slot_hashes::ID];
+
+/// The origin of the Solana accounts used by the emulator
+pub trait AccountSource: Send + Sync {
+    fn account(&self, key: &Pubkey) -> Result<Option<Account>>;
+
+    fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        keys.iter().map(|key| self.account(key)).collect()
+    }
+
+    // the accounts and the slot they are read at, the node must not serve the request before min_context_slot.
+    // the sources without the slots (the dumps) return None
+    fn accounts_at(
+        &self,
+        keys: &[Pubkey],
+        _min_context_slot: Option<Slot>,
+    ) -> Result<(Option<Slot>, Vec<Option<Account>>)> {
+        Ok((None, self.accounts(keys)?))
+    }
+
+    // clock, rent, slot_hashes
+    fn sysvars(&self) -> Result<Sysvars> {
+        let accounts = self.accounts(&SYSVARS)?;
+        Sysvars::from_accounts(&SYSVARS, &accounts)
+    }
+}
+
+impl AccountSource for RpcClient {
+    fn account(&self, key: &Pubkey) -> Result<Option<Account>> {
+        let mut accounts = self.accounts(std::slice::from_ref(key))?;
+        Ok(accounts.pop().flatten())
+    }
+
+    fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let (_, accounts) = self.accounts_at(keys, None)?;
+        Ok(accounts)
+    }
+
+    fn accounts_at(
+        &self,
+        keys: &[Pubkey],
+        min_context_slot: Option<Slot>,
+    ) -> Result<(Option<Slot>, Vec<Option<Account>>)> {
+        let mut accounts = Vec::with_capacity(keys.len());
+        let mut context = None;
+
+        for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let config = RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.commitment()),
+                min_context_slot: context.or(min_context_slot),
+                data_slice: None,
+            };
+            let response = self.get_multiple_accounts_with_config(chunk, config)?;
+
+            // the chunks of the request must be read at the same slot
+            let slot = response.context.slot;
+            match context {
+                Some(context) if context != slot => return Err(ContextSlotMismatch(context, slot)),
+                _ => context = Some(slot),
+            }
+
+            accounts.extend(response.value.into_iter().map(|sdk| {
+                sdk.map(|sdk| Account {
+                    lamports: sdk.lamports,
+                    data: sdk.data,
+                    owner: sdk.owner,
+                    executable: sdk.executable,
+                    rent_epoch: sdk.rent_epoch,
+                    writable: false,
+                })
+            }));
+        }
+
+        Ok((context, accounts))
+    }
+}
+
+/// The source pinned to one slot: the first request fixes the slot of the emulation.
+/// min_context_slot is the lower bound only, the node may serve the later slot.
+/// If the next request is served at the later slot, the accounts read before are re-read
+/// in the same request and the slot is moved on only if they are not changed. Otherwise the
+/// emulation observes a torn state, e.g. the balance from slot N and the storage from slot N+3
+pub struct Pinned {
+    source: Arc<dyn AccountSource>,
+    min_context_slot: Option<Slot>,
+    read: Mutex<Read>,
+}
+
+// the accounts read by the emulation and the slot they are read at
+#[derive(Default)]
+struct Read {
+    slot: Option<Slot>,
+    accounts: BTreeMap<Pubkey, Option<Account>>,
+}
+
+impl Pinned {
+    pub fn new(source: Arc<dyn AccountSource>, min_context_slot: Option<Slot>) -> Self {
+        Self {
+            source,
+            min_context_slot,
+            read: Mutex::new(Read::default()),
+        }
+    }
+
+    // None: the source does not report the slots or nothing is read yet
+    pub fn slot(&self) -> Option<Slot> {
+        self.read.lock().unwrap().slot
+    }
+}
+
+impl AccountSource for Pinned {
+    fn account(&self, key: &Pubkey) -> Result<Option<Account>> {
+        let mut accounts = self.accounts(std::slice::from_ref(key))?;
+        Ok(accounts.pop().flatten())
+    }
+
+    fn accounts(&self, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+        let (_, accounts) = self.accounts_at(keys, None)?;
+        Ok(accounts)
+    }
+
+    fn accounts_at(
+        &self,
+        keys: &[Pubkey],
+        min_context_slot: Option<Slot>,
+    ) -> Result<(Option<Slot>, Vec<Option<Account>>)> {
+        let mut read = self.read.lock().unwrap();
+        let min = [read.slot, self.min_context_slot, min_context_slot]
+            .into_iter()
+            .flatten()
+            .max();
+        let (mut slot, mut accounts) = self.source.accounts_at(keys, min)?;
+
+        if let (Some(pinned), Some(at)) = (read.slot, slot) {
+            if pinned != at {
+                // the chain has moved on: the accounts read before are re-read with the new ones
+                let all = keys
+                    .iter()
+                    .chain(read.accounts.keys().filter(|key| !keys.contains(key)))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let (at, mut all_accounts) = self.source.accounts_at(&all, Some(at))?;
+
+                // the sysvars change every slot, the emulation reads them at the latest slot
+                for (key, account) in all.iter().zip(all_accounts.iter()) {
+                    if SYSVARS.contains(key) {
+                        continue;
+                    }
+                    if read.accounts.get(key).map_or(false, |prev| prev != account) {
+                        return Err(ContextSlotMismatch(pinned, at.unwrap_or(pinned)));
+                    }
+                }
+
+                all_accounts.truncate(keys.len());
+                slot = at;
+                accounts = all_accounts;
+            }
+        }
+
+        for (key, account) in keys.iter().zip(accounts.iter()) {
+            read.accounts.insert(*key, account.clone());
+        }
+        if slot.is_some() {
+            read.slot = slot;
+        }
+
+        Ok((read.slot, accounts))
+    }
+}
+
+// repeats the emulation if the chain state has moved on while the accounts were being read
+pub fn retry_on_slot_mismatch<T>(mut emulate: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 1;
+    loop {
+        match emulate() {
+            Err(ContextSlotMismatch(pinned, slot)) if attempt < MAX_SLOT_RETRIES => {
+                msg!("context slot mismatch: pinned {}, read at {}, retry", pinned, slot);
+                attempt += 1;
+            }
+            res => return res,
+        }
+    }
+}
+
+/// The in-memory set of accounts, it can be stored to the file to run the emulator offline
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default)]
+pub struct AccountDump {
+    pub accounts: BTreeMap<Pubkey, Account>,
+}
+
+impl AccountDump {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, key: Pubkey, account: Account) {
+        self.accounts.insert(key, account);
+    }
+
+    // copies the accounts and the sysvars from the source, the missing accounts are skipped
+    pub fn fetch(source: &dyn AccountSource, keys: &[Pubkey]) -> Result<Self> {
+        let keys = keys.iter().chain(SYSVARS.iter()).cloned().collect::<Vec<_>>();
+        let accounts = source.accounts(&keys)?;
+
+        let accounts = keys
+            .into_iter()
+            .zip(accounts)
+            .filter_map(|(key, account)| account.map(|x| (key, x)))
+            .collect();
+
+        Ok(Self { accounts })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let bin = fs::read(path)?;
+        let dump = Self::try_from_slice(&bin)?;
+        Ok(dump)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let bin = borsh::to_vec(self)?;
+        fs::write(path, bin)?;
+        Ok(())
+    }
+}
+
+impl AccountSource for AccountDump {
+    fn account(&self, key: &Pubkey) -> Result<Option<Account>> {
+        Ok(self.accounts.get(key).cloned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{AccountDump, AccountSource, Pinned},
+        crate::fixture::{sysvar_account, wallet},
+        rome_evm::{
+            error::{Result, RomeProgramError::*},
+            state::aux::Account,
+        },
+        solana_program::{
+            clock::{Clock, Slot},
+            pubkey::Pubkey,
+            sysvar,
+        },
+        std::sync::{Arc, Mutex},
+    };
+
+    // the node serving each request at the next slot, the clock follows the slot
+    struct Advancing {
+        slot: Mutex<Slot>,
+        dump: Mutex<AccountDump>,
+    }
+
+    impl Advancing {
+        fn new(slot: Slot, accounts: &[(Pubkey, Account)]) -> Arc<Self> {
+            let mut dump = AccountDump::new();
+            accounts.iter().for_each(|(key, account)| dump.insert(*key, account.clone()));
+
+            Arc::new(Self {
+                slot: Mutex::new(slot),
+                dump: Mutex::new(dump),
+            })
+        }
+    }
+
+    impl AccountSource for Advancing {
+        fn account(&self, key: &Pubkey) -> Result<Option<Account>> {
+            self.dump.lock().unwrap().account(key)
+        }
+
+        fn accounts_at(
+            &self,
+            keys: &[Pubkey],
+            min_context_slot: Option<Slot>,
+        ) -> Result<(Option<Slot>, Vec<Option<Account>>)> {
+            let mut slot = self.slot.lock().unwrap();
+            *slot += 1;
+            assert!(min_context_slot.map_or(true, |min| *slot >= min));
+
+            let clock = Clock {
+                slot: *slot,
+                ..Clock::default()
+            };
+            let clock = sysvar_account(bincode::serialize(&clock).unwrap());
+            self.dump.lock().unwrap().insert(sysvar::clock::ID, clock);
+
+            Ok((Some(*slot), self.accounts(keys)?))
+        }
+    }
+
+    #[test]
+    fn later_slot_unchanged() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let chain = Advancing::new(10, &[(a, wallet(1)), (b, wallet(2))]);
+        // min_context_slot is the lower bound
+        let pinned = Pinned::new(chain, Some(5));
+
+        let (_, accounts) = pinned.accounts_at(&[a, sysvar::clock::ID], None).unwrap();
+        assert_eq!(accounts[0].as_ref().unwrap().lamports, 1);
+        assert_eq!(pinned.slot(), Some(11));
+
+        // b is served at slot 12, then b, a and the clock are re-read together at slot 13.
+        // The clock is changed, but the sysvars are not compared
+        let (slot, accounts) = pinned.accounts_at(&[b], None).unwrap();
+        assert_eq!(slot, Some(13));
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].as_ref().unwrap().lamports, 2);
+        assert_eq!(pinned.slot(), Some(13));
+
+        let clock = pinned.account(&sysvar::clock::ID).unwrap().unwrap();
+        let clock: Clock = bincode::deserialize(&clock.data).unwrap();
+        assert_eq!(clock.slot, 15);
+        assert_eq!(pinned.slot(), Some(15));
+    }
+
+    #[test]
+    fn later_slot_changed() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let chain = Advancing::new(10, &[(a, wallet(1)), (b, wallet(2))]);
+        let pinned = Pinned::new(chain.clone(), None);
+
+        pinned.account(&a).unwrap();
+        chain.dump.lock().unwrap().insert(a, wallet(3));
+
+        match pinned.account(&b) {
+            Err(ContextSlotMismatch(11, 13)) => {}
+            res => panic!("context slot mismatch expected: {:?}", res.map(|_| ())),
+        }
+        assert_eq!(pinned.slot(), Some(11));
+    }
+
+    #[test]
+    fn dump_without_slots() {
+        let a = Pubkey::new_unique();
+        let mut dump = AccountDump::new();
+        dump.insert(a, wallet(1));
+        let pinned = Pinned::new(Arc::new(dump), None);
+
+        assert_eq!(pinned.account(&a).unwrap().unwrap().lamports, 1);
+        assert_eq!(pinned.slot(), None);
+    }
+}
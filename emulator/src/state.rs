@@ -1,21 +1,26 @@
 use {
     super::fake,
-    crate::stubs::Stubs,
+    crate::{
+        logs::LogCollector,
+        overrides::{BlockOverrides, InvokeHints, StateOverride},
+        source::{AccountSource, Pinned},
+        struct_logger::{StructLogger, StructLoggerConfig},
+        tracer::CallTracer,
+    },
     rome_evm::{
         assert::asserts,
         error::{Result, RomeProgramError::*},
         state::{base::Base, pda::Pda},
         AccountType::{self, *},
-        Data, OwnerInfo, H160, U256, state::aux::Account, origin::Origin, pda::Seed,
+        ChainConfig, Data, OwnerInfo, H160, U256, state::aux::Account, origin::Origin, pda::Seed,
+        tx::{eip2930::AccessList, tx::Tx},
     },
-    solana_client::rpc_client::RpcClient,
     solana_program::{
-        account_info::IntoAccountInfo, msg, pubkey::Pubkey, rent::Rent, system_program,
-        sysvar::Sysvar, program_stubs::set_syscall_stubs,
+        account_info::IntoAccountInfo, clock::Slot, msg, pubkey::Pubkey, system_program,
     },
     solana_system_interface::instruction::{create_account, transfer},
     std::{
-        cell::RefCell, collections::BTreeMap, ops::Deref, sync::Arc, cmp::Ordering::{Greater, Less}},
+        cell::RefCell, collections::{BTreeMap, BTreeSet}, ops::Deref, sync::Arc, cmp::Ordering::{Greater, Less}},
 };
 
 #[derive(Clone, Debug)]
@@ -35,20 +40,39 @@ impl<'a> Deref for State<'a> {
 }
 pub struct State<'a> {
     pub base: Base<'a>,
-    pub client: Arc<RpcClient>,
+    pub source: Arc<dyn AccountSource>,
+    // the same source, all the accounts of the emulation are read at one slot
+    pinned: Arc<Pinned>,
+    // the accounts of the access list, loaded by the batch request before the execution
+    prefetched: RefCell<BTreeMap<Pubkey, Option<Account>>>,
     pub accounts: RefCell<BTreeMap<Pubkey, Item>>,
     pub storage: RefCell<BTreeMap<H160, Slots>>,
     pub signer: Option<Pubkey>,
+    pub tracer: Option<CallTracer>,
+    pub struct_logger: Option<StructLogger>,
+    pub logs: LogCollector,
+    pub overrides: StateOverride,
+    pub block_overrides: BlockOverrides,
+    pub invoke_hints: InvokeHints,
+    // the programs called by the generic CPI precompile, only their instructions are emulated by the hints
+    pub invoked: RefCell<BTreeSet<Pubkey>>,
+}
+
+// the emulation owns all of its state and can be moved to another thread
+#[allow(dead_code)]
+fn assert_send() {
+    fn send<T: Send>() {}
+    send::<State>();
 }
 
 impl<'a> State<'a> {
     pub fn new(
         program_id: &'a Pubkey,
         signer: Option<Pubkey>,
-        client: Arc<RpcClient>,
+        source: Arc<dyn AccountSource>,
         chain: u64,
     ) -> Result<Self> {
-        let state = Self::new_unchecked(program_id, signer, client, chain)?;
+        let mut state = Self::new_unchecked(program_id, signer, source, chain)?;
         // 1. needs for transmit_tx,  
         // 2. reduces the number of failures if tx depends on timestamp
         let _ = state.info_sys(&system_program::ID)?; 
@@ -56,6 +80,7 @@ impl<'a> State<'a> {
         let mut bind = state.info_owner_reg(false)?;
         let info = bind.into_account_info();
         OwnerInfo::check_chain(&info, chain)?;
+        state.base.config = state.load_config()?;
 
         Ok(state)
     }
@@ -63,19 +88,32 @@ impl<'a> State<'a> {
     pub fn new_unchecked(
         program_id: &'a Pubkey,
         signer: Option<Pubkey>,
-        client: Arc<RpcClient>,
+        source: Arc<dyn AccountSource>,
         chain: u64,
     ) -> Result<Self> {
         asserts();
-        let stubs = Stubs::from_chain(Arc::clone(&client))?;
-        set_syscall_stubs(stubs);
+        let pinned = Arc::new(Pinned::new(source, None));
+        let source: Arc<dyn AccountSource> = pinned.clone();
+
+        // each emulation has its own sysvars, the emulations are isolated from each other.
+        // the first request pins the emulation to the slot
+        let sysvars = source.sysvars()?;
 
         let state = Self {
-            base: Base::new(program_id, chain),
-            client,
+            base: Base::with_sysvars(program_id, chain, Box::new(sysvars)),
+            source,
+            pinned,
+            prefetched: RefCell::new(BTreeMap::new()),
             accounts: RefCell::new(BTreeMap::new()),
             storage: RefCell::new(BTreeMap::new()),
             signer,
+            tracer: None,
+            struct_logger: None,
+            logs: LogCollector::default(),
+            overrides: StateOverride::new(),
+            block_overrides: BlockOverrides::default(),
+            invoke_hints: InvokeHints::new(),
+            invoked: RefCell::new(BTreeSet::new()),
         };
 
         if let Some(signer) = signer {
@@ -100,6 +138,30 @@ impl<'a> State<'a> {
         Ok(state)
     }
 
+    // the slot of the chain state the emulation is executed at
+    pub fn slot(&self) -> Option<Slot> {
+        self.pinned.slot()
+    }
+
+    pub fn enable_tracer(&mut self) {
+        self.tracer = Some(CallTracer::default());
+    }
+
+    pub fn enable_struct_logger(&mut self, config: StructLoggerConfig) {
+        self.struct_logger = Some(StructLogger::new(config));
+    }
+
+    // the overrides are seen by the vm, the accounts are not changed
+    pub fn set_overrides(&mut self, overrides: StateOverride, block_overrides: BlockOverrides) {
+        self.overrides = overrides;
+        self.block_overrides = block_overrides;
+    }
+
+    // the effects of the programs invoked by the generic CPI
+    pub fn set_invoke_hints(&mut self, hints: InvokeHints) {
+        self.invoke_hints = hints;
+    }
+
     pub fn info_addr(&self, address: &H160, or_create: bool) -> Result<Bind> {
         let key = self.pda.balance_key(address).0;
         self.info_pda(&key, Balance, Some(*address), or_create)
@@ -134,6 +196,23 @@ impl<'a> State<'a> {
         let (key, _) = self.pda.alt_slots_key(&signer, index);
         self.info_pda(&key, AltSlots, None, or_create)
     }
+    pub fn info_rollup_config(&self, or_create: bool) -> Result<Bind> {
+        let (key, _) = self.pda.rollup_config_key();
+        self.info_pda(&key, RollupConfig, None, or_create)
+    }
+    // the config account is added to the transaction accounts even if it is not created,
+    // the program requires it to distinguish the defaults from the omitted account
+    fn load_config(&self) -> Result<ChainConfig> {
+        let (key, _) = self.pda.rollup_config_key();
+        let bind = self.info_external(&key, false)?;
+        if system_program::check_id(&bind.1.owner) {
+            return Ok(ChainConfig::default());
+        }
+
+        let mut bind = self.info_rollup_config(false)?;
+        let info = bind.into_account_info();
+        ChainConfig::from_account(&info)
+    }
     // TODO: the missing account must be included in the transaction accounts
     pub fn info_pda(
         &self,
@@ -208,7 +287,7 @@ impl<'a> State<'a> {
             assert!(bind.1.writable);
             assert_eq!(bind.1.owner, system_program::ID);
 
-            let rent = Rent::get()?.minimum_balance(0);
+            let rent = self.rent()?.minimum_balance(0);
             let ix = &create_account(
                 &self.signer(),
                 &key,
@@ -264,30 +343,54 @@ impl<'a> State<'a> {
             return Ok(Some(bind))
         }
 
-        self
-            .client
-            .get_account_with_commitment(key, self.client.commitment())?
-            .value
+        let prefetched = self.prefetched.borrow().get(key).cloned();
+        let account = match prefetched {
+            Some(account) => account,
+            None => self.source.account(key)?,
+        };
+
+        account
             .map_or_else(
                 || Ok(None),
-                |sdk| {
-                    let acc = if sdk.executable {
+                |acc| {
+                    let acc = if acc.executable {
                         Account::new_executable()
                     } else {
-                        Account {
-                            lamports: sdk.lamports,
-                            data: sdk.data,
-                            owner: sdk.owner,
-                            executable: sdk.executable,
-                            rent_epoch: sdk.rent_epoch,
-                            writable,
-                        }
+                        Account { writable, ..acc }
                     };
                     let bind = (*key, acc);
                     self.insert(bind, address);
                     self.load(key, address, writable)
             })
    }
+    pub fn prefetch_tx(&self, rlp: &[u8]) -> Result<()> {
+        if let Some(list) = Tx::from_instruction(rlp)?.access_list() {
+            self.prefetch(list)?;
+        }
+
+        Ok(())
+    }
+
+    // loads the balance and storage accounts of the declared access list by one request
+    pub fn prefetch(&self, list: &AccessList) -> Result<()> {
+        let mut keys = vec![];
+        for item in list.0.iter() {
+            keys.push(self.pda.balance_key(&item.address).0);
+
+            for slot in item.storage_keys.iter() {
+                let slot = U256::from_big_endian(slot.as_bytes());
+                keys.push(self.slot_to_key(&item.address, &slot).0);
+            }
+        }
+        keys.sort();
+        keys.dedup();
+        keys.retain(|key| !self.accounts.borrow().contains_key(key));
+
+        let accounts = self.source.accounts(&keys)?;
+        self.prefetched.borrow_mut().extend(keys.into_iter().zip(accounts));
+
+        Ok(())
+    }
     pub fn update(&self, bind: Bind) {
         let mut accounts = self.accounts.borrow_mut();
         let item = accounts.get_mut(&bind.0).unwrap();
@@ -360,7 +463,7 @@ impl<'a> State<'a> {
         msg!("resized len: {}", bind.1.data.len());
 
         let lamports = bind.1.lamports;
-        let rent = Rent::get()?.minimum_balance(bind.1.data.len());
+        let rent = self.rent()?.minimum_balance(bind.1.data.len());
 
         match rent.cmp(&lamports) {
             Greater => {
@@ -395,7 +498,7 @@ impl<'a> State<'a> {
     }
     pub fn create_pda(&self, typ: &AccountType, key: Pubkey, addr: Option<H160>) -> Result<()> {
         let len = State::pda_size(typ);
-        let rent = Rent::get()?.minimum_balance(len);
+        let rent = self.rent()?.minimum_balance(len);
 
         let ix = create_account(
             &self.signer(),
@@ -421,3 +524,86 @@ impl<'a> State<'a> {
 pub fn def_bind() -> Bind {
     (Pubkey::default(), Account::default())
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::State,
+        crate::{
+            fixture::{Fixture, CHAIN},
+            source::{AccountDump, AccountSource},
+        },
+        rome_evm::{
+            error::Result,
+            state::aux::Account,
+            tx::eip2930::{AccessList, AccessListItem},
+            H160, H256, U256,
+        },
+        solana_program::{clock::Slot, pubkey::Pubkey},
+        std::sync::{Arc, Mutex},
+    };
+
+    // the dump recording the keys of each request
+    struct Counting {
+        dump: AccountDump,
+        requests: Mutex<Vec<Vec<Pubkey>>>,
+    }
+
+    impl AccountSource for Counting {
+        fn account(&self, key: &Pubkey) -> Result<Option<Account>> {
+            let mut accounts = self.accounts(std::slice::from_ref(key))?;
+            Ok(accounts.pop().flatten())
+        }
+
+        fn accounts_at(
+            &self,
+            keys: &[Pubkey],
+            _min_context_slot: Option<Slot>,
+        ) -> Result<(Option<Slot>, Vec<Option<Account>>)> {
+            self.requests.lock().unwrap().push(keys.to_vec());
+            let accounts = keys
+                .iter()
+                .map(|key| self.dump.account(key))
+                .collect::<Result<Vec<_>>>()?;
+            Ok((None, accounts))
+        }
+    }
+
+    #[test]
+    fn prefetch_access_list() {
+        let mut fixture = Fixture::new();
+        let address = H160::repeat_byte(2);
+        let balance_key = fixture.contract(&address, U256::zero(), &[0x00]);
+
+        let source = Arc::new(Counting {
+            dump: fixture.dump.clone(),
+            requests: Mutex::new(vec![]),
+        });
+        let state = State::new(&fixture.program_id, None, source.clone(), CHAIN).unwrap();
+        source.requests.lock().unwrap().clear();
+
+        let slot = U256::from(5);
+        let mut storage_key = H256::zero();
+        slot.to_big_endian(storage_key.as_bytes_mut());
+        let list = AccessList(vec![AccessListItem {
+            address,
+            storage_keys: vec![storage_key],
+        }]);
+        state.prefetch(&list).unwrap();
+
+        // the balance and the storage accounts are loaded by one request
+        let slot_key = state.slot_to_key(&address, &slot).0;
+        {
+            let requests = source.requests.lock().unwrap();
+            assert_eq!(requests.len(), 1);
+            let mut expected = vec![balance_key, slot_key];
+            expected.sort();
+            assert_eq!(requests[0], expected);
+        }
+
+        // the declared accounts are served from the prefetched, the missing storage account too
+        assert!(state.load(&balance_key, Some(address), false).unwrap().is_some());
+        assert!(state.load(&slot_key, Some(address), false).unwrap().is_none());
+        assert_eq!(source.requests.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,293 @@
+use {
+    rome_evm::{
+        tracer::{StepTracer, TraceStep},
+        H160, H256, U256,
+    },
+    std::{cell::RefCell, collections::BTreeMap},
+};
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StructLoggerConfig {
+    pub disable_stack: bool,
+    pub disable_memory: bool,
+    pub disable_storage: bool,
+}
+
+/// The step of the geth structLogger
+#[derive(Clone, Debug)]
+pub struct StructLog {
+    pub pc: usize,
+    pub op: String,
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: usize,
+    pub stack: Option<Vec<H256>>,
+    // 32-byte words
+    pub memory: Option<Vec<H256>>,
+    // the storage of the contract seen so far, reported for SLOAD and SSTORE only
+    pub storage: Option<BTreeMap<U256, U256>>,
+}
+
+pub struct StructLogger {
+    config: StructLoggerConfig,
+    logs: RefCell<Vec<StructLog>>,
+    storage: RefCell<BTreeMap<H160, BTreeMap<U256, U256>>>,
+}
+
+impl StructLogger {
+    pub fn new(config: StructLoggerConfig) -> Self {
+        Self {
+            config,
+            logs: RefCell::new(vec![]),
+            storage: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn logs(&self) -> Vec<StructLog> {
+        self.logs.borrow().clone()
+    }
+}
+
+impl StepTracer for StructLogger {
+    fn reset(&self) {
+        self.logs.borrow_mut().clear();
+        self.storage.borrow_mut().clear();
+    }
+
+    fn capture_stack(&self) -> bool {
+        !self.config.disable_stack
+    }
+
+    fn capture_memory(&self) -> bool {
+        !self.config.disable_memory
+    }
+
+    fn step(&self, step: TraceStep) {
+        let storage = match (step.storage, self.config.disable_storage) {
+            (Some((address, slot, value)), false) => {
+                let mut storage = self.storage.borrow_mut();
+                let slots = storage.entry(address).or_default();
+                slots.insert(slot, value);
+                Some(slots.clone())
+            }
+            _ => None,
+        };
+
+        // the memory is not necessarily aligned to the word size
+        let memory = step.memory.filter(|_| self.capture_memory()).map(|memory| {
+            memory
+                .chunks(32)
+                .map(|chunk| {
+                    let mut word = [0_u8; 32];
+                    word[..chunk.len()].copy_from_slice(chunk);
+                    H256(word)
+                })
+                .collect()
+        });
+
+        self.logs.borrow_mut().push(StructLog {
+            pc: step.pc,
+            op: opcode_name(step.opcode),
+            gas: step.gas,
+            gas_cost: step.gas_cost,
+            depth: step.depth,
+            stack: step.stack.filter(|_| self.capture_stack()),
+            memory,
+            storage,
+        });
+    }
+}
+
+pub fn opcode_name(opcode: u8) -> String {
+    let name = match opcode {
+        0x00 => "STOP",
+        0x01 => "ADD",
+        0x02 => "MUL",
+        0x03 => "SUB",
+        0x04 => "DIV",
+        0x05 => "SDIV",
+        0x06 => "MOD",
+        0x07 => "SMOD",
+        0x08 => "ADDMOD",
+        0x09 => "MULMOD",
+        0x0a => "EXP",
+        0x0b => "SIGNEXTEND",
+        0x10 => "LT",
+        0x11 => "GT",
+        0x12 => "SLT",
+        0x13 => "SGT",
+        0x14 => "EQ",
+        0x15 => "ISZERO",
+        0x16 => "AND",
+        0x17 => "OR",
+        0x18 => "XOR",
+        0x19 => "NOT",
+        0x1a => "BYTE",
+        0x1b => "SHL",
+        0x1c => "SHR",
+        0x1d => "SAR",
+        0x20 => "KECCAK256",
+        0x30 => "ADDRESS",
+        0x31 => "BALANCE",
+        0x32 => "ORIGIN",
+        0x33 => "CALLER",
+        0x34 => "CALLVALUE",
+        0x35 => "CALLDATALOAD",
+        0x36 => "CALLDATASIZE",
+        0x37 => "CALLDATACOPY",
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3a => "GASPRICE",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3d => "RETURNDATASIZE",
+        0x3e => "RETURNDATACOPY",
+        0x3f => "EXTCODEHASH",
+        0x40 => "BLOCKHASH",
+        0x41 => "COINBASE",
+        0x42 => "TIMESTAMP",
+        0x43 => "NUMBER",
+        0x44 => "PREVRANDAO",
+        0x45 => "GASLIMIT",
+        0x46 => "CHAINID",
+        0x47 => "SELFBALANCE",
+        0x48 => "BASEFEE",
+        0x49 => "BLOBHASH",
+        0x4a => "BLOBBASEFEE",
+        0x50 => "POP",
+        0x51 => "MLOAD",
+        0x52 => "MSTORE",
+        0x53 => "MSTORE8",
+        0x54 => "SLOAD",
+        0x55 => "SSTORE",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x59 => "MSIZE",
+        0x5a => "GAS",
+        0x5b => "JUMPDEST",
+        0x5c => "TLOAD",
+        0x5d => "TSTORE",
+        0x5e => "MCOPY",
+        0x5f => "PUSH0",
+        0x60..=0x7f => return format!("PUSH{}", opcode - 0x5f),
+        0x80..=0x8f => return format!("DUP{}", opcode - 0x7f),
+        0x90..=0x9f => return format!("SWAP{}", opcode - 0x8f),
+        0xa0..=0xa4 => return format!("LOG{}", opcode - 0xa0),
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf3 => "RETURN",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xfd => "REVERT",
+        0xfe => "INVALID",
+        0xff => "SELFDESTRUCT",
+        _ => return format!("opcode {:#04x} not defined", opcode),
+    };
+
+    name.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{opcode_name, StructLogger, StructLoggerConfig},
+        rome_evm::{
+            tracer::{StepTracer, TraceStep},
+            H160, H256, U256,
+        },
+    };
+
+    fn step(opcode: u8, memory: Vec<u8>, storage: Option<(H160, U256, U256)>) -> TraceStep {
+        TraceStep {
+            pc: 0,
+            opcode,
+            gas: 100,
+            gas_cost: 3,
+            depth: 1,
+            stack: Some(vec![H256::repeat_byte(1)]),
+            memory: Some(memory),
+            storage,
+        }
+    }
+
+    #[test]
+    fn memory_chunks() {
+        let logger = StructLogger::new(StructLoggerConfig::default());
+        logger.step(step(0x52, vec![0xaa; 40], None));
+        logger.step(step(0x52, vec![], None));
+
+        let logs = logger.logs();
+        let memory = logs[0].memory.as_ref().unwrap();
+        assert_eq!(memory.len(), 2);
+        assert_eq!(memory[0], H256::repeat_byte(0xaa));
+
+        let mut last = [0_u8; 32];
+        last[..8].copy_from_slice(&[0xaa; 8]);
+        assert_eq!(memory[1], H256(last));
+
+        assert!(logs[1].memory.as_ref().unwrap().is_empty());
+    }
+
+    #[test]
+    fn storage() {
+        let logger = StructLogger::new(StructLoggerConfig::default());
+        let address = H160::repeat_byte(1);
+        logger.step(step(0x55, vec![], Some((address, 1.into(), 10.into()))));
+        logger.step(step(0x54, vec![], Some((address, 2.into(), 20.into()))));
+        logger.step(step(0x01, vec![], None));
+
+        let logs = logger.logs();
+        assert_eq!(logs[0].storage.as_ref().unwrap().len(), 1);
+        // the storage seen so far
+        let storage = logs[1].storage.as_ref().unwrap();
+        assert_eq!(storage.get(&U256::from(1)), Some(&U256::from(10)));
+        assert_eq!(storage.get(&U256::from(2)), Some(&U256::from(20)));
+        assert!(logs[2].storage.is_none());
+
+        logger.reset();
+        assert!(logger.logs().is_empty());
+        logger.step(step(0x54, vec![], Some((address, 3.into(), 30.into()))));
+        assert_eq!(logger.logs()[0].storage.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn disable() {
+        let config = StructLoggerConfig {
+            disable_stack: true,
+            disable_memory: true,
+            disable_storage: true,
+        };
+        let logger = StructLogger::new(config);
+        assert!(!logger.capture_stack());
+        assert!(!logger.capture_memory());
+
+        logger.step(step(0x55, vec![1; 32], Some((H160::zero(), 1.into(), 1.into()))));
+        let log = &logger.logs()[0];
+        assert!(log.stack.is_none());
+        assert!(log.memory.is_none());
+        assert!(log.storage.is_none());
+        assert_eq!(log.op, "SSTORE");
+
+        let logger = StructLogger::new(StructLoggerConfig::default());
+        assert!(logger.capture_stack());
+        assert!(logger.capture_memory());
+    }
+
+    #[test]
+    fn opcode_names() {
+        assert_eq!(opcode_name(0x5f), "PUSH0");
+        assert_eq!(opcode_name(0x60), "PUSH1");
+        assert_eq!(opcode_name(0x7f), "PUSH32");
+        assert_eq!(opcode_name(0x80), "DUP1");
+        assert_eq!(opcode_name(0x8f), "DUP16");
+        assert_eq!(opcode_name(0x90), "SWAP1");
+        assert_eq!(opcode_name(0x9f), "SWAP16");
+        assert_eq!(opcode_name(0xa0), "LOG0");
+        assert_eq!(opcode_name(0xa4), "LOG4");
+        assert_eq!(opcode_name(0xa5), "opcode 0xa5 not defined");
+        assert_eq!(opcode_name(0x0c), "opcode 0x0c not defined");
+    }
+}
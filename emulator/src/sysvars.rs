@@ -0,0 +1,56 @@
+use {
+    rome_evm::{error::Result, state::aux::Account, sysvars::SysvarProvider},
+    solana_program::{
+        pubkey::Pubkey,
+        clock::{Clock, Slot}, rent::Rent, sysvar,
+        slot_hashes::SlotHashes,
+    },
+};
+
+/// The sysvars of the emulation, loaded from the account source together with the accounts
+#[derive(Clone, Default)]
+pub struct Sysvars {
+    pub rent: Rent,
+    pub clock: Clock,
+    pub slot_hashes: SlotHashes,
+}
+
+impl Sysvars {
+    // the missing sysvar accounts are replaced by the defaults
+    pub fn from_accounts(keys: &[Pubkey], accounts: &[Option<Account>]) -> Result<Self> {
+        // TODO:  optimize: load slot_hashes only for alt instruction
+        let mut sysvars = Sysvars::default();
+        for (&key, acc) in keys.iter().zip(accounts.iter()) {
+            let Some(acc) = acc else {
+                continue;
+            };
+
+            match key {
+                sysvar::clock::ID => {
+                    sysvars.clock = bincode::deserialize(&acc.data)?;
+                }
+                sysvar::rent::ID => {
+                    sysvars.rent = bincode::deserialize(&acc.data)?;
+                }
+                sysvar::slot_hashes::ID => {
+                    sysvars.slot_hashes = bincode::deserialize(&acc.data)?;
+                }
+                _ => {}
+            }
+        }
+
+        Ok(sysvars)
+    }
+}
+
+impl SysvarProvider for Sysvars {
+    fn clock(&self) -> Result<Clock> {
+        Ok(self.clock.clone())
+    }
+    fn rent(&self) -> Result<Rent> {
+        Ok(self.rent.clone())
+    }
+    fn slot_hash_position(&self, slot: &Slot) -> Result<Option<usize>> {
+        Ok(self.slot_hashes.position(slot))
+    }
+}
@@ -0,0 +1,171 @@
+use {
+    rome_evm::{
+        tracer::{CallKind, TraceAccount, TraceCall, Tracer},
+        vm::RevertInfo,
+        ExitError, ExitReason, H160, U256,
+    },
+    std::{cell::RefCell, collections::BTreeMap},
+};
+
+/// The frame of the geth callTracer
+#[derive(Clone, Debug)]
+pub struct CallFrame {
+    pub typ: CallKind,
+    pub from: H160,
+    pub to: H160,
+    pub value: U256,
+    pub gas: u64,
+    pub gas_used: u64,
+    pub input: Vec<u8>,
+    pub output: Vec<u8>,
+    pub error: Option<String>,
+    pub revert_reason: Option<String>,
+    pub calls: Vec<CallFrame>,
+}
+
+impl From<TraceCall> for CallFrame {
+    fn from(call: TraceCall) -> Self {
+        Self {
+            typ: call.kind,
+            from: call.from,
+            to: call.to,
+            value: call.value,
+            gas: call.gas,
+            gas_used: 0,
+            input: call.input,
+            output: vec![],
+            error: None,
+            revert_reason: None,
+            calls: vec![],
+        }
+    }
+}
+
+/// The account of the geth prestateTracer, the missing fields are not changed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub balance: Option<U256>,
+    pub nonce: Option<u64>,
+    pub code: Option<Vec<u8>>,
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// The geth prestateTracer in the diff mode: the modified accounts only
+#[derive(Clone, Debug, Default)]
+pub struct PrestateDiff {
+    pub pre: BTreeMap<H160, AccountDiff>,
+    pub post: BTreeMap<H160, AccountDiff>,
+}
+
+impl PrestateDiff {
+    pub fn new(pre: BTreeMap<H160, TraceAccount>, post: BTreeMap<H160, TraceAccount>) -> Self {
+        let mut diff = Self::default();
+
+        for (address, before) in pre {
+            let after = post.get(&address).cloned().unwrap_or_default();
+            if before == after {
+                continue;
+            }
+
+            let storage = |from: &TraceAccount, to: &TraceAccount| {
+                from.storage
+                    .iter()
+                    .filter(|(slot, value)| to.storage.get(slot) != Some(value))
+                    .map(|(slot, value)| (*slot, *value))
+                    .collect::<BTreeMap<_, _>>()
+            };
+
+            let old = AccountDiff {
+                balance: Some(before.balance),
+                nonce: Some(before.nonce),
+                code: Some(before.code.clone()).filter(|code| !code.is_empty()),
+                storage: storage(&before, &after),
+            };
+            let new = AccountDiff {
+                balance: Some(after.balance).filter(|x| *x != before.balance),
+                nonce: Some(after.nonce).filter(|x| *x != before.nonce),
+                code: Some(after.code.clone()).filter(|x| *x != before.code),
+                storage: storage(&after, &before),
+            };
+
+            diff.pre.insert(address, old);
+            diff.post.insert(address, new);
+        }
+
+        diff
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Trace {
+    pub call: Option<CallFrame>,
+    pub prestate: PrestateDiff,
+}
+
+/// Collects the call tree and the state diff of the emulated transaction
+#[derive(Default)]
+pub struct CallTracer {
+    stack: RefCell<Vec<CallFrame>>,
+    root: RefCell<Option<CallFrame>>,
+    prestate: RefCell<PrestateDiff>,
+}
+
+impl CallTracer {
+    // the gas used by the top-level call is the gas used by the transaction
+    pub fn trace(&self, gas_used: u64) -> Trace {
+        let mut call = self.root.borrow().clone();
+        if let Some(root) = call.as_mut() {
+            root.gas_used = gas_used;
+        }
+
+        Trace {
+            call,
+            prestate: self.prestate.borrow().clone(),
+        }
+    }
+}
+
+impl Tracer for CallTracer {
+    fn reset(&self) {
+        self.stack.borrow_mut().clear();
+        *self.root.borrow_mut() = None;
+        *self.prestate.borrow_mut() = PrestateDiff::default();
+    }
+
+    fn enter(&self, call: TraceCall) {
+        self.stack.borrow_mut().push(call.into());
+    }
+
+    fn exit(&self, reason: &ExitReason, output: &[u8], gas_used: u64) {
+        let mut stack = self.stack.borrow_mut();
+        let mut frame = stack.pop().expect("tracer: call frame expected");
+
+        frame.gas_used = gas_used;
+        frame.output = output.to_vec();
+        frame.error = error(reason);
+        if reason.is_revert() {
+            frame.revert_reason = RevertInfo::decode(output).reason;
+        }
+
+        if let Some(parent) = stack.last_mut() {
+            parent.calls.push(frame);
+        } else {
+            *self.root.borrow_mut() = Some(frame);
+        }
+    }
+
+    fn state(&self, pre: BTreeMap<H160, TraceAccount>, post: BTreeMap<H160, TraceAccount>) {
+        *self.prestate.borrow_mut() = PrestateDiff::new(pre, post);
+    }
+}
+
+fn error(reason: &ExitReason) -> Option<String> {
+    match reason {
+        ExitReason::Succeed(_) => None,
+        ExitReason::Revert(_) => Some("execution reverted".to_string()),
+        ExitReason::Error(ExitError::OutOfGas) => Some("out of gas".to_string()),
+        ExitReason::Error(e) => Some(format!("{:?}", e)),
+        ExitReason::Fatal(e) => Some(format!("{:?}", e)),
+        ExitReason::StepLimitReached => Some("step limit reached".to_string()),
+    }
+}
@@ -20,6 +20,8 @@ pub struct VmCall<'a, T: Origin + Allocate> {
     pub vm: Vm<'a, T>,
     state_machine: Option<MachineEthCall>,
     legacy: Option<Legacy>,
+    // eth_simulateV1 validates the nonce of the sender
+    pub check_nonce: bool,
 }
 
 impl<'a, T: Origin + Allocate> VmCall<'a, T> {
@@ -29,6 +31,7 @@ impl<'a, T: Origin + Allocate> VmCall<'a, T> {
             vm: Vm::new(state)?,
             state_machine: None,
             legacy: Some(legacy),
+            check_nonce: false,
         };
 
         Ok(Box::new(atomic))
@@ -46,7 +49,7 @@ impl<T: Origin + Allocate> Execute<MachineEthCall> for VmCall<'_, T> {
             Init => {
                 msg!("Init");
                 let mut tx = Tx::from_legacy(self.legacy.take().unwrap());
-                if let Some((value, reason)) = self.vm.init(&mut tx, false, None)? {
+                if let Some((value, reason)) = self.vm.init(&mut tx, self.check_nonce, None)? {
                     self.vm.set_exit_reason(reason, value);
                     Exit
                 } else {
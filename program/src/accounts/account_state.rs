@@ -1,7 +1,8 @@
 use {
-    super::{cast, cast_mut, Data, Lock},
+    super::{cast, cast_mut, Code, Data, Lock},
     crate::{
         error::{Result, RomeProgramError::DeployContractToExistingAccount},
+        tx::eip7702::delegation,
         AccountType,
     },
     evm::{H160, U256},
@@ -41,13 +42,28 @@ impl AccountState {
         AccountType::from_account(info)?;
         Ok(())
     }
-    pub fn check_no_contract<'a>(info: &'a AccountInfo<'a>, address: &H160) -> Result<()> {
-        let state = AccountState::from_account(info)?;
-        if state.is_contract {
-            return Err(DeployContractToExistingAccount(*address));
+    // EIP-7702: the account code is the delegation designator
+    pub fn is_delegated(info: &AccountInfo) -> Result<bool> {
+        if !AccountState::from_account(info)?.is_contract {
+            return Ok(false);
         }
 
-        Ok(())
+        let code = Code::from_account(info)?;
+        Ok(delegation(&code).is_some())
+    }
+
+    // the contract code is immutable,
+    // the delegation designator can be replaced by another designator or cleared by the empty code
+    pub fn check_set_code(info: &AccountInfo, address: &H160, code: &[u8]) -> Result<()> {
+        if !AccountState::from_account(info)?.is_contract {
+            return Ok(());
+        }
+
+        if AccountState::is_delegated(info)? && (code.is_empty() || delegation(code).is_some()) {
+            return Ok(());
+        }
+
+        Err(DeployContractToExistingAccount(*address))
     }
 }
 
@@ -61,10 +77,85 @@ impl Data for AccountState {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_mut(info, Self::offset(info), Self::size(info))
     }
-    fn size(_info: &AccountInfo) -> usize {
+    fn size_ver(_info: &AccountInfo, _ver: u8) -> usize {
         size_of::<Self>()
     }
-    fn offset(info: &AccountInfo) -> usize {
-        Lock::offset(info) + Lock::size(info)
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
+        Lock::offset_ver(info, ver) + Lock::size_ver(info, ver)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{AccountState, Code, Data},
+        crate::{error::RomeProgramError::DeployContractToExistingAccount, AccountType},
+        evm::H160,
+        solana_program::{account_info::AccountInfo, pubkey::Pubkey},
+        std::mem::size_of,
+    };
+
+    fn designator(byte: u8) -> Vec<u8> {
+        let mut code = vec![0xef, 0x01, 0x00];
+        code.extend(H160::repeat_byte(byte).as_bytes());
+        code
+    }
+
+    // account_type | ver | lock | AccountState | code | valids
+    fn balance(code: &[u8]) -> Vec<u8> {
+        let offset = 2 + size_of::<super::Lock>();
+        let mut data = vec![0; offset + size_of::<AccountState>()];
+        data[0] = AccountType::Balance as u8;
+        if !code.is_empty() {
+            // is_contract
+            data[offset + size_of::<AccountState>() - 1] = 1;
+        }
+        data.extend(code);
+        data.extend(evm::Valids::compute(code));
+        data
+    }
+
+    fn check(data: &mut [u8], code: &[u8]) -> (bool, bool) {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let info = AccountInfo::new(&key, false, true, &mut lamports, data, &key, false, 0);
+        assert_eq!(Code::offset(&info), 2 + size_of::<super::Lock>() + size_of::<AccountState>());
+
+        let delegated = AccountState::is_delegated(&info).unwrap();
+        let res = AccountState::check_set_code(&info, &H160::zero(), code);
+        assert!(matches!(res, Ok(()) | Err(DeployContractToExistingAccount(_))));
+
+        (delegated, res.is_ok())
+    }
+
+    #[test]
+    fn set_code_of_empty_account() {
+        let mut data = balance(&[]);
+        assert_eq!(check(&mut data, &[0x60, 0x00]), (false, true));
+        assert_eq!(check(&mut data, &designator(1)), (false, true));
+    }
+
+    #[test]
+    fn set_code_of_contract() {
+        let mut data = balance(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        assert_eq!(check(&mut data, &designator(1)), (false, false));
+        assert_eq!(check(&mut data, &[]), (false, false));
+
+        // the contract code of the designator length
+        let mut code = designator(1);
+        code[0] = 0x60;
+        let mut data = balance(&code);
+        assert_eq!(check(&mut data, &designator(2)), (false, false));
+    }
+
+    #[test]
+    fn set_code_of_delegated_account() {
+        let mut data = balance(&designator(1));
+        // re-delegation
+        assert_eq!(check(&mut data, &designator(2)), (true, true));
+        // clearing
+        assert_eq!(check(&mut data, &[]), (true, true));
+        // the contract code is not deployed to the delegated account
+        assert_eq!(check(&mut data, &[0x60, 0x00]), (true, false));
     }
 }
@@ -1,5 +1,5 @@
 use {
-    super::{cast, cast_mut, Data},
+    super::{cast, cast_mut, Data, Ver},
     crate::error::{Result, RomeProgramError::*},
     solana_program::{account_info::AccountInfo, pubkey::Pubkey},
     std::{
@@ -19,6 +19,7 @@ pub enum AccountType {
     RoLock = 5,
     OwnerInfo = 6,
     AltSlots = 7,
+    RollupConfig = 8,
 }
 
 impl AccountType {
@@ -46,11 +47,11 @@ impl AccountType {
     pub fn is_ok(info: &AccountInfo, typ: Self, program_id: &Pubkey) -> Result<()> {
         AccountType::check_owner(info, program_id)?;
 
-        if *AccountType::from_account(info)? == typ {
-            Ok(())
-        } else {
-            Err(InvalidAccountType(*info.key))
+        if *AccountType::from_account(info)? != typ {
+            return Err(InvalidAccountType(*info.key));
         }
+
+        Ver::check(info)
     }
 
     pub fn is_paid(&self) -> bool {
@@ -72,10 +73,10 @@ impl Data for AccountType {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_mut(info, Self::offset(info), Self::size(info))
     }
-    fn offset(_info: &AccountInfo) -> usize {
+    fn offset_ver(_info: &AccountInfo, _ver: u8) -> usize {
         0
     }
-    fn size(_info: &AccountInfo) -> usize {
+    fn size_ver(_info: &AccountInfo, _ver: u8) -> usize {
         size_of::<Self>()
     }
 }
@@ -46,11 +46,11 @@ impl Data for AltId {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_mut(info, Self::offset(info), Self::size(info))
     }
-    fn offset(info: &AccountInfo) -> usize {
-        Ver::offset(info) + Ver::size(info)
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
+        Ver::offset_ver(info, ver) + Ver::size_ver(info, ver)
     }
     // account_type | ver | alt
-    fn size(_info: &AccountInfo) -> usize {
+    fn size_ver(_info: &AccountInfo, _ver: u8) -> usize {
         size_of::<Self>()
     }
 }
\ No newline at end of file
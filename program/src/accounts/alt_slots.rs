@@ -46,10 +46,10 @@ impl Data for AltSlots {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_slice_mut(info, Self::offset(info), Self::size(info))
     }
-    fn size(info: &AccountInfo) -> usize {
-        slice_len::<Self>(info)
+    fn size_ver(info: &AccountInfo, ver: u8) -> usize {
+        slice_len::<Self>(info, ver)
     }
-    fn offset(info: &AccountInfo) -> usize {
-        AltId::offset(info) + AltId::size(info)
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
+        AltId::offset_ver(info, ver) + AltId::size_ver(info, ver)
     }
 }
\ No newline at end of file
@@ -17,11 +17,11 @@ impl Data for Code {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_slice_mut(info, Self::offset(info), Self::size(info))
     }
-    fn offset(info: &AccountInfo) -> usize {
-        AccountState::offset(info) + AccountState::size(info)
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
+        AccountState::offset_ver(info, ver) + AccountState::size_ver(info, ver)
     }
-    fn size(info: &AccountInfo) -> usize {
-        let offset = Self::offset(info);
+    fn size_ver(info: &AccountInfo, ver: u8) -> usize {
+        let offset = Self::offset_ver(info, ver);
         assert!(info.data_len() >= offset);
 
         let len = info.data_len() - offset;
@@ -48,17 +48,17 @@ impl Data for Holder {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_slice_mut(info, Self::offset(info), Self::size(info))
     }
-    fn size(info: &AccountInfo) -> usize {
-        assert!(info.data_len() >= Self::offset(info));
-        info.data_len() - Self::offset(info)
+    fn size_ver(info: &AccountInfo, ver: u8) -> usize {
+        assert!(info.data_len() >= Self::offset_ver(info, ver));
+        info.data_len() - Self::offset_ver(info, ver)
     }
-    fn offset(info: &AccountInfo) -> usize {
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
         let typ = AccountType::from_account(info)
             .expect("invalid argument Holder::offset");
 
         match *typ {
-            AccountType::TxHolder => TxHolder::offset(info) + TxHolder::size(info),
-            AccountType::StateHolder => StateHolder::offset(info) + StateHolder::size(info),
+            AccountType::TxHolder => TxHolder::offset_ver(info, ver) + TxHolder::size_ver(info, ver),
+            AccountType::StateHolder => StateHolder::offset_ver(info, ver) + StateHolder::size_ver(info, ver),
             _ => unreachable!()
         }
     }
@@ -3,11 +3,8 @@ use {
         AccountType::{self, *},
         Ver, {cast, cast_mut, Data},
     },
-    crate::{
-        config::LOCK_DURATION,
-        error::{Result, RomeProgramError::*},
-    },
-    solana_program::{account_info::AccountInfo, clock::Clock, pubkey::Pubkey, sysvar::Sysvar},
+    crate::error::{Result, RomeProgramError::*},
+    solana_program::{account_info::AccountInfo, pubkey::Pubkey},
     std::{
         cell::{Ref, RefMut},
         fmt::{self, Debug, Formatter},
@@ -61,40 +58,37 @@ impl Lock {
 
         Ok(false)
     }
-    fn is_expired(&self) -> Result<bool> {
-        let expired = Clock::get()?
-            .unix_timestamp
+    // duration is the lock_duration of the rollup config, now is the unix_timestamp of the clock
+    fn is_expired(&self, duration: i64, now: i64) -> Result<bool> {
+        let expired = now
             .checked_sub(self.timestamp)
             .ok_or(CalculationUnderflow)?
-            >= LOCK_DURATION;
+            >= duration;
 
         Ok(expired)
     }
-    pub fn get(&self) -> Result<Option<LockType>> {
+    pub fn get(&self, duration: i64, now: i64) -> Result<Option<LockType>> {
         let mut lock = None;
 
-        if self.lock.is_some() && !self.is_expired()? {
+        if self.lock.is_some() && !self.is_expired(duration, now)? {
             lock = self.lock
         }
 
         Ok(lock)
     }
-    pub fn ro_lock(&mut self) -> Result<()> {
+    pub fn ro_lock(&mut self, now: i64) {
         self.lock = Some(LockType::Ro);
-        self.timestamp = Clock::get()?.unix_timestamp;
-        Ok(())
+        self.timestamp = now;
     }
-    pub fn rw_lock(&mut self, holder: &Pubkey) -> Result<()> {
+    pub fn rw_lock(&mut self, holder: &Pubkey, now: i64) {
         self.lock = Some(LockType::Rw(holder.to_bytes()));
-        self.timestamp = Clock::get()?.unix_timestamp;
-        Ok(())
+        self.timestamp = now;
     }
     pub fn unlock(&mut self) {
         self.lock = None
     }
-    pub fn update(&mut self) -> Result<()> {
-        self.timestamp = Clock::get()?.unix_timestamp;
-        Ok(())
+    pub fn update(&mut self, now: i64) {
+        self.timestamp = now;
     }
     pub fn is_new_one(&self) -> bool {
         self.lock.is_none() && self.timestamp == 0
@@ -111,10 +105,10 @@ impl Data for Lock {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_mut(info, Self::offset(info), Self::size(info))
     }
-    fn size(_info: &AccountInfo) -> usize {
+    fn size_ver(_info: &AccountInfo, _ver: u8) -> usize {
         size_of::<Self>()
     }
-    fn offset(info: &AccountInfo) -> usize {
-        Ver::offset(info) + Ver::size(info)
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
+        Ver::offset_ver(info, ver) + Ver::size_ver(info, ver)
     }
 }
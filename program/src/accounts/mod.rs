@@ -10,9 +10,10 @@ mod state_holder;
 mod storage;
 mod tx_holder;
 mod valids;
-mod ver;
+pub(crate) mod ver;
 mod alt_id;
 mod alt_slots;
+mod rollup_config;
 
 pub use account_state::*;
 pub use account_type::*;
@@ -26,9 +27,10 @@ pub use state_holder::{Iterations, StateHolder};
 pub use storage::Storage;
 pub use tx_holder::TxHolder;
 pub use valids::Valids;
-pub use ver::Ver;
+pub use ver::{Migration, Ver, CURRENT_VER, MIGRATIONS};
 pub use alt_id::AltId;
 pub use alt_slots::AltSlots;
+pub use rollup_config::{ChainConfig, FeeRecipient, RollupConfig};
 
 use {
     crate::error::{Result, RomeProgramError::InvalidDataLength},
@@ -45,8 +47,15 @@ pub trait Data {
     type ItemMut<'a>;
     fn from_account<'a>(info: &'a AccountInfo) -> Result<Self::Item<'a>>;
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>>;
-    fn size(info: &AccountInfo) -> usize;
-    fn offset(info: &AccountInfo) -> usize;
+    // the layout of the account of the version ver, the migration reads the previous layout by them
+    fn size_ver(info: &AccountInfo, ver: u8) -> usize;
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize;
+    fn size(info: &AccountInfo) -> usize {
+        Self::size_ver(info, CURRENT_VER)
+    }
+    fn offset(info: &AccountInfo) -> usize {
+        Self::offset_ver(info, CURRENT_VER)
+    }
 }
 
 fn cast<'a, T>(info: &'a AccountInfo, offset: usize, len: usize) -> Result<Ref<'a, T>> {
@@ -135,8 +144,8 @@ fn cast_slice_mut<'a, T>(
     Ok(slice)
 }
 
-fn slice_len<T: Data>(info: &AccountInfo) -> usize {
-    let offset = T::offset(info);
+fn slice_len<T: Data>(info: &AccountInfo, ver: u8) -> usize {
+    let offset = T::offset_ver(info, ver);
     let mut len = info.data.borrow().len();
     assert!(len >= offset);
     len -= offset;
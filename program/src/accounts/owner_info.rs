@@ -2,8 +2,8 @@ use {
     super::{AccountType, Data, Ver},
     crate::{
         accounts::{cast_slice, cast_slice_mut, slice_len},
-        error::{Result, RomeProgramError::UnregisteredChainId},
-        H160,
+        error::{Result, RomeProgramError::*},
+        registration_key, H160,
     },
     solana_program::{account_info::AccountInfo, pubkey::Pubkey},
     std::cell::{Ref, RefMut},
@@ -12,7 +12,7 @@ use {
 #[derive(Clone, Default, Debug)]
 #[repr(C, packed)]
 pub struct OwnerInfo {
-    pub _key: Pubkey,
+    pub owner_key: Pubkey,
     pub chain: u64,
     pub _mint_address: Option<H160>,
     pub slot: u64,
@@ -46,6 +46,40 @@ impl OwnerInfo {
 
         Ok(())
     }
+
+    pub fn position(info: &AccountInfo, chain: u64) -> Result<usize> {
+        let reg = OwnerInfo::from_account(info)?;
+
+        reg.iter()
+            .position(|owner| owner.chain == chain)
+            .ok_or(UnregisteredChainId(chain))
+    }
+
+    // the per-chain owner key, None if the key is not set
+    pub fn owner_key(&self) -> Option<Pubkey> {
+        Some(self.owner_key).filter(|key| *key != Pubkey::default())
+    }
+
+    // the privileged actions of the chain must be signed by the owner key if it is set,
+    // or by the registration keypair otherwise
+    pub fn authority(info: &AccountInfo, chain: u64) -> Result<Pubkey> {
+        let ix = OwnerInfo::position(info, chain)?;
+        let reg = OwnerInfo::from_account(info)?;
+
+        Ok(reg[ix].owner_key().unwrap_or(registration_key::ID))
+    }
+
+    pub fn check_authority(info: &AccountInfo, chain: u64, signer: &Pubkey) -> Result<()> {
+        let authority = OwnerInfo::authority(info, chain)?;
+        if *signer != authority {
+            return Err(Custom(format!(
+                "private instruction for chain {} must be signed by the owner key: {}",
+                chain, authority
+            )));
+        }
+
+        Ok(())
+    }
 }
 
 impl Data for OwnerInfo {
@@ -58,11 +92,11 @@ impl Data for OwnerInfo {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_slice_mut(info, Self::offset(info), Self::size(info))
     }
-    fn offset(info: &AccountInfo) -> usize {
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
         // account_type | ver | reg_owner
-        Ver::offset(info) + Ver::size(info)
+        Ver::offset_ver(info, ver) + Ver::size_ver(info, ver)
     }
-    fn size(info: &AccountInfo) -> usize {
-        slice_len::<Self>(info)
+    fn size_ver(info: &AccountInfo, ver: u8) -> usize {
+        slice_len::<Self>(info, ver)
     }
 }
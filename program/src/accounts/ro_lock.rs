@@ -75,11 +75,11 @@ impl Data for RoLock {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_slice_mut(info, Self::offset(info), Self::size(info))
     }
-    fn offset(info: &AccountInfo) -> usize {
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
         // account_type | ver | lock_overrides
-        Ver::offset(info) + Ver::size(info)
+        Ver::offset_ver(info, ver) + Ver::size_ver(info, ver)
     }
-    fn size(info: &AccountInfo) -> usize {
-        slice_len::<Self>(info)
+    fn size_ver(info: &AccountInfo, ver: u8) -> usize {
+        slice_len::<Self>(info, ver)
     }
 }
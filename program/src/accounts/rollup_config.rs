@@ -0,0 +1,149 @@
+use {
+    super::{cast, cast_mut, cast_slice, cast_slice_mut, slice_len, AccountType, Data, Ver},
+    crate::{
+        error::{Result, RomeProgramError::InvalidDataLength},
+        config::{LOCK_DURATION, MAX_CODE_SIZE, MIN_GAS_PRICE, NUMBER_OPCODES_PER_TX},
+    },
+    evm::{H160, U256},
+    solana_program::account_info::AccountInfo,
+    std::{
+        cell::{Ref, RefMut},
+        mem::size_of,
+    },
+};
+
+#[derive(Clone)]
+#[repr(C, packed)]
+pub struct RollupConfig {
+    pub min_gas_price: U256,
+    pub opcodes_per_iteration: u64,
+    pub lock_duration: i64,
+    pub max_code_size: u64,
+}
+
+#[derive(Clone)]
+#[repr(C, packed)]
+pub struct FeeRecipient {
+    pub address: H160,
+}
+
+/// The per-chain settings managed by the rollup owner.
+/// The defaults are used if the config account is not created.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChainConfig {
+    pub min_gas_price: U256,
+    // the empty list allows any fee recipient
+    pub fee_recipients: Vec<H160>,
+    pub opcodes_per_iteration: u64,
+    pub lock_duration: i64,
+    pub max_code_size: u64,
+}
+
+impl Default for ChainConfig {
+    fn default() -> Self {
+        Self {
+            min_gas_price: MIN_GAS_PRICE.into(),
+            fee_recipients: vec![],
+            opcodes_per_iteration: NUMBER_OPCODES_PER_TX,
+            lock_duration: LOCK_DURATION,
+            max_code_size: MAX_CODE_SIZE,
+        }
+    }
+}
+
+impl ChainConfig {
+    pub fn from_account(info: &AccountInfo) -> Result<Self> {
+        let config = RollupConfig::from_account(info)?;
+        let recipients = FeeRecipient::from_account(info)?;
+
+        Ok(Self {
+            min_gas_price: config.min_gas_price,
+            fee_recipients: recipients.iter().map(|x| x.address).collect(),
+            opcodes_per_iteration: config.opcodes_per_iteration,
+            lock_duration: config.lock_duration,
+            max_code_size: config.max_code_size,
+        })
+    }
+
+    // the account must be resized to ChainConfig::len before the call
+    pub fn store(&self, info: &AccountInfo) -> Result<()> {
+        {
+            let mut config = RollupConfig::from_account_mut(info)?;
+            *config = RollupConfig {
+                min_gas_price: self.min_gas_price,
+                opcodes_per_iteration: self.opcodes_per_iteration,
+                lock_duration: self.lock_duration,
+                max_code_size: self.max_code_size,
+            };
+        }
+
+        let mut recipients = FeeRecipient::from_account_mut(info)?;
+        if recipients.len() != self.fee_recipients.len() {
+            return Err(InvalidDataLength(*info.key, info.data_len(), self.len(info)));
+        }
+
+        for (to, from) in recipients.iter_mut().zip(self.fee_recipients.iter()) {
+            to.address = *from;
+        }
+
+        Ok(())
+    }
+
+    pub fn len(&self, info: &AccountInfo) -> usize {
+        FeeRecipient::offset(info) + self.fee_recipients.len() * size_of::<FeeRecipient>()
+    }
+
+    pub fn is_fee_recipient_allowed(&self, address: &H160) -> bool {
+        self.fee_recipients.is_empty() || self.fee_recipients.contains(address)
+    }
+}
+
+impl RollupConfig {
+    pub fn init(info: &AccountInfo) -> Result<()> {
+        Ver::init(info, AccountType::RollupConfig)?;
+
+        let len = RollupConfig::offset(info) + RollupConfig::size(info);
+        if len != info.data_len() {
+            return Err(InvalidDataLength(*info.key, info.data_len(), len));
+        }
+
+        ChainConfig::default().store(info)
+    }
+}
+
+impl Data for RollupConfig {
+    type Item<'a> = Ref<'a, Self>;
+    type ItemMut<'a> = RefMut<'a, Self>;
+
+    fn from_account<'a>(info: &'a AccountInfo) -> Result<Self::Item<'a>> {
+        cast(info, Self::offset(info), Self::size(info))
+    }
+    fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
+        cast_mut(info, Self::offset(info), Self::size(info))
+    }
+    fn size_ver(_info: &AccountInfo, _ver: u8) -> usize {
+        size_of::<Self>()
+    }
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
+        Ver::offset_ver(info, ver) + Ver::size_ver(info, ver)
+    }
+}
+
+impl Data for FeeRecipient {
+    type Item<'a> = Ref<'a, [Self]>;
+    type ItemMut<'a> = RefMut<'a, [Self]>;
+
+    fn from_account<'a>(info: &'a AccountInfo) -> Result<Self::Item<'a>> {
+        cast_slice(info, Self::offset(info), Self::size(info))
+    }
+    fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
+        cast_slice_mut(info, Self::offset(info), Self::size(info))
+    }
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
+        // account_type | ver | RollupConfig | [FeeRecipient]
+        RollupConfig::offset_ver(info, ver) + RollupConfig::size_ver(info, ver)
+    }
+    fn size_ver(info: &AccountInfo, ver: u8) -> usize {
+        slice_len::<Self>(info, ver)
+    }
+}
@@ -25,12 +25,12 @@ impl Data for Slot {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_slice_mut(info, Self::offset(info), Self::size(info))
     }
-    fn offset(info: &AccountInfo) -> usize {
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
         // account_type | Ver | Lock | Storage | [Slot]
-        Storage::offset(info) + Storage::size(info)
+        Storage::offset_ver(info, ver) + Storage::size_ver(info, ver)
     }
-    fn size(info: &AccountInfo) -> usize {
-        let cnt = slice_len::<Self>(info);
+    fn size_ver(info: &AccountInfo, ver: u8) -> usize {
+        let cnt = slice_len::<Self>(info, ver);
         assert!(cnt <= STORAGE_LEN);
         cnt
     }
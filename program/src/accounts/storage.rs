@@ -115,11 +115,11 @@ impl Data for Storage {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_mut(info, Self::offset(info), Self::size(info))
     }
-    fn offset(info: &AccountInfo) -> usize {
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
         // account_type | Ver | Lock | Storage | Slot
-        Lock::offset(info) + Lock::size(info)
+        Lock::offset_ver(info, ver) + Lock::size_ver(info, ver)
     }
-    fn size(_info: &AccountInfo) -> usize {
+    fn size_ver(_info: &AccountInfo, _ver: u8) -> usize {
         size_of::<Self>()
     }
 }
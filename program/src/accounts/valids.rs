@@ -17,11 +17,11 @@ impl Data for Valids {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_slice_mut(info, Self::offset(info), Self::size(info))
     }
-    fn offset(info: &AccountInfo) -> usize {
-        Code::offset(info) + Code::size(info)
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
+        Code::offset_ver(info, ver) + Code::size_ver(info, ver)
     }
-    fn size(info: &AccountInfo) -> usize {
-        let offset = Self::offset(info);
+    fn size_ver(info: &AccountInfo, ver: u8) -> usize {
+        let offset = Self::offset_ver(info, ver);
         let len = info.data_len();
         assert!(len >= offset);
 
@@ -1,13 +1,21 @@
 use {
-    super::{cast, cast_mut, Data},
-    crate::{error::Result, AccountType},
+    super::{cast, cast_mut, Data, Lock},
+    crate::{
+        error::{Result, RomeProgramError::*},
+        AccountType,
+    },
     solana_program::account_info::AccountInfo,
     std::{
         cell::{Ref, RefMut},
+        cmp::Ordering::*,
         mem::size_of,
     },
 };
 
+// the layout version of the accounts created by the program,
+// it must be increased on any change of the account layouts
+pub const CURRENT_VER: u8 = 0;
+
 #[repr(C, packed)]
 pub struct Ver(u8);
 
@@ -16,12 +24,71 @@ impl Ver {
         AccountType::init(info, typ)?;
 
         let mut ver = Ver::from_account_mut(info)?;
-        ver.0 = 0;
+        ver.0 = CURRENT_VER;
+
+        Ok(())
+    }
+
+    pub fn get(info: &AccountInfo) -> Result<u8> {
+        Ok(Ver::from_account(info)?.0)
+    }
+
+    pub fn check(info: &AccountInfo) -> Result<()> {
+        let ver = Ver::get(info)?;
+
+        match ver.cmp(&CURRENT_VER) {
+            Equal => Ok(()),
+            Less => Err(OutdatedAccountVersion(*info.key, ver)),
+            Greater => Err(UnsupportedAccountVersion(*info.key, ver)),
+        }
+    }
 
+    // the account must not be migrated while it is used by an iterative transaction
+    pub fn check_migrate(info: &AccountInfo, target: u8, lock_duration: i64, now: i64) -> Result<u8> {
+        let ver = Ver::get(info)?;
+        if ver > target {
+            return Err(UnsupportedAccountVersion(*info.key, ver));
+        }
+
+        let typ = AccountType::from_account(info)?;
+        if *typ == AccountType::Balance || *typ == AccountType::Storage {
+            let lock = Lock::from_account(info)?.get(lock_duration, now)?;
+            if lock.is_some() {
+                return Err(AccountLocked(*info.key, lock));
+            }
+        }
+
+        Ok(ver)
+    }
+
+    pub fn set(info: &AccountInfo, ver: u8) -> Result<()> {
+        Ver::from_account_mut(info)?.0 = ver;
         Ok(())
     }
+
+    // None if the layout of the account type is not changed in the version ver + 1
+    pub fn migration<'m>(info: &AccountInfo, ver: u8, migrations: &'m [Migration]) -> Result<Option<&'m Migration>> {
+        let typ = AccountType::from_account(info)?;
+        let step = migrations.iter().find(|step| step.typ == *typ && step.ver == ver);
+
+        Ok(step)
+    }
 }
 
+/// The migration of the account layout from the version ver to ver + 1
+pub struct Migration {
+    pub typ: AccountType,
+    pub ver: u8,
+    // the data length in the new layout
+    pub len: fn(&AccountInfo) -> usize,
+    // moves the data to the new layout, the account is resized to max(data_len, len) before
+    // the call and to len after the call. The previous layout is available via Data::offset_ver
+    pub upgrade: fn(&AccountInfo) -> Result<()>,
+}
+
+// the history of the layout changes, the migrations lead from the version 0 to CURRENT_VER
+pub const MIGRATIONS: &[Migration] = &[];
+
 impl Data for Ver {
     type Item<'a> = Ref<'a, Self>;
     type ItemMut<'a> = RefMut<'a, Self>;
@@ -32,10 +99,10 @@ impl Data for Ver {
     fn from_account_mut<'a>(info: &'a AccountInfo) -> Result<Self::ItemMut<'a>> {
         cast_mut(info, Self::offset(info), Self::size(info))
     }
-    fn size(_info: &AccountInfo) -> usize {
+    fn size_ver(_info: &AccountInfo, _ver: u8) -> usize {
         size_of::<Self>()
     }
-    fn offset(info: &AccountInfo) -> usize {
-        AccountType::offset(info) + AccountType::size(info)
+    fn offset_ver(info: &AccountInfo, ver: u8) -> usize {
+        AccountType::offset_ver(info, ver) + AccountType::size_ver(info, ver)
     }
 }
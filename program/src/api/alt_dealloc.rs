@@ -1,6 +1,4 @@
 
-#![allow(deprecated)] // TODO: remove and replace by PodSlotHashes
-use solana_program::sysvar::slot_hashes::SlotHashesSysvar;
 use {
     crate::{
         api::split_u64,
@@ -13,8 +11,7 @@ use {
         address_lookup_table::{
             instruction::*,
         },
-        account_info::AccountInfo, msg, pubkey::Pubkey, clock::Clock,
-        sysvar::Sysvar,
+        account_info::AccountInfo, msg, pubkey::Pubkey,
     },
     super::alt_alloc::{Action::{self, *}, track_slots, Alt}
 };
@@ -91,7 +88,7 @@ pub fn get_dealloc_actions<T: Origin + Alt>(
 
 pub fn outdated_slot_actions<T: Origin + Alt>(state: &T, slots: &[u64],) -> Result<Vec<Action>> {
     let mut vec = vec![];
-    let clock = Clock::get()?;
+    let clock = state.base().clock()?;
 
     for slot in slots {
         let (key, _) = derive_lookup_table_address(&state.signer(), *slot);
@@ -101,7 +98,7 @@ pub fn outdated_slot_actions<T: Origin + Alt>(state: &T, slots: &[u64],) -> Resu
             vec.push(Deactivate{ key });
         } else {
             if meta.deactivation_slot != clock.slot {
-                if SlotHashesSysvar::position(&meta.deactivation_slot)?.is_none() {
+                if state.base().slot_hash_position(&meta.deactivation_slot)?.is_none() {
                     vec.push(Close{ key, slot: *slot });
                 }
             }
@@ -0,0 +1,131 @@
+use {
+    crate::{
+        api::reg_owner,
+        error::Result,
+        Data, OwnerInfo, State,
+    },
+    solana_program::{account_info::AccountInfo, msg, pubkey::Pubkey},
+    std::mem::size_of,
+};
+
+pub fn check(info: &AccountInfo, signer: &Pubkey, chain: u64) -> Result<()> {
+    OwnerInfo::check_authority(info, chain, signer)
+}
+
+// keeps the order of the registered chains, the last entry is released by realloc
+pub fn dereg(info: &AccountInfo, chain: u64) -> Result<usize> {
+    let ix = OwnerInfo::position(info, chain)?;
+    let len = info.data_len() - size_of::<OwnerInfo>();
+
+    let mut owner_info = OwnerInfo::from_account_mut(info)?;
+    owner_info[ix..].rotate_left(1);
+
+    Ok(len)
+}
+
+// Instruction is used to deregister the rollup.
+// This private instruction must be signed by the rollup owner key.
+pub fn dereg_owner<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    data: &'a [u8],
+) -> Result<()> {
+    let chain = reg_owner::args(data)?;
+    msg!("Instruction: chain_id deregistration {}", chain);
+
+    let state = State::new(program_id, accounts, chain)?;
+    let info = state.info_owner_reg(false)?;
+    check(info, state.signer.key, chain)?;
+    let len = dereg(info, chain)?;
+    state.realloc(info, len)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{check, dereg},
+        crate::{
+            api::set_owner_key::set, registration_key, AccountType, Data, OwnerInfo, CURRENT_VER,
+        },
+        solana_program::{account_info::AccountInfo, pubkey::Pubkey},
+        std::mem::size_of,
+    };
+
+    const CHAINS: [u64; 3] = [100, 200, 300];
+
+    fn owner_info_data() -> Vec<u8> {
+        let mut data = vec![AccountType::OwnerInfo as u8, CURRENT_VER];
+        data.resize(data.len() + CHAINS.len() * size_of::<OwnerInfo>(), 0);
+        data
+    }
+
+    fn chains(info: &AccountInfo, len: usize) -> Vec<u64> {
+        let owner_info = OwnerInfo::from_account(info).unwrap();
+        owner_info[..len].iter().map(|owner| owner.chain).collect()
+    }
+
+    #[test]
+    fn dereg_position() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+
+        for (ix, chain) in CHAINS.iter().enumerate() {
+            let mut lamports = 0;
+            let mut data = owner_info_data();
+            let info = AccountInfo::new(
+                &key, false, true, &mut lamports, &mut data, &program_id, false, 0,
+            );
+
+            {
+                let mut owner_info = OwnerInfo::from_account_mut(&info).unwrap();
+                for (owner, chain) in owner_info.iter_mut().zip(CHAINS) {
+                    owner.chain = chain;
+                    owner.slot = chain + 1;
+                }
+            }
+
+            let len = dereg(&info, *chain).unwrap();
+            assert_eq!(len, info.data_len() - size_of::<OwnerInfo>());
+
+            // the order of the rest chains is kept
+            let mut expected = CHAINS.to_vec();
+            expected.remove(ix);
+            assert_eq!(chains(&info, CHAINS.len() - 1), expected);
+
+            let owner_info = OwnerInfo::from_account(&info).unwrap();
+            for owner in owner_info[..CHAINS.len() - 1].iter() {
+                assert_eq!({ owner.slot }, { owner.chain } + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn dereg_authority() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let owner_key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let mut data = owner_info_data();
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut data, &program_id, false, 0,
+        );
+
+        {
+            let mut owner_info = OwnerInfo::from_account_mut(&info).unwrap();
+            for (owner, chain) in owner_info.iter_mut().zip(CHAINS) {
+                owner.chain = chain;
+            }
+        }
+
+        assert!(check(&info, &registration_key::ID, 200).is_ok());
+        set(&info, 200, &owner_key).unwrap();
+        assert!(check(&info, &registration_key::ID, 200).is_err());
+        assert!(check(&info, &owner_key, 200).is_ok());
+        // the key is per chain
+        assert!(check(&info, &registration_key::ID, 300).is_ok());
+        assert!(check(&info, &owner_key, 400).is_err());
+        assert!(dereg(&info, 400).is_err());
+    }
+}
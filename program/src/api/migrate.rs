@@ -0,0 +1,201 @@
+use {
+    crate::{
+        api::split_u64,
+        error::{Result, RomeProgramError::*},
+        AccountType, Migration, State, Ver, CURRENT_VER, MIGRATIONS,
+    },
+    solana_program::{account_info::AccountInfo, msg, pubkey::Pubkey},
+    std::{convert::TryInto, mem::size_of},
+};
+
+// chain_id | [Pubkey]
+pub fn args(data: &[u8]) -> Result<(u64, Vec<Pubkey>)> {
+    let (chain, data) = split_u64(data)?;
+
+    if data.is_empty() || data.len() % size_of::<Pubkey>() != 0 {
+        return Err(InvalidInstructionData);
+    }
+
+    let keys = data
+        .chunks(size_of::<Pubkey>())
+        .map(|key| Pubkey::new_from_array(key.try_into().unwrap()))
+        .collect();
+
+    Ok((chain, keys))
+}
+
+// returns the version of the account layout
+pub fn check(info: &AccountInfo, program_id: &Pubkey, lock_duration: i64, now: i64) -> Result<u8> {
+    AccountType::check_owner(info, program_id)?;
+
+    if *AccountType::from_account(info)? == AccountType::New {
+        return Err(InvalidAccountType(*info.key));
+    }
+    if !info.is_writable {
+        return Err(NonWritableAccount(*info.key));
+    }
+
+    Ver::check_migrate(info, CURRENT_VER, lock_duration, now)
+}
+
+// Instruction upgrades the accounts to the current layout version.
+// The account is resized if the layout is changed, the rent is paid by the signer.
+pub fn migrate<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    data: &'a [u8],
+) -> Result<()> {
+    let (chain, keys) = args(data)?;
+    msg!("Instruction: migrate {} accounts", keys.len());
+
+    let state = State::new(program_id, accounts, chain)?;
+    let now = state.clock()?.unix_timestamp;
+
+    for key in keys {
+        let info = state.all().get(&key).cloned().ok_or(AccountNotFound(key))?;
+        let ver = check(info, program_id, state.config.lock_duration, now)?;
+        upgrade(info, ver, CURRENT_VER, MIGRATIONS, |info, len| state.realloc(info, len))?;
+        msg!("account {} layout version {} -> {}", key, ver, CURRENT_VER);
+    }
+
+    Ok(())
+}
+
+// applies the migrations from the version ver to the version target
+pub fn upgrade<'a>(
+    info: &'a AccountInfo<'a>,
+    ver: u8,
+    target: u8,
+    migrations: &[Migration],
+    mut realloc: impl FnMut(&'a AccountInfo<'a>, usize) -> Result<()>,
+) -> Result<()> {
+    for ver in ver..target {
+        if let Some(migration) = Ver::migration(info, ver, migrations)? {
+            let len = (migration.len)(info);
+            realloc(info, len.max(info.data_len()))?;
+            (migration.upgrade)(info)?;
+            realloc(info, len)?;
+        }
+        Ver::set(info, ver + 1)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{args, check, upgrade},
+        crate::{error::RomeProgramError::*, AccountType, Data, Migration, Ver, CURRENT_VER},
+        solana_program::{account_info::AccountInfo, pubkey::Pubkey},
+    };
+
+    // the reserved u64 is inserted between the header and the data of the tx_holder in the version 1
+    const RESERVED: usize = 8;
+
+    const MIGRATIONS: &[Migration] = &[Migration {
+        typ: AccountType::TxHolder,
+        ver: 0,
+        len: |info| info.data_len() + RESERVED,
+        upgrade: reserve,
+    }];
+
+    fn reserve(info: &AccountInfo) -> crate::error::Result<()> {
+        // the header of the previous layout
+        let header = Ver::offset_ver(info, 0) + Ver::size_ver(info, 0);
+        let mut data = info.data.borrow_mut();
+        let len = data.len();
+
+        data.copy_within(header..len - RESERVED, header + RESERVED);
+        data[header..header + RESERVED].fill(0);
+
+        Ok(())
+    }
+
+    // AccountInfo::realloc requires the runtime buffer, the test replaces the data
+    fn realloc(info: &AccountInfo, len: usize) -> crate::error::Result<()> {
+        let mut data = info.data.borrow().to_vec();
+        data.resize(len, 0);
+        *info.data.borrow_mut() = Box::leak(data.into_boxed_slice());
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_args() {
+        let keys = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut data = 100_u64.to_le_bytes().to_vec();
+        keys.iter().for_each(|key| data.extend(key.as_ref()));
+
+        assert_eq!(args(&data).unwrap(), (100, keys));
+        assert!(args(&data[..data.len() - 1]).is_err());
+        assert!(args(&100_u64.to_le_bytes()).is_err());
+    }
+
+    #[test]
+    fn migrate_tx_holder() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let payload = [1_u8, 2, 3, 4, 5];
+        let mut data = vec![AccountType::TxHolder as u8, 0];
+        data.extend(payload);
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut data, &program_id, false, 0,
+        );
+
+        let ver = check(&info, &program_id, 10, 0).unwrap();
+        assert_eq!(ver, 0);
+
+        upgrade(&info, ver, 1, MIGRATIONS, realloc).unwrap();
+
+        assert_eq!(Ver::get(&info).unwrap(), 1);
+        let header = Ver::offset_ver(&info, 1) + Ver::size_ver(&info, 1);
+        let data = info.data.borrow();
+        assert_eq!(data.len(), header + RESERVED + payload.len());
+        assert_eq!(&data[header..header + RESERVED], &[0; RESERVED]);
+        assert_eq!(&data[header + RESERVED..], &payload);
+    }
+
+    #[test]
+    fn migrate_current() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+
+        // the layout of the other account types is not changed
+        let mut data = vec![AccountType::RollupConfig as u8, 0, 7, 7];
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut data, &program_id, false, 0,
+        );
+        upgrade(&info, 0, 1, MIGRATIONS, realloc).unwrap();
+        assert_eq!(Ver::get(&info).unwrap(), 1);
+        assert_eq!(info.data_len(), 4);
+
+        // the account of the current version is not changed by the empty table
+        let mut data = vec![AccountType::TxHolder as u8, CURRENT_VER, 7];
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut data, &program_id, false, 0,
+        );
+        assert!(Ver::check(&info).is_ok());
+        upgrade(&info, CURRENT_VER, CURRENT_VER, MIGRATIONS, realloc).unwrap();
+        assert_eq!(info.data_len(), 3);
+
+        // the account of the newer program version
+        let mut data = vec![AccountType::TxHolder as u8, CURRENT_VER + 1];
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut data, &program_id, false, 0,
+        );
+        assert!(matches!(Ver::check(&info), Err(UnsupportedAccountVersion(_, _))));
+        assert!(matches!(
+            check(&info, &program_id, 10, 0),
+            Err(UnsupportedAccountVersion(_, _))
+        ));
+
+        // not owned by the program
+        let mut data = vec![AccountType::TxHolder as u8, 0];
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut data, &key, false, 0,
+        );
+        assert!(matches!(check(&info, &program_id, 10, 0), Err(InvalidOwner(_))));
+    }
+}
@@ -7,6 +7,10 @@ pub mod reg_owner;
 pub mod transmit_tx;
 pub mod alt_alloc;
 pub mod alt_dealloc;
+pub mod migrate;
+pub mod dereg_owner;
+pub mod set_owner_key;
+pub mod set_rollup_config;
 
 pub use deposit::deposit;
 pub use do_tx::do_tx;
@@ -17,6 +21,10 @@ pub use reg_owner::reg_owner;
 pub use transmit_tx::transmit_tx;
 pub use alt_alloc::alt_alloc;
 pub use alt_dealloc::alt_dealloc;
+pub use migrate::migrate;
+pub use dereg_owner::dereg_owner;
+pub use set_owner_key::set_owner_key;
+pub use set_rollup_config::set_rollup_config;
 
 use {
     crate::{
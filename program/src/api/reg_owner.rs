@@ -4,7 +4,7 @@ use {
         registration_key, Data, OwnerInfo, State,
     },
     solana_program::{
-        account_info::AccountInfo, clock::Clock, msg, pubkey::Pubkey, sysvar::Sysvar,
+        account_info::AccountInfo, msg, pubkey::Pubkey,
     },
     std::{
         convert::{TryInto},
@@ -38,15 +38,15 @@ pub fn check(info: &AccountInfo, signer: &Pubkey, chain: u64) -> Result<()> {
     Ok(())
 }
 
-pub fn reg(info: &AccountInfo, chain: u64) -> Result<()> {
+// slot is the current slot of the clock
+pub fn reg(info: &AccountInfo, chain: u64, slot: u64) -> Result<()> {
     let mut owner_info = OwnerInfo::from_account_mut(info)?;
     let owner = owner_info.last_mut().unwrap();
-    let clock = Clock::get()?;
 
-    owner._key = Pubkey::default();
+    owner.owner_key = Pubkey::default();
     owner.chain = chain;
     owner._mint_address = None;
-    owner.slot = clock.slot;
+    owner.slot = slot;
 
     Ok(())
 }
@@ -65,7 +65,7 @@ pub fn reg_owner<'a>(
     let info = state.info_owner_reg(true)?;
     check(info, state.signer.key, chain)?;
     state.realloc(info, info.data_len() + size_of::<OwnerInfo>())?;
-    reg(info, chain)?;
+    reg(info, chain, state.clock()?.slot)?;
     let _ = state.info_sol_wallet(true);
 
     Ok(())
@@ -0,0 +1,68 @@
+use {
+    crate::{
+        api::split_u64,
+        error::{Result, RomeProgramError::*},
+        Data, OwnerInfo, State,
+    },
+    solana_program::{account_info::AccountInfo, msg, pubkey::Pubkey},
+    std::{convert::TryInto, mem::size_of},
+};
+
+// chain_id | owner_key
+pub fn args(data: &[u8]) -> Result<(u64, Pubkey)> {
+    if data.len() != size_of::<u64>() + size_of::<Pubkey>() {
+        return Err(InvalidInstructionData);
+    }
+
+    let (chain, data) = split_u64(data)?;
+    let key = Pubkey::new_from_array(data.try_into().unwrap());
+
+    Ok((chain, key))
+}
+
+pub fn check(info: &AccountInfo, signer: &Pubkey, chain: u64) -> Result<()> {
+    OwnerInfo::check_authority(info, chain, signer)
+}
+
+// Pubkey::default() resets the owner key to the registration keypair
+pub fn set(info: &AccountInfo, chain: u64, key: &Pubkey) -> Result<()> {
+    let ix = OwnerInfo::position(info, chain)?;
+    let mut owner_info = OwnerInfo::from_account_mut(info)?;
+    owner_info[ix].owner_key = *key;
+
+    Ok(())
+}
+
+// Instruction is used to set or rotate the rollup owner key.
+// This private instruction must be signed by the current owner key,
+// or by the registration keypair if the owner key is not set.
+pub fn set_owner_key<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    data: &'a [u8],
+) -> Result<()> {
+    let (chain, key) = args(data)?;
+    msg!("Instruction: set owner key of chain_id {}: {}", chain, key);
+
+    let state = State::new(program_id, accounts, chain)?;
+    let info = state.info_owner_reg(false)?;
+    check(info, state.signer.key, chain)?;
+    set(info, chain, &key)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use {super::args, solana_program::pubkey::Pubkey};
+
+    #[test]
+    fn set_owner_key_args() {
+        let key = Pubkey::new_unique();
+        let mut data = 1001_u64.to_le_bytes().to_vec();
+        data.extend(key.as_ref());
+
+        assert_eq!(args(&data).unwrap(), (1001, key));
+        assert!(args(&data[..data.len() - 1]).is_err());
+    }
+}
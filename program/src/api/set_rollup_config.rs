@@ -0,0 +1,148 @@
+use {
+    crate::{
+        api::split_u64,
+        error::{Result, RomeProgramError::*},
+        ChainConfig, OwnerInfo, State, H160, U256,
+    },
+    solana_program::{account_info::AccountInfo, msg, pubkey::Pubkey},
+    std::mem::size_of,
+};
+
+// chain_id | min_gas_price | opcodes_per_iteration | lock_duration | max_code_size | [fee_recipient]
+pub fn args(data: &[u8]) -> Result<(u64, ChainConfig)> {
+    let (chain, data) = split_u64(data)?;
+
+    if data.len() < size_of::<U256>() {
+        return Err(InvalidInstructionData);
+    }
+    let (min_gas_price, data) = data.split_at(size_of::<U256>());
+    let (opcodes_per_iteration, data) = split_u64(data)?;
+    let (lock_duration, data) = split_u64(data)?;
+    let (max_code_size, data) = split_u64(data)?;
+
+    if data.len() % size_of::<H160>() != 0 {
+        return Err(InvalidInstructionData);
+    }
+    let fee_recipients = data.chunks(size_of::<H160>()).map(H160::from_slice).collect();
+
+    let config = ChainConfig {
+        min_gas_price: U256::from_big_endian(min_gas_price),
+        fee_recipients,
+        opcodes_per_iteration,
+        lock_duration: lock_duration as i64,
+        max_code_size,
+    };
+
+    if config.opcodes_per_iteration == 0 || config.lock_duration <= 0 {
+        return Err(InvalidInstructionData);
+    }
+
+    Ok((chain, config))
+}
+
+pub fn check(info: &AccountInfo, signer: &Pubkey, chain: u64) -> Result<()> {
+    OwnerInfo::check_authority(info, chain, signer)
+}
+
+// Instruction is used to create or update the rollup config.
+// This private instruction must be signed by the rollup owner key.
+pub fn set_rollup_config<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    data: &'a [u8],
+) -> Result<()> {
+    let (chain, config) = args(data)?;
+    msg!("Instruction: set rollup config of chain_id {}: {:?}", chain, config);
+
+    let state = State::new(program_id, accounts, chain)?;
+    check(state.info_owner_reg(false)?, state.signer.key, chain)?;
+
+    let info = state.info_rollup_config(true)?;
+    state.realloc(info, config.len(info))?;
+    config.store(info)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::args,
+        crate::{
+            error::RomeProgramError::InvalidDataLength, AccountType, ChainConfig, RollupConfig,
+            CURRENT_VER, H160, U256,
+        },
+        solana_program::{account_info::AccountInfo, pubkey::Pubkey},
+    };
+
+    fn data(opcodes: u64, recipients: &[H160]) -> Vec<u8> {
+        let mut data = 1001_u64.to_le_bytes().to_vec();
+        let mut price = [0_u8; 32];
+        U256::exp10(9).to_big_endian(&mut price);
+        data.extend(price);
+        data.extend(opcodes.to_le_bytes());
+        data.extend(3_u64.to_le_bytes());
+        data.extend(0x6000_u64.to_le_bytes());
+        recipients.iter().for_each(|x| data.extend(x.as_bytes()));
+        data
+    }
+
+    #[test]
+    fn set_rollup_config_args() {
+        let recipients = vec![H160::repeat_byte(1), H160::repeat_byte(2)];
+        let (chain, config) = args(&data(500, &recipients)).unwrap();
+
+        assert_eq!(chain, 1001);
+        assert_eq!(config.min_gas_price, U256::exp10(9));
+        assert_eq!(config.opcodes_per_iteration, 500);
+        assert_eq!(config.lock_duration, 3);
+        assert_eq!(config.max_code_size, 0x6000);
+        assert_eq!(config.fee_recipients, recipients);
+        assert!(config.is_fee_recipient_allowed(&H160::repeat_byte(2)));
+        assert!(!config.is_fee_recipient_allowed(&H160::repeat_byte(3)));
+
+        let (_, config) = args(&data(500, &[])).unwrap();
+        assert!(config.is_fee_recipient_allowed(&H160::repeat_byte(3)));
+    }
+
+    #[test]
+    fn invalid_rollup_config() {
+        assert!(args(&data(0, &[])).is_err());
+
+        let data = data(500, &[H160::repeat_byte(1)]);
+        assert!(args(&data[..data.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn store_rollup_config() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let (_, config) = args(&data(500, &[H160::repeat_byte(1)])).unwrap();
+
+        let mut lamports = 0;
+        let mut buf = vec![AccountType::RollupConfig as u8, CURRENT_VER];
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut buf, &program_id, false, 0,
+        );
+        let len = config.len(&info);
+
+        let mut buf = vec![AccountType::RollupConfig as u8, CURRENT_VER];
+        buf.resize(len, 0);
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut buf, &program_id, false, 0,
+        );
+        config.store(&info).unwrap();
+        assert_eq!(ChainConfig::from_account(&info).unwrap(), config);
+
+        // the account is not resized to the number of the fee recipients
+        let (_, more) = args(&data(500, &[H160::repeat_byte(1); 2])).unwrap();
+        assert!(matches!(more.store(&info), Err(InvalidDataLength(..))));
+
+        // RollupConfig::init expects the account without the fee recipients
+        let mut buf = vec![0_u8; len];
+        let info = AccountInfo::new(
+            &key, false, true, &mut lamports, &mut buf, &program_id, false, 0,
+        );
+        assert!(matches!(RollupConfig::init(&info), Err(InvalidDataLength(..))));
+    }
+}
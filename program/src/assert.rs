@@ -23,4 +23,6 @@ pub fn asserts() {
     assert!(RSOL_DECIMALS > 9);
     assert_eq!(align_of::<AltId>(), 1);
     assert_eq!(align_of::<AltSlots>(), 1);
+    assert_eq!(align_of::<RollupConfig>(), 1);
+    assert_eq!(align_of::<FeeRecipient>(), 1);
 }
@@ -0,0 +1,598 @@
+use {
+    crate::{pda::Pda, Instruction as RomeInstruction},
+    evm::{H160, H256},
+    solana_program::{
+        address_lookup_table::{self, instruction::derive_lookup_table_address},
+        clock::Slot,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+    },
+};
+
+/// Option<fee_recipient> | tx
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DoTxArgs {
+    pub fee_addr: Option<H160>,
+    pub rlp: Vec<u8>,
+}
+
+/// chain_id | rlp
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DepositArgs {
+    pub chain: u64,
+    pub rlp: Vec<u8>,
+}
+
+/// holder_index | offset | hash | chain_id | tx
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransmitTxArgs {
+    pub holder: u64,
+    pub offset: usize,
+    pub hash: H256,
+    pub chain: u64,
+    pub tx: Vec<u8>,
+}
+
+/// holder_index | tx_hash | chain_id | Option<fee_recipient>
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DoTxHolderArgs {
+    pub holder: u64,
+    pub hash: H256,
+    pub chain: u64,
+    pub fee_addr: Option<H160>,
+}
+
+/// unique | session | holder_index | Option<fee_recipient> | overrides_len | overrides | tx
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DoTxIterativeArgs {
+    pub unique: u64,
+    pub session: u64,
+    pub holder: u64,
+    pub fee_addr: Option<H160>,
+    pub lock_overrides: Vec<u8>,
+    pub rlp: Vec<u8>,
+}
+
+/// unique | session | holder_index | tx_hash | chain_id | Option<fee_recipient> | lock_overrides
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DoTxHolderIterativeArgs {
+    pub unique: u64,
+    pub session: u64,
+    pub holder: u64,
+    pub hash: H256,
+    pub chain: u64,
+    pub fee_addr: Option<H160>,
+    pub lock_overrides: Vec<u8>,
+}
+
+/// chain_id
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegOwnerArgs {
+    pub chain: u64,
+}
+
+/// holder | chain_id | session | recent_slot | total | keys
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AltAllocArgs {
+    pub holder: u64,
+    pub chain: u64,
+    pub session: u64,
+    pub recent_slot: Slot,
+    pub total: u64,
+    pub keys: Vec<Pubkey>,
+}
+
+/// holder | chain_id | session
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AltDeallocArgs {
+    pub holder: u64,
+    pub chain: u64,
+    pub session: u64,
+}
+
+// Option<fee_recipient>
+fn fee(data: &mut Vec<u8>, fee_addr: &Option<H160>) {
+    match fee_addr {
+        Some(addr) => {
+            data.push(1);
+            data.extend(addr.as_bytes());
+        }
+        None => data.push(0),
+    }
+}
+
+impl DoTxArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = vec![];
+        fee(&mut data, &self.fee_addr);
+        data.extend(&self.rlp);
+        data
+    }
+}
+
+impl DepositArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = self.chain.to_le_bytes().to_vec();
+        data.extend(&self.rlp);
+        data
+    }
+}
+
+impl TransmitTxArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = self.holder.to_le_bytes().to_vec();
+        data.extend((self.offset as u64).to_le_bytes());
+        data.extend(self.hash.as_bytes());
+        data.extend(self.chain.to_le_bytes());
+        data.extend(&self.tx);
+        data
+    }
+}
+
+impl DoTxHolderArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = self.holder.to_le_bytes().to_vec();
+        data.extend(self.hash.as_bytes());
+        data.extend(self.chain.to_le_bytes());
+        fee(&mut data, &self.fee_addr);
+        data
+    }
+}
+
+impl DoTxIterativeArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = self.unique.to_le_bytes().to_vec();
+        data.extend(self.session.to_le_bytes());
+        data.extend(self.holder.to_le_bytes());
+        fee(&mut data, &self.fee_addr);
+        data.extend((self.lock_overrides.len() as u64).to_le_bytes());
+        data.extend(&self.lock_overrides);
+        data.extend(&self.rlp);
+        data
+    }
+}
+
+impl DoTxHolderIterativeArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = self.unique.to_le_bytes().to_vec();
+        data.extend(self.session.to_le_bytes());
+        data.extend(self.holder.to_le_bytes());
+        data.extend(self.hash.as_bytes());
+        data.extend(self.chain.to_le_bytes());
+        fee(&mut data, &self.fee_addr);
+        data.extend(&self.lock_overrides);
+        data
+    }
+}
+
+impl RegOwnerArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        self.chain.to_le_bytes().to_vec()
+    }
+}
+
+impl AltAllocArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = self.holder.to_le_bytes().to_vec();
+        data.extend(self.chain.to_le_bytes());
+        data.extend(self.session.to_le_bytes());
+        data.extend(self.recent_slot.to_le_bytes());
+        data.extend(self.total.to_le_bytes());
+        self.keys.iter().for_each(|key| data.extend(key.as_ref()));
+        data
+    }
+}
+
+impl AltDeallocArgs {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = self.holder.to_le_bytes().to_vec();
+        data.extend(self.chain.to_le_bytes());
+        data.extend(self.session.to_le_bytes());
+        data
+    }
+}
+
+/// The account metas of the instruction. The signer, the owner registry and the rollup config are required
+/// by every instruction, the flags of the repeated accounts are merged
+#[derive(Clone, Debug, Default)]
+pub struct Metas(Vec<AccountMeta>);
+
+impl Metas {
+    pub fn new(program_id: &Pubkey, signer: &Pubkey, chain: u64) -> Self {
+        let pda = Pda::new_(program_id, chain);
+        let (owner_reg, _) = pda.owner_info_key();
+
+        let mut metas = Self::default();
+        metas.add(AccountMeta::new(*signer, true));
+        metas.add(AccountMeta::new_readonly(owner_reg, false));
+        // the account is passed even if the config is not created
+        metas.add(AccountMeta::new_readonly(pda.rollup_config_key().0, false));
+        metas
+    }
+
+    pub fn add(&mut self, meta: AccountMeta) {
+        match self.0.iter_mut().find(|x| x.pubkey == meta.pubkey) {
+            Some(found) => {
+                found.is_signer |= meta.is_signer;
+                found.is_writable |= meta.is_writable;
+            }
+            None => self.0.push(meta),
+        }
+    }
+
+    // the accounts of the emulation, the signer flags are not changed
+    pub fn extend(&mut self, metas: &[AccountMeta]) {
+        metas.iter().for_each(|meta| {
+            self.add(AccountMeta {
+                is_signer: false,
+                ..meta.clone()
+            })
+        });
+    }
+
+    pub fn into_vec(self) -> Vec<AccountMeta> {
+        self.0
+    }
+}
+
+fn instruction(program_id: &Pubkey, tag: RomeInstruction, args: Vec<u8>, metas: Metas) -> Instruction {
+    let mut data = vec![tag as u8];
+    data.extend(args);
+    Instruction::new_with_bytes(*program_id, &data, metas.into_vec())
+}
+
+// the accounts of the EVM transactions are the accounts of the emulation
+pub fn do_tx(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    chain: u64,
+    args: &DoTxArgs,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    let mut metas = Metas::new(program_id, signer, chain);
+    metas.extend(accounts);
+    instruction(program_id, RomeInstruction::DoTx, args.encode(), metas)
+}
+
+pub fn deposit(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    args: &DepositArgs,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    let pda = Pda::new_(program_id, args.chain);
+    let (wallet, _) = pda.sol_wallet();
+
+    let mut metas = Metas::new(program_id, signer, args.chain);
+    metas.add(AccountMeta::new(wallet, false));
+    metas.add(AccountMeta::new_readonly(system_program::ID, false));
+    metas.extend(accounts);
+    instruction(program_id, RomeInstruction::Deposit, args.encode(), metas)
+}
+
+pub fn transmit_tx(program_id: &Pubkey, signer: &Pubkey, args: &TransmitTxArgs) -> Instruction {
+    let pda = Pda::new_(program_id, args.chain);
+    let (holder, _) = pda.tx_holder_key(signer, args.holder);
+
+    let mut metas = Metas::new(program_id, signer, args.chain);
+    metas.add(AccountMeta::new(holder, false));
+    metas.add(AccountMeta::new_readonly(system_program::ID, false));
+    instruction(program_id, RomeInstruction::TransmitTx, args.encode(), metas)
+}
+
+pub fn do_tx_holder(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    args: &DoTxHolderArgs,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    let pda = Pda::new_(program_id, args.chain);
+    let (holder, _) = pda.tx_holder_key(signer, args.holder);
+
+    let mut metas = Metas::new(program_id, signer, args.chain);
+    metas.add(AccountMeta::new_readonly(holder, false));
+    metas.extend(accounts);
+    instruction(program_id, RomeInstruction::DoTxHolder, args.encode(), metas)
+}
+
+// the lock_overrides are the indices of the accounts, the accounts must be passed in the order of the emulation
+pub fn do_tx_iterative(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    chain: u64,
+    args: &DoTxIterativeArgs,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    let pda = Pda::new_(program_id, chain);
+    let (state_holder, _) = pda.state_holder_key(signer, args.holder);
+
+    let mut metas = Metas::default();
+    metas.extend(accounts);
+    metas.add(AccountMeta::new(*signer, true));
+    metas.add(AccountMeta::new(state_holder, false));
+    metas.add(AccountMeta::new_readonly(pda.owner_info_key().0, false));
+    metas.add(AccountMeta::new_readonly(pda.rollup_config_key().0, false));
+    instruction(program_id, RomeInstruction::DoTxIterative, args.encode(), metas)
+}
+
+pub fn do_tx_holder_iterative(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    args: &DoTxHolderIterativeArgs,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    let pda = Pda::new_(program_id, args.chain);
+    let (tx_holder, _) = pda.tx_holder_key(signer, args.holder);
+    let (state_holder, _) = pda.state_holder_key(signer, args.holder);
+
+    let mut metas = Metas::default();
+    metas.extend(accounts);
+    metas.add(AccountMeta::new(*signer, true));
+    metas.add(AccountMeta::new_readonly(tx_holder, false));
+    metas.add(AccountMeta::new(state_holder, false));
+    metas.add(AccountMeta::new_readonly(pda.owner_info_key().0, false));
+    metas.add(AccountMeta::new_readonly(pda.rollup_config_key().0, false));
+    instruction(program_id, RomeInstruction::DoTxHolderIterative, args.encode(), metas)
+}
+
+// the private instruction signed by the registration key
+pub fn reg_owner(program_id: &Pubkey, signer: &Pubkey, args: &RegOwnerArgs) -> Instruction {
+    let pda = Pda::new_(program_id, args.chain);
+    let (owner_reg, _) = pda.owner_info_key();
+    let (wallet, _) = pda.sol_wallet();
+
+    let mut metas = Metas::new(program_id, signer, args.chain);
+    metas.add(AccountMeta::new(owner_reg, false));
+    metas.add(AccountMeta::new(wallet, false));
+    metas.add(AccountMeta::new_readonly(system_program::ID, false));
+    instruction(program_id, RomeInstruction::RegOwner, args.encode(), metas)
+}
+
+// latest is the latest lookup table of the signer, it is extended if it has enough space.
+// Otherwise the new table is created at recent_slot
+pub fn alt_alloc(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    args: &AltAllocArgs,
+    latest: Option<Pubkey>,
+) -> Instruction {
+    let pda = Pda::new_(program_id, args.chain);
+    let (alt_slots, _) = pda.alt_slots_key(signer, args.holder);
+    let (table, _) = derive_lookup_table_address(signer, args.recent_slot);
+
+    let mut metas = Metas::new(program_id, signer, args.chain);
+    metas.add(AccountMeta::new(alt_slots, false));
+    metas.add(AccountMeta::new(table, false));
+    if let Some(latest) = latest {
+        metas.add(AccountMeta::new(latest, false));
+    }
+    metas.add(AccountMeta::new_readonly(address_lookup_table::program::ID, false));
+    metas.add(AccountMeta::new_readonly(system_program::ID, false));
+    instruction(program_id, RomeInstruction::AltAlloc, args.encode(), metas)
+}
+
+// tables are the lookup tables to deactivate or close
+pub fn alt_dealloc(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    args: &AltDeallocArgs,
+    tables: &[Pubkey],
+) -> Instruction {
+    let pda = Pda::new_(program_id, args.chain);
+    let (alt_slots, _) = pda.alt_slots_key(signer, args.holder);
+
+    let mut metas = Metas::new(program_id, signer, args.chain);
+    metas.add(AccountMeta::new(alt_slots, false));
+    tables.iter().for_each(|table| metas.add(AccountMeta::new(*table, false)));
+    metas.add(AccountMeta::new_readonly(address_lookup_table::program::ID, false));
+    metas.add(AccountMeta::new_readonly(system_program::ID, false));
+    instruction(program_id, RomeInstruction::AltDelloc, args.encode(), metas)
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        crate::api::{
+            alt_alloc, alt_dealloc, deposit, do_tx_holder, do_tx_holder_iterative,
+            do_tx_iterative, reg_owner, split_fee, transmit_tx,
+        },
+    };
+
+    fn signer() -> Pubkey {
+        Pubkey::new_from_array([1; 32])
+    }
+
+    fn program_id() -> Pubkey {
+        Pubkey::new_from_array([2; 32])
+    }
+
+    fn check_tag(ix: &Instruction, tag: RomeInstruction) {
+        assert_eq!(ix.program_id, program_id());
+        assert_eq!(ix.data[0], tag as u8);
+
+        let signers = ix.accounts.iter().filter(|x| x.is_signer).collect::<Vec<_>>();
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers[0].pubkey, signer());
+        assert!(signers[0].is_writable);
+
+        // every instruction loads the rollup config
+        let (config, _) = Pda::new_(&program_id(), 1001).rollup_config_key();
+        assert!(ix.accounts.contains(&AccountMeta::new_readonly(config, false)));
+    }
+
+    #[test]
+    fn do_tx_args() {
+        let args = DoTxArgs {
+            fee_addr: Some(H160::repeat_byte(3)),
+            rlp: vec![0xf8, 1, 2, 3],
+        };
+        let account = AccountMeta::new(Pubkey::new_from_array([4; 32]), false);
+        let ix = super::do_tx(&program_id(), &signer(), 1001, &args, &[account.clone()]);
+        check_tag(&ix, RomeInstruction::DoTx);
+        assert!(ix.accounts.contains(&account));
+
+        let (fee_addr, rlp) = split_fee(&ix.data[1..]).unwrap();
+        assert_eq!(fee_addr, args.fee_addr);
+        assert_eq!(rlp, args.rlp.as_slice());
+
+        let args = DoTxArgs {
+            fee_addr: None,
+            rlp: vec![0xf8, 1, 2, 3],
+        };
+        let (fee_addr, rlp) = split_fee(&args.encode()).unwrap();
+        assert_eq!(fee_addr, None);
+        assert_eq!(rlp, args.rlp.as_slice());
+    }
+
+    #[test]
+    fn deposit_args() {
+        let args = DepositArgs {
+            chain: 1001,
+            rlp: vec![0x7e, 1, 2],
+        };
+        let ix = super::deposit(&program_id(), &signer(), &args, &[]);
+        check_tag(&ix, RomeInstruction::Deposit);
+
+        let (chain, rlp) = deposit::args(&ix.data[1..]).unwrap();
+        assert_eq!(chain, args.chain);
+        assert_eq!(rlp, args.rlp.as_slice());
+    }
+
+    #[test]
+    fn transmit_tx_args() {
+        let args = TransmitTxArgs {
+            holder: 7,
+            offset: 900,
+            hash: H256::repeat_byte(5),
+            chain: 1001,
+            tx: vec![9; 100],
+        };
+        let ix = super::transmit_tx(&program_id(), &signer(), &args);
+        check_tag(&ix, RomeInstruction::TransmitTx);
+
+        let pda = Pda::new_(&program_id(), args.chain);
+        let (holder, _) = pda.tx_holder_key(&signer(), args.holder);
+        assert!(ix.accounts.contains(&AccountMeta::new(holder, false)));
+
+        let (holder, offset, hash, chain, tx) = transmit_tx::args(&ix.data[1..]).unwrap();
+        assert_eq!(
+            (holder, offset, hash, chain, tx),
+            (args.holder, args.offset, args.hash, args.chain, args.tx.as_slice())
+        );
+    }
+
+    #[test]
+    fn do_tx_holder_args() {
+        let args = DoTxHolderArgs {
+            holder: 7,
+            hash: H256::repeat_byte(5),
+            chain: 1001,
+            fee_addr: Some(H160::repeat_byte(3)),
+        };
+        let ix = super::do_tx_holder(&program_id(), &signer(), &args, &[]);
+        check_tag(&ix, RomeInstruction::DoTxHolder);
+
+        let parsed = do_tx_holder::args(&ix.data[1..]).unwrap();
+        assert_eq!(parsed, (args.holder, args.hash, args.chain, args.fee_addr));
+    }
+
+    #[test]
+    fn do_tx_iterative_args() {
+        let args = DoTxIterativeArgs {
+            unique: 2,
+            session: 11,
+            holder: 7,
+            fee_addr: None,
+            lock_overrides: vec![0, 4],
+            rlp: vec![0xf8, 1, 2, 3],
+        };
+        let accounts = (10..14)
+            .map(|x| AccountMeta::new(Pubkey::new_from_array([x; 32]), false))
+            .collect::<Vec<_>>();
+        let ix = super::do_tx_iterative(&program_id(), &signer(), 1001, &args, &accounts);
+        check_tag(&ix, RomeInstruction::DoTxIterative);
+        // the lock_overrides refer to the order of the emulation accounts
+        assert_eq!(&ix.accounts[..accounts.len()], accounts.as_slice());
+
+        let (session, holder, fee_addr, overrides, rlp) = do_tx_iterative::args(&ix.data[1..]).unwrap();
+        assert_eq!((session, holder, fee_addr), (args.session, args.holder, args.fee_addr));
+        assert_eq!(overrides, args.lock_overrides.as_slice());
+        assert_eq!(rlp, args.rlp.as_slice());
+    }
+
+    #[test]
+    fn do_tx_holder_iterative_args() {
+        let args = DoTxHolderIterativeArgs {
+            unique: 2,
+            session: 11,
+            holder: 7,
+            hash: H256::repeat_byte(5),
+            chain: 1001,
+            fee_addr: Some(H160::repeat_byte(3)),
+            lock_overrides: vec![1, 2, 3],
+        };
+        let ix = super::do_tx_holder_iterative(&program_id(), &signer(), &args, &[]);
+        check_tag(&ix, RomeInstruction::DoTxHolderIterative);
+
+        let (session, holder, hash, chain, fee_addr, overrides) =
+            do_tx_holder_iterative::args(&ix.data[1..]).unwrap();
+        assert_eq!(
+            (session, holder, hash, chain, fee_addr),
+            (args.session, args.holder, args.hash, args.chain, args.fee_addr)
+        );
+        assert_eq!(overrides, args.lock_overrides.as_slice());
+    }
+
+    #[test]
+    fn reg_owner_args() {
+        let args = RegOwnerArgs { chain: 1001 };
+        let ix = super::reg_owner(&program_id(), &signer(), &args);
+        check_tag(&ix, RomeInstruction::RegOwner);
+
+        let pda = Pda::new_(&program_id(), args.chain);
+        let (owner_reg, _) = pda.owner_info_key();
+        assert!(ix.accounts.contains(&AccountMeta::new(owner_reg, false)));
+
+        assert_eq!(reg_owner::args(&ix.data[1..]).unwrap(), args.chain);
+    }
+
+    #[test]
+    fn alt_alloc_args() {
+        let args = AltAllocArgs {
+            holder: 1,
+            chain: 1001,
+            session: 11,
+            recent_slot: 500,
+            total: 3,
+            keys: (20..23).map(|x| Pubkey::new_from_array([x; 32])).collect(),
+        };
+        let ix = super::alt_alloc(&program_id(), &signer(), &args, None);
+        check_tag(&ix, RomeInstruction::AltAlloc);
+
+        let (table, _) = derive_lookup_table_address(&signer(), args.recent_slot);
+        assert!(ix.accounts.contains(&AccountMeta::new(table, false)));
+
+        let (holder, chain, session, slot, total, keys) = alt_alloc::args(&ix.data[1..]).unwrap();
+        assert_eq!(
+            (holder, chain, session, slot, total),
+            (args.holder, args.chain, args.session, args.recent_slot, args.total)
+        );
+        assert_eq!(keys, args.keys);
+    }
+
+    #[test]
+    fn alt_dealloc_args() {
+        let args = AltDeallocArgs {
+            holder: 1,
+            chain: 1001,
+            session: 11,
+        };
+        let ix = super::alt_dealloc(&program_id(), &signer(), &args, &[]);
+        check_tag(&ix, RomeInstruction::AltDelloc);
+
+        let parsed = alt_dealloc::args(&ix.data[1..]).unwrap();
+        assert_eq!(parsed, (args.holder, args.chain, args.session));
+    }
+}
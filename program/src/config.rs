@@ -12,6 +12,7 @@ pub const SIG_VERIFY_COST: u64 = 5000;
 pub const GAS_VALUE: &[u8] = b"GAS_VALUE";
 pub const GAS_PRICE: &[u8] = b"GAS_PRICE";
 pub const GAS_RECIPIENT: &[u8] = b"GAS_RECIPIENT";
+pub const GAS_USED: &[u8] = b"GAS_USED"; // the evm gas used by the tx
 pub const OWNER_INFO: &[u8] = b"OWNER_INFO";
 pub const NUMBER_ALLOC_DIFF_PER_TX: u64 = 10; // mut be <= 64  (max_instruction_trace_length)
 pub const STORAGE_LEN: usize = 256; // must be <= u8::MAX+1
@@ -20,6 +21,10 @@ pub const RSOL_DECIMALS: usize = 18;
 pub const HASH: &[u8] = b"HASH"; // hash of the journaled accounts
 pub const ALT_SLOTS: &[u8] = b"ALT_SLOTS";
 pub const ALT_OUTDATED_SLOTS_TRACK :usize = 25; 
+pub const BLOCK_GAS_LIMIT: u64 = 30_000_000; // reported by GASLIMIT opcode
+pub const ROLLUP_CONFIG: &[u8] = b"ROLLUP_CONFIG";
+pub const MIN_GAS_PRICE: u64 = 1_000_000_000; // default of the rollup config, 10^(RSOL_DECIMALS - 9)
+pub const MAX_CODE_SIZE: u64 = 0x6000; // default of the rollup config, EIP-170
 
 #[cfg(feature = "ci")]
 pub mod registration_key {
@@ -21,8 +21,9 @@ impl<'a, 'b> ContextAt<'a, 'b> {
 
 impl<'a, 'b> AccountLock for ContextAt<'a, 'b> {
     fn lock(&self) -> Result<()> {
+        let now = self.state.clock()?.unix_timestamp;
         for &info in self.state.all().values() {
-            lock_impl(info, self.state.program_id)?;
+            lock_impl(info, self.state.program_id, self.state.config.lock_duration, now)?;
         }
 
         Ok(())
@@ -41,11 +42,11 @@ impl<'a, 'b> AccountLock for ContextAt<'a, 'b> {
     }
 }
 
-pub fn lock_impl(info: &AccountInfo, program_id: &Pubkey) -> Result<()> {
+pub fn lock_impl(info: &AccountInfo, program_id: &Pubkey, duration: i64, now: i64) -> Result<()> {
     // existings locks can only affect writable accounts of the atomic tx
     if Lock::is_managed(info, program_id)? && info.is_writable {
         let lock = Lock::from_account_mut(info)?;
-        if lock.get()?.is_some() {
+        if lock.get(duration, now)?.is_some() {
             return Err(AccountLocked(*info.key, lock.lock));
         }
     }
@@ -13,6 +13,7 @@ use {
 impl AccountLock for ContextIt<'_, '_> {
     fn lock(&self) -> Result<()> {
         assert!(self.origin_accounts.len() <= 256); // ALT supports up to 256 accounts
+        let now = self.state.clock()?.unix_timestamp;
 
         // TODO: there may be allocation and deallocation together (ro_lock)
         for (index, info) in self.origin_accounts.iter().enumerate() {
@@ -21,7 +22,7 @@ impl AccountLock for ContextIt<'_, '_> {
                 // ro-lock is required
                 if self.lock_overrides.iter().any(|&ro| ro == index as u8) {
                     let ro_lock_info = self.state.info_ro_lock(info.key, true)?;
-                    let push = match lock.get()? {
+                    let push = match lock.get(self.state.config.lock_duration, now)? {
                         Some(LockType::Ro) => {
                             // allocate/resize ro-lock-info
                             if !RoLock::found(ro_lock_info, self.state_holder.key)? {
@@ -43,28 +44,29 @@ impl AccountLock for ContextIt<'_, '_> {
                     };
 
                     // add ro-lock
-                    lock.ro_lock()?;
+                    lock.ro_lock(now);
                     // push holder.key to ro-lock-info
                     if push {
                         RoLock::add_preallocated(ro_lock_info, self.state_holder.key)?;
                     }
                 } else {
                     // writable lock is required
-                    if let Some(lock) = lock.get()? {
+                    if let Some(lock) = lock.get(self.state.config.lock_duration, now)? {
                         return Err(AccountLocked(*info.key, Some(lock)));
                     }
                     // add rw-lock
-                    lock.rw_lock(self.state_holder.key)?;
+                    lock.rw_lock(self.state_holder.key, now);
                 }
             }
         }
         Ok(())
     }
     fn locked(&self) -> Result<bool> {
+        let now = self.state.clock()?.unix_timestamp;
         for info in self.origin_accounts {
             if Lock::is_managed(info, self.state.program_id)? {
                 let mut lock = Lock::from_account_mut(info)?;
-                match lock.get()? {
+                match lock.get(self.state.config.lock_duration, now)? {
                     None => {
                         msg!("account lock not found: {}", info.key);
                         return Ok(false);
@@ -83,18 +85,19 @@ impl AccountLock for ContextIt<'_, '_> {
                         }
                     }
                 }
-                lock.update()?;
+                lock.update(now);
             }
         }
 
         Ok(true)
     }
     fn unlock(&self) -> Result<()> {
+        let now = self.state.clock()?.unix_timestamp;
         for info in self.origin_accounts {
             if Lock::is_managed(info, self.state.program_id)? {
                 let mut lock = Lock::from_account_mut(info)?;
 
-                match lock.get()? {
+                match lock.get(self.state.config.lock_duration, now)? {
                     Some(LockType::Ro) => {
                         let ro_info = self.state.info_ro_lock(info.key, false)?;
 
@@ -137,7 +140,7 @@ impl AccountLock for ContextIt<'_, '_> {
         if Lock::is_managed(info, self.state.program_id)? {
             let mut lock = Lock::from_account_mut(info)?;
             if lock.is_new_one() {
-                lock.rw_lock(self.state_holder.key)?;
+                lock.rw_lock(self.state_holder.key, self.state.clock()?.unix_timestamp);
             }
         }
 
@@ -198,7 +198,166 @@ pub enum RomeProgramError {
     AltSlotAlreadyInUse(u64),
 
     #[error("Too many accounts: {0}")]
-    TooManyAccounts(u64)
+    TooManyAccounts(u64),
+
+    #[error("Invalid precompile input: {0}")]
+    InvalidPrecompileInput(String),
+
+    #[error("Invalid log data: {0}")]
+    InvalidLogData(String),
+
+    #[error("Account layout must be migrated: {0}, version {1}")]
+    OutdatedAccountVersion(Pubkey, u8),
+
+    #[error("Unsupported account layout version: {0}, version {1}")]
+    UnsupportedAccountVersion(Pubkey, u8),
+
+    #[error("Fee recipient is not allowed by the rollup config: {0}")]
+    FeeRecipientNotAllowed(H160),
+
+    #[error("EIP-4844 blob transactions are not supported")]
+    BlobTxNotSupported,
+
+    #[error("The emulation is pinned to slot {0}, the account is read at slot {1}")]
+    ContextSlotMismatch(u64, u64),
+
+    #[error("The invoke of the non-EVM program is prohibited: {0}")]
+    InvokeProhibited(String),
+
+    #[error("The value transfer to the non-EVM program is prohibited: {0}")]
+    TransferProhibited(H160),
+
+    #[error("The non-EVM program is called in the static mode: {0}")]
+    NonEvmStaticModeViolation(H160),
+
+    #[error("The non-EVM program is called by DELEGATECALL or CALLCODE: {0}")]
+    DelegateCallProhibited(H160),
+}
+
+macro_rules! error_codes {
+    { $($(#[$attr:meta])* $variant:ident = $code:literal,)+ } => {
+        /// The stable code of RomeProgramError, it is returned as ProgramError::Custom(code).
+        /// The codes are append-only: the code of the removed variant is never reused
+        #[repr(u32)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum ErrorCode {
+            $($variant = $code,)+
+        }
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)+];
+
+            pub fn from_u32(code: u32) -> Option<Self> {
+                match code {
+                    $($code => Some(ErrorCode::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+
+        impl RomeProgramError {
+            pub fn code(&self) -> ErrorCode {
+                match self {
+                    $($(#[$attr])* RomeProgramError::$variant { .. } => ErrorCode::$variant,)+
+                }
+            }
+        }
+    };
+}
+
+// 0 is the code of the errors of the program versions before the code table
+error_codes! {
+    InvalidMutability = 1,
+    InvalidSigner = 2,
+    InvalidSysvar = 3,
+    InvalidDerive = 4,
+    InvalidOwner = 5,
+    NonWritableAccount = 6,
+    IoError = 7,
+    ProgramError = 8,
+    UnknownInstruction = 9,
+    Custom = 10,
+    InsufficientFunds = 11,
+    InsufficientLamports = 12,
+    RlpDecoderError = 13,
+    InvalidAccountState = 14,
+    InvalidAccountType = 15,
+    InvalidHolderHash = 16,
+    InvalidDataLength = 17,
+    PdaNotFound = 18,
+    StaticModeViolation = 19,
+    DeployContractToExistingAccount = 20,
+    NotImplemented = 21,
+    CalculationOverflow = 22,
+    PubkeyError = 23,
+    AccountNotFound = 24,
+    PdaAccountNotFound = 25,
+    AccountInitialized = 26,
+    InvalidEthereumSignature = 27,
+    InvalidInstructionData = 28,
+    InvalidNonEvmInstructionData = 29,
+    // off-chain only
+    #[cfg(not(target_os = "solana"))]
+    RpcClientError = 30,
+    // off-chain only
+    #[cfg(not(target_os = "solana"))]
+    BincodeError = 31,
+    IncorrectChainId = 32,
+    VmFault = 33,
+    CalculationUnderflow = 34,
+    AccountLocked = 35,
+    AttemptWriteRoAccount = 36,
+    IterationCastError = 37,
+    InvalidTxNonce = 38,
+    AllocationError = 39,
+    Unimplemented = 40,
+    UnnecessaryIteration = 41,
+    ParsePubkeyError = 42,
+    UnregisteredChainId = 43,
+    AccountAlreadyExists = 44,
+    AccountAlreadyInUse = 45,
+    ExternalAccountDataModified = 46,
+    TransferFromAccountWithData = 47,
+    AccountsMismatch = 48,
+    ModifyReadOnlyAccount = 49,
+    InconsistentAccountList = 50,
+    IncorrectRlpType = 51,
+    InvalidDepositInstruction = 52,
+    TxValueNotMultipleOf10_9 = 53,
+    TxValueExceedsU64 = 54,
+    InsufficientGas = 55,
+    InvalidGasPrice = 56,
+    InstructionError = 57,
+    AltSlotAlreadyInUse = 58,
+    TooManyAccounts = 59,
+    InvalidPrecompileInput = 60,
+    InvalidLogData = 61,
+    OutdatedAccountVersion = 62,
+    UnsupportedAccountVersion = 63,
+    FeeRecipientNotAllowed = 64,
+    BlobTxNotSupported = 65,
+    ContextSlotMismatch = 66,
+    InvokeProhibited = 67,
+    TransferProhibited = 68,
+    NonEvmStaticModeViolation = 69,
+    DelegateCallProhibited = 70,
+}
+
+impl ErrorCode {
+    // decodes the error of the failed instruction, TransactionError::InstructionError(_, err)
+    pub fn from_instruction_error(err: &InstructionError) -> Option<Self> {
+        match err {
+            InstructionError::Custom(code) => Self::from_u32(*code),
+            _ => None,
+        }
+    }
+
+    // decodes the error from the log of the failed instruction: "custom program error: 0x1c"
+    pub fn from_log(log: &str) -> Option<Self> {
+        let (_, hex) = log.split_once("custom program error: 0x")?;
+        let hex = hex.split(|c: char| !c.is_ascii_hexdigit()).next()?;
+        u32::from_str_radix(hex, 16).ok().and_then(Self::from_u32)
+    }
 }
 
 impl From<ProgramError> for RomeProgramError {
@@ -217,7 +376,7 @@ impl From<RomeProgramError> for ProgramError {
     fn from(err: RomeProgramError) -> ProgramError {
         match err {
             RomeProgramError::ProgramError(e) => e,
-            _ => ProgramError::Custom(0),
+            err => ProgramError::Custom(err.code() as u32),
         }
     }
 }
@@ -233,3 +392,124 @@ impl From<bincode::Error> for RomeProgramError {
         RomeProgramError::BincodeError(e)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{ErrorCode, RomeProgramError::{self, *}},
+        solana_program::{instruction::InstructionError, program_error::ProgramError},
+    };
+
+    // the published codes, the table may only be extended
+    const TABLE: &[(u32, &str)] = &[
+        (1, "InvalidMutability"),
+        (2, "InvalidSigner"),
+        (3, "InvalidSysvar"),
+        (4, "InvalidDerive"),
+        (5, "InvalidOwner"),
+        (6, "NonWritableAccount"),
+        (7, "IoError"),
+        (8, "ProgramError"),
+        (9, "UnknownInstruction"),
+        (10, "Custom"),
+        (11, "InsufficientFunds"),
+        (12, "InsufficientLamports"),
+        (13, "RlpDecoderError"),
+        (14, "InvalidAccountState"),
+        (15, "InvalidAccountType"),
+        (16, "InvalidHolderHash"),
+        (17, "InvalidDataLength"),
+        (18, "PdaNotFound"),
+        (19, "StaticModeViolation"),
+        (20, "DeployContractToExistingAccount"),
+        (21, "NotImplemented"),
+        (22, "CalculationOverflow"),
+        (23, "PubkeyError"),
+        (24, "AccountNotFound"),
+        (25, "PdaAccountNotFound"),
+        (26, "AccountInitialized"),
+        (27, "InvalidEthereumSignature"),
+        (28, "InvalidInstructionData"),
+        (29, "InvalidNonEvmInstructionData"),
+        (30, "RpcClientError"),
+        (31, "BincodeError"),
+        (32, "IncorrectChainId"),
+        (33, "VmFault"),
+        (34, "CalculationUnderflow"),
+        (35, "AccountLocked"),
+        (36, "AttemptWriteRoAccount"),
+        (37, "IterationCastError"),
+        (38, "InvalidTxNonce"),
+        (39, "AllocationError"),
+        (40, "Unimplemented"),
+        (41, "UnnecessaryIteration"),
+        (42, "ParsePubkeyError"),
+        (43, "UnregisteredChainId"),
+        (44, "AccountAlreadyExists"),
+        (45, "AccountAlreadyInUse"),
+        (46, "ExternalAccountDataModified"),
+        (47, "TransferFromAccountWithData"),
+        (48, "AccountsMismatch"),
+        (49, "ModifyReadOnlyAccount"),
+        (50, "InconsistentAccountList"),
+        (51, "IncorrectRlpType"),
+        (52, "InvalidDepositInstruction"),
+        (53, "TxValueNotMultipleOf10_9"),
+        (54, "TxValueExceedsU64"),
+        (55, "InsufficientGas"),
+        (56, "InvalidGasPrice"),
+        (57, "InstructionError"),
+        (58, "AltSlotAlreadyInUse"),
+        (59, "TooManyAccounts"),
+        (60, "InvalidPrecompileInput"),
+        (61, "InvalidLogData"),
+        (62, "OutdatedAccountVersion"),
+        (63, "UnsupportedAccountVersion"),
+        (64, "FeeRecipientNotAllowed"),
+        (65, "BlobTxNotSupported"),
+        (66, "ContextSlotMismatch"),
+        (67, "InvokeProhibited"),
+        (68, "TransferProhibited"),
+        (69, "NonEvmStaticModeViolation"),
+        (70, "DelegateCallProhibited"),
+    ];
+
+    #[test]
+    fn code_table() {
+        assert_eq!(ErrorCode::ALL.len(), TABLE.len());
+
+        for (code, name) in TABLE {
+            let error = ErrorCode::from_u32(*code).unwrap();
+            assert_eq!(format!("{:?}", error), *name);
+            assert_eq!(error as u32, *code);
+        }
+
+        assert_eq!(ErrorCode::from_u32(0), None);
+        assert_eq!(ErrorCode::from_u32(TABLE.len() as u32 + 1), None);
+    }
+
+    #[test]
+    fn program_error() {
+        assert_eq!(ProgramError::from(InvalidSigner), ProgramError::Custom(2));
+        assert_eq!(
+            ProgramError::from(InsufficientLamports(Default::default(), 1)),
+            ProgramError::Custom(12)
+        );
+        // the solana errors are passed as is
+        assert_eq!(
+            ProgramError::from(RomeProgramError::ProgramError(ProgramError::InvalidArgument)),
+            ProgramError::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn decode() {
+        let code = ErrorCode::from_instruction_error(&InstructionError::Custom(22));
+        assert_eq!(code, Some(ErrorCode::CalculationOverflow));
+        assert_eq!(ErrorCode::from_instruction_error(&InstructionError::InvalidArgument), None);
+
+        let log = "Program 11111111111111111111111111111111 failed: custom program error: 0x23";
+        assert_eq!(ErrorCode::from_log(log), Some(ErrorCode::AccountLocked));
+        assert_eq!(ErrorCode::from_log("Program failed: invalid account data"), None);
+    }
+}
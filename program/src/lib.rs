@@ -2,10 +2,13 @@ pub mod accounts;
 mod alloc;
 pub mod api;
 pub mod assert;
+#[cfg(not(target_os = "solana"))]
+pub mod client;
 mod config;
 pub mod context;
 mod entrypoint;
 pub mod error;
+pub mod log_data;
 pub mod precompile;
 pub mod state;
 pub mod tx;
@@ -16,7 +19,7 @@ pub use accounts::*;
 use api::*;
 pub use assert::*;
 pub use config::*;
-pub use evm::{ExitReason, Valids as EvmValids, H160, H256, U256, Context};
+pub use evm::{ExitError, ExitReason, Valids as EvmValids, H160, H256, U256, Context};
 pub use state::*;
 
 entrypoint! {
@@ -29,4 +32,8 @@ entrypoint! {
     RegOwner => reg_owner,
     AltAlloc => alt_alloc,
     AltDelloc => alt_dealloc,
+    Migrate => migrate,
+    DeregOwner => dereg_owner,
+    SetOwnerKey => set_owner_key,
+    SetRollupConfig => set_rollup_config,
 }
@@ -0,0 +1,487 @@
+// Layouts of the sol_log_data records written by the program.
+// The encoders borrow the fields and are used on-chain.
+// The owned records, the parser and the receipt builder are used by indexers.
+use {
+    crate::config::{EVENT_LOG, EXIT_REASON, GAS_PRICE, GAS_RECIPIENT, GAS_USED, GAS_VALUE},
+    evm::{H160, H256, U256},
+    solana_program::log::sol_log_data,
+    std::mem::size_of,
+};
+
+#[cfg(not(target_os = "solana"))]
+use {
+    crate::error::{Result, RomeProgramError::InvalidLogData},
+    base64::{engine::general_purpose::STANDARD, Engine},
+    solana_program::keccak::hash,
+    std::convert::TryInto,
+};
+
+/// The fields of a single sol_log_data call
+pub type Record = Vec<Vec<u8>>;
+
+pub const PROGRAM_DATA: &str = "Program data: ";
+
+// the records are written on-chain from the borrowed fields, without the copies
+pub fn log_event(address: &H160, topics: &[H256], data: &[u8]) {
+    event_fields(address, topics, data, sol_log_data)
+}
+
+pub fn log_exit(code: u8, reason: &str, return_value: &[u8]) {
+    exit_fields(code, reason, return_value, sol_log_data)
+}
+
+pub fn log_gas(recipient: Option<&H160>, gas_value: &U256, gas_price: &U256) {
+    gas_fields(recipient, gas_value, gas_price, sol_log_data)
+}
+
+pub fn log_gas_used(gas_used: u64) {
+    gas_used_fields(gas_used, sol_log_data)
+}
+
+/// EVENT_LOG | address | topics.len() as u8 | topic * n | data
+fn event_fields<R>(
+    address: &H160,
+    topics: &[H256],
+    data: &[u8],
+    f: impl FnOnce(&[&[u8]]) -> R,
+) -> R {
+    assert!(topics.len() <= 4, "vm fault, event logs topics.len > 4");
+
+    let len = [topics.len() as u8];
+    let mut fields: [&[u8]; 8] = [&[]; 8];
+    fields[0] = EVENT_LOG;
+    fields[1] = address.as_bytes();
+    fields[2] = &len;
+    for (ix, topic) in topics.iter().enumerate() {
+        fields[3 + ix] = topic.as_bytes();
+    }
+    fields[3 + topics.len()] = data;
+
+    f(&fields[..4 + topics.len()])
+}
+
+/// EXIT_REASON | code | reason.len() as usize LE | reason | return_value
+fn exit_fields<R>(
+    code: u8,
+    reason: &str,
+    return_value: &[u8],
+    f: impl FnOnce(&[&[u8]]) -> R,
+) -> R {
+    let len = (reason.len() as u64).to_le_bytes();
+    f(&[EXIT_REASON, &[code], &len, reason.as_bytes(), return_value])
+}
+
+/// GAS_RECIPIENT | address (optional), GAS_VALUE | U256 BE, GAS_PRICE | U256 BE
+fn gas_fields(
+    recipient: Option<&H160>,
+    gas_value: &U256,
+    gas_price: &U256,
+    mut f: impl FnMut(&[&[u8]]),
+) {
+    let mut buf_value = [0_u8; size_of::<U256>()];
+    let mut buf_price = [0_u8; size_of::<U256>()];
+    gas_value.to_big_endian(&mut buf_value);
+    gas_price.to_big_endian(&mut buf_price);
+
+    if let Some(recipient) = recipient {
+        f(&[GAS_RECIPIENT, recipient.as_bytes()]);
+    }
+    f(&[GAS_VALUE, &buf_value]);
+    f(&[GAS_PRICE, &buf_price]);
+}
+
+/// GAS_USED | u64 LE
+fn gas_used_fields<R>(gas_used: u64, f: impl FnOnce(&[&[u8]]) -> R) -> R {
+    f(&[GAS_USED, &gas_used.to_le_bytes()])
+}
+
+#[cfg(not(target_os = "solana"))]
+fn to_record(fields: &[&[u8]]) -> Record {
+    fields.iter().map(|field| field.to_vec()).collect()
+}
+
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvmLog {
+    pub address: H160,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl EvmLog {
+    pub fn record(&self) -> Record {
+        event_fields(&self.address, &self.topics, &self.data, to_record)
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExitStatus {
+    // 0 - succeed, 1 - error, 2 - revert, 3 - fatal
+    pub code: u8,
+    pub reason: String,
+    pub return_value: Vec<u8>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl ExitStatus {
+    pub fn record(&self) -> Record {
+        exit_fields(self.code, &self.reason, &self.return_value, to_record)
+    }
+
+    pub fn is_succeed(&self) -> bool {
+        self.code == 0
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasReceipt {
+    pub recipient: Option<H160>,
+    // the lamports charged, zero if the gas recipient is not set
+    pub gas_value: U256,
+    pub gas_price: U256,
+    // the evm gas used by the tx
+    pub gas_used: u64,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl GasReceipt {
+    pub fn records(&self) -> Vec<Record> {
+        let mut records = vec![];
+        gas_fields(
+            self.recipient.as_ref(),
+            &self.gas_value,
+            &self.gas_price,
+            |fields| records.push(to_record(fields)),
+        );
+        records.push(gas_used_fields(self.gas_used, to_record));
+        records
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogRecord {
+    Event(EvmLog),
+    Exit(ExitStatus),
+    GasRecipient(H160),
+    GasValue(U256),
+    GasPrice(U256),
+    GasUsed(u64),
+}
+
+#[cfg(not(target_os = "solana"))]
+fn field<'a>(record: &'a [Vec<u8>], ix: usize, len: Option<usize>) -> Result<&'a [u8]> {
+    let field = record
+        .get(ix)
+        .ok_or_else(|| InvalidLogData(format!("field {} is missing", ix)))?;
+
+    match len {
+        Some(len) if field.len() != len => Err(InvalidLogData(format!(
+            "field {} length {}, expected {}",
+            ix,
+            field.len(),
+            len
+        ))),
+        _ => Ok(field),
+    }
+}
+
+// returns None for the records of the other programs and for the unknown tags
+#[cfg(not(target_os = "solana"))]
+pub fn parse_record(record: &[Vec<u8>]) -> Result<Option<LogRecord>> {
+    let tag = match record.first() {
+        Some(tag) => tag.as_slice(),
+        None => return Ok(None),
+    };
+
+    let parsed = match tag {
+        EVENT_LOG => {
+            let address = H160::from_slice(field(record, 1, Some(size_of::<H160>()))?);
+            let len = field(record, 2, Some(1))?[0] as usize;
+            if len > 4 || record.len() != len + 4 {
+                return Err(InvalidLogData(format!("event log with {} topics", len)));
+            }
+            let topics = (0..len)
+                .map(|ix| field(record, 3 + ix, Some(size_of::<H256>())).map(H256::from_slice))
+                .collect::<Result<Vec<_>>>()?;
+            let data = field(record, 3 + len, None)?.to_vec();
+
+            LogRecord::Event(EvmLog { address, topics, data })
+        }
+        EXIT_REASON => {
+            let code = field(record, 1, Some(1))?[0];
+            let len = field(record, 2, Some(size_of::<u64>()))?;
+            let len = u64::from_le_bytes(len.try_into().unwrap()) as usize;
+            let reason = field(record, 3, Some(len))?;
+            let reason = String::from_utf8(reason.to_vec())
+                .map_err(|e| InvalidLogData(e.to_string()))?;
+            let return_value = field(record, 4, None)?.to_vec();
+
+            LogRecord::Exit(ExitStatus { code, reason, return_value })
+        }
+        GAS_RECIPIENT => {
+            LogRecord::GasRecipient(H160::from_slice(field(record, 1, Some(size_of::<H160>()))?))
+        }
+        GAS_VALUE => {
+            LogRecord::GasValue(U256::from_big_endian(field(record, 1, Some(size_of::<U256>()))?))
+        }
+        GAS_PRICE => {
+            LogRecord::GasPrice(U256::from_big_endian(field(record, 1, Some(size_of::<U256>()))?))
+        }
+        GAS_USED => {
+            let gas_used = field(record, 1, Some(size_of::<u64>()))?;
+            LogRecord::GasUsed(u64::from_le_bytes(gas_used.try_into().unwrap()))
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(parsed))
+}
+
+// "Program data: <base64> <base64> ..", the empty field is encoded as an empty string
+#[cfg(not(target_os = "solana"))]
+pub fn parse_line(line: &str) -> Result<Option<LogRecord>> {
+    let data = match line.strip_prefix(PROGRAM_DATA) {
+        Some(data) => data,
+        None => return Ok(None),
+    };
+
+    let record = data
+        .split(' ')
+        .map(|field| STANDARD.decode(field).map_err(|e| InvalidLogData(e.to_string())))
+        .collect::<Result<Record>>()?;
+
+    parse_record(&record)
+}
+
+#[cfg(not(target_os = "solana"))]
+pub fn format_line(record: &[Vec<u8>]) -> String {
+    let fields = record.iter().map(|field| STANDARD.encode(field)).collect::<Vec<_>>();
+    format!("{}{}", PROGRAM_DATA, fields.join(" "))
+}
+
+/// The EVM records of the Solana transaction(s) executing one Ethereum transaction.
+/// For the iterative transaction the log messages of all the iterations must be concatenated.
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxLogs {
+    pub logs: Vec<EvmLog>,
+    pub exit: Option<ExitStatus>,
+    pub gas: GasReceipt,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl TxLogs {
+    pub fn parse<S: AsRef<str>>(messages: &[S]) -> Result<Self> {
+        let mut tx = Self::default();
+
+        for message in messages {
+            match parse_line(message.as_ref())? {
+                Some(LogRecord::Event(log)) => tx.logs.push(log),
+                Some(LogRecord::Exit(exit)) => tx.exit = Some(exit),
+                Some(LogRecord::GasRecipient(recipient)) => tx.gas.recipient = Some(recipient),
+                Some(LogRecord::GasValue(value)) => tx.gas.gas_value = value,
+                Some(LogRecord::GasPrice(price)) => tx.gas.gas_price = price,
+                Some(LogRecord::GasUsed(gas_used)) => tx.gas.gas_used = gas_used,
+                None => {}
+            }
+        }
+
+        Ok(tx)
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+pub type Bloom = [u8; 256];
+
+#[cfg(not(target_os = "solana"))]
+pub fn accrue_bloom(bloom: &mut Bloom, input: &[u8]) {
+    let hash = hash(input).to_bytes();
+
+    for i in 0..3 {
+        let bit = (((hash[2 * i] as usize) << 8) | hash[2 * i + 1] as usize) & 0x7ff;
+        bloom[255 - bit / 8] |= 1 << (bit % 8);
+    }
+}
+
+#[cfg(not(target_os = "solana"))]
+pub fn logs_bloom(logs: &[EvmLog]) -> Bloom {
+    let mut bloom = [0_u8; 256];
+
+    for log in logs {
+        accrue_bloom(&mut bloom, log.address.as_bytes());
+        for topic in &log.topics {
+            accrue_bloom(&mut bloom, topic.as_bytes());
+        }
+    }
+
+    bloom
+}
+
+/// Ethereum transaction receipt
+#[cfg(not(target_os = "solana"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Receipt {
+    // 1 - success, 0 - failure
+    pub status: u8,
+    pub gas_used: U256,
+    pub cumulative_gas_used: U256,
+    pub logs_bloom: Bloom,
+    pub logs: Vec<EvmLog>,
+}
+
+// builds the receipts of the transactions in the order of their execution in the block,
+// the gas is the evm gas, not the lamports charged
+#[cfg(not(target_os = "solana"))]
+pub fn receipts(txs: &[TxLogs]) -> Vec<Receipt> {
+    let mut cumulative_gas_used = U256::zero();
+
+    txs.iter()
+        .map(|tx| {
+            let status = tx.exit.as_ref().map_or(false, ExitStatus::is_succeed);
+            // the logs of the failed transaction are discarded
+            let logs = if status { tx.logs.clone() } else { vec![] };
+            let gas_used = U256::from(tx.gas.gas_used);
+            cumulative_gas_used = cumulative_gas_used.saturating_add(gas_used);
+
+            Receipt {
+                status: status as u8,
+                gas_used,
+                cumulative_gas_used,
+                logs_bloom: logs_bloom(&logs),
+                logs,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn event(topics: usize, data: &[u8]) -> EvmLog {
+        EvmLog {
+            address: H160::repeat_byte(0xaa),
+            topics: (0..topics).map(|x| H256::repeat_byte(x as u8 + 1)).collect(),
+            data: data.to_vec(),
+        }
+    }
+
+    fn round_trip(record: &[Vec<u8>]) -> LogRecord {
+        parse_line(&format_line(record)).unwrap().unwrap()
+    }
+
+    #[test]
+    fn event_log() {
+        for topics in 0..=4 {
+            let log = event(topics, &[1, 2, 3]);
+            assert_eq!(round_trip(&log.record()), LogRecord::Event(log));
+        }
+
+        let log = event(2, &[]);
+        assert_eq!(round_trip(&log.record()), LogRecord::Event(log));
+    }
+
+    #[test]
+    fn exit_status() {
+        let exit = ExitStatus {
+            code: 2,
+            reason: "Revert(Reverted)".to_string(),
+            return_value: vec![0x08, 0xc3, 0x79, 0xa0],
+        };
+        assert_eq!(round_trip(&exit.record()), LogRecord::Exit(exit));
+    }
+
+    #[test]
+    fn gas_receipt() {
+        let gas = GasReceipt {
+            recipient: Some(H160::repeat_byte(7)),
+            gas_value: U256::from(25_000),
+            gas_price: U256::exp10(9),
+            gas_used: 21_000,
+        };
+
+        let lines = gas.records().iter().map(|x| format_line(x)).collect::<Vec<_>>();
+        assert_eq!(lines.len(), 4);
+
+        let tx = TxLogs::parse(&lines).unwrap();
+        assert_eq!(tx.gas, gas);
+        assert!(tx.logs.is_empty());
+        assert!(tx.exit.is_none());
+    }
+
+    #[test]
+    fn foreign_lines() {
+        assert_eq!(parse_line("Program log: Instruction: Transfer").unwrap(), None);
+        assert_eq!(parse_line(&format_line(&[b"HASH".to_vec(), vec![0; 32]])).unwrap(), None);
+        assert!(parse_line(&format_line(&[EVENT_LOG.to_vec(), vec![0; 3]])).is_err());
+        assert!(parse_line("Program data: ###").is_err());
+    }
+
+    // the test vector of the ethbloom crate
+    #[test]
+    fn bloom() {
+        let expected = hex::decode(concat!(
+            "00000000000000000000000000000000",
+            "00000000100000000000000000000000",
+            "00000000000000000000000000000000",
+            "00000000000000000000000000000000",
+            "00000000000000000000000000000000",
+            "00000000000000000000000000000000",
+            "00000002020000000000000000000000",
+            "00000000000000000000000800000000",
+            "10000000000000000000000000000000",
+            "00000000000000000000001000000000",
+            "00000000000000000000000000000000",
+            "00000000000000000000000000000000",
+            "00000000000000000000000000000000",
+            "00000000000000000000000000000000",
+            "00000000000000000000000000000000",
+            "00000000000000000000000000000000",
+        ))
+        .unwrap();
+        let log = EvmLog {
+            address: H160::from_slice(&hex::decode("ef2d6d194084c2de36e0dabfce45d046b37d1106").unwrap()),
+            topics: vec![H256::from_slice(
+                &hex::decode("02c69be41d0b7e40352fc85be1cd65eb03d40ef8427a0ca4596b1ead9a00e9fc").unwrap(),
+            )],
+            data: vec![1, 2, 3],
+        };
+
+        assert_eq!(logs_bloom(&[log]).to_vec(), expected);
+        assert_eq!(logs_bloom(&[]), [0_u8; 256]);
+    }
+
+    #[test]
+    fn receipt() {
+        let exit = |code| ExitStatus { code, ..Default::default() };
+        // the lamports charged differ from the evm gas
+        let gas = |used: u64| GasReceipt { gas_value: (used * 7).into(), gas_used: used, ..Default::default() };
+
+        let mut lines = vec![];
+        lines.push(format_line(&event(1, &[5]).record()));
+        lines.extend(gas(30_000).records().iter().map(|x| format_line(x)));
+        lines.push(format_line(&exit(0).record()));
+        let succeed = TxLogs::parse(&lines).unwrap();
+
+        let mut lines = vec![format_line(&event(2, &[]).record())];
+        lines.extend(gas(25_000).records().iter().map(|x| format_line(x)));
+        lines.push(format_line(&exit(2).record()));
+        let reverted = TxLogs::parse(&lines).unwrap();
+
+        let receipts = receipts(&[succeed.clone(), reverted]);
+
+        assert_eq!(receipts[0].status, 1);
+        assert_eq!(receipts[0].logs, succeed.logs);
+        assert_eq!(receipts[0].logs_bloom, logs_bloom(&succeed.logs));
+        assert_eq!(receipts[0].cumulative_gas_used, U256::from(30_000));
+
+        assert_eq!(receipts[1].status, 0);
+        assert!(receipts[1].logs.is_empty());
+        assert_eq!(receipts[1].gas_used, U256::from(25_000));
+        assert_eq!(receipts[1].cumulative_gas_used, U256::from(55_000));
+    }
+}
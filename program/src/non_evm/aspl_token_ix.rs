@@ -2,7 +2,7 @@ use {
     solana_program::{
         instruction::{Instruction, AccountMeta,}, pubkey::Pubkey, program_pack::Pack,
         system_program,
-        rent::Rent, sysvar::Sysvar, system_instruction::create_account,
+        system_instruction::create_account,
     },
     crate::{
         error::Result, origin::Origin, error::RomeProgramError::*, non_evm::Bind,
@@ -58,7 +58,7 @@ impl Create {
         }
 
         let len = spl_token::state::Account::LEN;
-        let rent = Rent::get()?.minimum_balance(len);
+        let rent = state.base().rent()?.minimum_balance(len);
 
         let ix = create_account(&signer, &new, rent, len as u64, &spl_program);
         System::new(state).emulate(&ix, binds)?;
@@ -0,0 +1,266 @@
+use {
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+    },
+    crate::{
+        H160, U256, pda::Seed, error::{Result, RomeProgramError::*}, origin::Origin,
+        non_evm::NonEvmState, len_ge,
+    },
+    super::{Program, Bind, EvmDiff, get_account_mut, get_pubkey},
+    evm::Context,
+};
+
+//  0x7480cb86       invoke(bytes32,(bytes32,bool,bool)[],bytes)   program_id, metas, data
+
+pub const INVOKE_ID: &[u8] = &[0x74, 0x80, 0xcb, 0x86];
+// (bytes32 pubkey, bool is_signer, bool is_writable)
+const META_LEN: usize = 32 * 3;
+
+/// The generic CPI: invokes an arbitrary Solana program on behalf of the caller.
+/// The instruction is signed by the balance PDA of the caller
+pub struct Invoke<'a, T: Origin> {
+    state: &'a T,
+}
+
+impl<'a, T: Origin> Invoke<'a, T> {
+    pub const ADDRESS: H160 = H160([
+        0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x08,
+    ]);
+    pub fn new(state: &'a T) -> Self {
+        Self {
+            state
+        }
+    }
+}
+
+impl<'a, T: Origin> Program for Invoke<'a, T> {
+    // the program is not executed by rome-evm, the declared post-state of the accounts is applied if any.
+    // on-chain there are no hints, it is a no-op: the real program is invoked when the journal is applied
+    fn emulate(&self, ix: &Instruction, binds: &mut Vec<Bind>) -> Result<()>  {
+        for meta in ix.accounts.iter() {
+            if let Some(hint) = self.state.invoke_hint(&ix.program_id, &meta.pubkey) {
+                if !meta.is_writable {
+                    return Err(ModifyReadOnlyAccount(meta.pubkey))
+                }
+
+                let acc = get_account_mut(&meta.pubkey, binds)?;
+                acc.lamports = hint.lamports;
+                acc.data = hint.data;
+                acc.owner = hint.owner;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn ix_from_abi(&self, abi: &[u8], context: &Context) -> Result<(Instruction, Seed, Vec<EvmDiff>)> {
+        len_ge!(abi, 4);
+        let (func, rest) = abi.split_at(4);
+        match func {
+            INVOKE_ID => {
+                let ix = decode_invoke(rest)?;
+                let base = self.state.base();
+                let (auth, seed) = base.pda.balance_key(&context.caller);
+                check_invoke(&ix, &auth, base.program_id)?;
+
+                // the program must be in the accounts of the transaction
+                let program = self.state.account(&ix.program_id)?;
+                if !program.executable {
+                    return Err(InvokeProhibited(format!("{} is not executable", ix.program_id)))
+                }
+                self.state.track_invoke(&ix.program_id);
+
+                Ok((ix, seed, vec![]))
+            },
+            _ => Err(Unimplemented(format!("method is not supported by InvokeProgram {}", hex::encode(func))))
+        }
+    }
+
+    fn eth_call(&self, _: &[u8], _: &NonEvmState) -> Result<Vec<u8>> {
+        Err(Unimplemented("eth_call is not supported by InvokeProgram".to_string()))
+    }
+
+    fn found_eth_call(&self, _: &[u8]) -> bool {
+        false
+    }
+
+    fn transfer_allowed(&self) -> bool {
+        false
+    }
+}
+
+// program_id | offset of metas | offset of data | len | metas | len | data
+// the offsets are counted from the program_id
+pub fn decode_invoke(abi: &[u8]) -> Result<Instruction> {
+    let program_id = get_pubkey(abi)?;
+
+    let offset = usize_at(abi, 32)?;
+    let len = usize_at(abi, offset)?;
+    let metas = len
+        .checked_mul(META_LEN)
+        .and_then(|len| abi.get(offset + 32..)?.get(..len))
+        .ok_or(InvalidNonEvmInstructionData)?;
+
+    let accounts = metas
+        .chunks(META_LEN)
+        .map(decode_meta)
+        .collect::<Result<Vec<_>>>()?;
+
+    let offset = usize_at(abi, 64)?;
+    let len = usize_at(abi, offset)?;
+    let data = abi
+        .get(offset + 32..)
+        .and_then(|data| data.get(..len))
+        .ok_or(InvalidNonEvmInstructionData)?;
+
+    Ok(Instruction {
+        program_id,
+        accounts,
+        data: data.to_vec(),
+    })
+}
+
+// the contract signs by the pda of its own only, the reentrancy to rome-evm is prohibited
+pub fn check_invoke(ix: &Instruction, auth: &Pubkey, program_id: &Pubkey) -> Result<()> {
+    if ix.program_id == *program_id {
+        return Err(InvokeProhibited(format!("reentrancy to {}", program_id)))
+    }
+
+    if let Some(meta) = ix.accounts.iter().find(|meta| meta.is_signer && meta.pubkey != *auth) {
+        return Err(InvokeProhibited(format!("{} is not the signer of the caller", meta.pubkey)))
+    }
+
+    Ok(())
+}
+
+fn decode_meta(abi: &[u8]) -> Result<AccountMeta> {
+    Ok(AccountMeta {
+        pubkey: get_pubkey(abi)?,
+        is_signer: bool_at(abi, 32)?,
+        is_writable: bool_at(abi, 64)?,
+    })
+}
+
+// the offsets and the lengths can not exceed the length of abi
+fn usize_at(abi: &[u8], offset: usize) -> Result<usize> {
+    let word = abi
+        .get(offset..)
+        .and_then(|word| word.get(..32))
+        .map(U256::from_big_endian)
+        .ok_or(InvalidNonEvmInstructionData)?;
+
+    if word > U256::from(abi.len()) {
+        return Err(InvalidNonEvmInstructionData)
+    }
+
+    Ok(word.as_usize())
+}
+
+fn bool_at(abi: &[u8], offset: usize) -> Result<bool> {
+    let word = abi
+        .get(offset..)
+        .and_then(|word| word.get(..32))
+        .map(U256::from_big_endian)
+        .ok_or(InvalidNonEvmInstructionData)?;
+
+    match word {
+        _ if word.is_zero() => Ok(false),
+        _ if word == U256::one() => Ok(true),
+        _ => Err(InvalidNonEvmInstructionData),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{check_invoke, decode_invoke},
+        crate::{error::RomeProgramError::*, U256},
+        solana_program::{instruction::AccountMeta, pubkey::Pubkey},
+    };
+
+    fn word(value: usize) -> Vec<u8> {
+        let mut buf = vec![0_u8; 32];
+        U256::from(value).to_big_endian(&mut buf);
+        buf
+    }
+
+    fn encode(program_id: &Pubkey, metas: &[AccountMeta], data: &[u8]) -> Vec<u8> {
+        let mut abi = program_id.to_bytes().to_vec();
+        abi.extend(word(32 * 3));
+        abi.extend(word(32 * 4 + metas.len() * 32 * 3));
+
+        abi.extend(word(metas.len()));
+        for meta in metas {
+            abi.extend(meta.pubkey.to_bytes());
+            abi.extend(word(meta.is_signer as usize));
+            abi.extend(word(meta.is_writable as usize));
+        }
+
+        abi.extend(word(data.len()));
+        let mut data = data.to_vec();
+        data.resize((data.len() + 31) / 32 * 32, 0);
+        abi.extend(data);
+        abi
+    }
+
+    #[test]
+    fn decode() {
+        let program_id = Pubkey::new_unique();
+        let metas = vec![
+            AccountMeta::new(Pubkey::new_unique(), true),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+        ];
+        let data = b"hello from evm".to_vec();
+
+        let ix = decode_invoke(&encode(&program_id, &metas, &data)).unwrap();
+        assert_eq!(ix.program_id, program_id);
+        assert_eq!(ix.accounts, metas);
+        assert_eq!(ix.data, data);
+
+        let ix = decode_invoke(&encode(&program_id, &[], &[])).unwrap();
+        assert!(ix.accounts.is_empty());
+        assert!(ix.data.is_empty());
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let program_id = Pubkey::new_unique();
+        let metas = vec![AccountMeta::new(Pubkey::new_unique(), false)];
+        let abi = encode(&program_id, &metas, b"data");
+
+        // truncated
+        assert!(matches!(decode_invoke(&abi[..abi.len() - 64]), Err(InvalidNonEvmInstructionData)));
+        // the length of metas exceeds the input
+        let mut bad = abi.clone();
+        bad[96..128].copy_from_slice(&word(1000));
+        assert!(matches!(decode_invoke(&bad), Err(InvalidNonEvmInstructionData)));
+        // is_signer is not a bool
+        let mut bad = abi.clone();
+        bad[160..192].copy_from_slice(&word(2));
+        assert!(matches!(decode_invoke(&bad), Err(InvalidNonEvmInstructionData)));
+    }
+
+    #[test]
+    fn check() {
+        let rome_evm = Pubkey::new_unique();
+        let auth = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+
+        let metas = vec![
+            AccountMeta::new(auth, true),
+            AccountMeta::new(Pubkey::new_unique(), false),
+        ];
+        let abi = encode(&program_id, &metas, &[]);
+        let ix = decode_invoke(&abi).unwrap();
+        assert!(check_invoke(&ix, &auth, &rome_evm).is_ok());
+
+        // the foreign signer, e.g. the operator
+        let metas = vec![AccountMeta::new(Pubkey::new_unique(), true)];
+        let ix = decode_invoke(&encode(&program_id, &metas, &[])).unwrap();
+        assert!(matches!(check_invoke(&ix, &auth, &rome_evm), Err(InvokeProhibited(_))));
+
+        let ix = decode_invoke(&encode(&rome_evm, &[], &[])).unwrap();
+        assert!(matches!(check_invoke(&ix, &auth, &rome_evm), Err(InvokeProhibited(_))));
+    }
+}
@@ -7,6 +7,7 @@ pub mod aspl_token_ix;
 pub mod spl_token_ix;
 pub mod aux;
 mod withdraw;
+mod invoke;
 
 pub use {
     spl_token::SplToken,
@@ -15,6 +16,7 @@ pub use {
     system::System,
     system_ix::{CreateA, Allocate, Assign, Transfer,},
     withdraw::Withdraw,
+    invoke::Invoke,
     non_evm_state::{NonEvmState, Bind,},
     aux::{
         len_ge, len_eq, next, get_vec_slices, get_pubkey, get_account_mut,
@@ -35,4 +37,12 @@ pub trait Program {
     fn emulate(&self, _ix: &Instruction, _: &mut Vec<Bind>) -> Result<()>;
     fn found_eth_call(&self, _: &[u8]) -> bool;
     fn transfer_allowed(&self) -> bool;
+    // the evm gas of the call, the solana programs are paid by the compute units of the tx
+    fn gas(&self, _input: &[u8]) -> u64 {
+        0
+    }
+    // the standard precompiles fail without the revert data, the non-evm programs revert
+    fn is_precompile(&self) -> bool {
+        false
+    }
 }
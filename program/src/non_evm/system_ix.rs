@@ -5,7 +5,6 @@ use {
             create_account, allocate, assign, transfer,
         },
         pubkey::Pubkey, system_program,  instruction::Instruction,
-        rent::Rent, sysvar::Sysvar,
     },
     crate::{
         error::{Result, RomeProgramError::*,}, U256, origin::Origin,
@@ -37,7 +36,7 @@ impl CreateA {
             .pda
             .from_balance_key(&from, salt);
 
-        let rent = Rent::get()?.minimum_balance(len);
+        let rent = state.base().rent()?.minimum_balance(len);
 
         let ix = create_account(&state.signer(), &auth, rent, len as u64, &owner);
         Ok((ix, seed))
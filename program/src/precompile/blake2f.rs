@@ -2,7 +2,11 @@ use {
     evm::H160, solana_program::msg, std::convert::TryInto, super::impl_contract,
 };
 
-impl_contract!(Blake2f, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9,]);
+impl_contract!(
+    Blake2f,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9,],
+    gas = gas
+);
 
 const SIGMA: [[usize; 16]; 10] = [
     [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
@@ -53,6 +57,16 @@ fn round(i: u32, v: &mut [u64; 16], m: &[u64]) {
     g(v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
 }
 
+// EIP-152: 1 gas per round, the invalid input fails without the charge
+fn gas(input: &[u8]) -> u64 {
+    match input.get(..4) {
+        Some(rounds) if input.len() == BLAKE2_INPUT_LEN => {
+            u32::from_be_bytes(rounds.try_into().unwrap()) as u64
+        }
+        _ => 0,
+    }
+}
+
 fn contract(input: &[u8]) -> Vec<u8> {
     msg!("blake2f");
     if input.len() != BLAKE2_INPUT_LEN {
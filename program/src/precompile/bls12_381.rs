@@ -0,0 +1,190 @@
+// EIP-2537 encoding of the BLS12-381 field elements and points.
+// Solana doesn't provide BLS12-381 syscalls, the arithmetic is implemented by arkworks.
+use {
+    crate::error::{Result, RomeProgramError},
+    ark_bls12_381::{Fq, Fq2, Fr, G1Affine, G2Affine},
+    ark_ff::{BigInteger, PrimeField, Zero},
+};
+
+pub const FP_LEN: usize = 64;
+pub const FP2_LEN: usize = 2 * FP_LEN;
+pub const G1_LEN: usize = 2 * FP_LEN;
+pub const G2_LEN: usize = 2 * FP2_LEN;
+pub const SCALAR_LEN: usize = 32;
+
+const FP_PADDING: usize = 16;
+
+// EIP-2537 gas schedule
+pub const G1ADD_GAS: u64 = 375;
+pub const G2ADD_GAS: u64 = 600;
+pub const G1MUL_GAS: u64 = 12_000;
+pub const G2MUL_GAS: u64 = 22_500;
+pub const PAIRING_BASE_GAS: u64 = 37_700;
+pub const PAIRING_PAIR_GAS: u64 = 32_600;
+pub const MAP_FP_TO_G1_GAS: u64 = 5_500;
+pub const MAP_FP2_TO_G2_GAS: u64 = 23_800;
+
+// the MSM discounts per the number of pairs, the last one is applied to the bigger inputs
+pub const G1MSM_DISCOUNT: [u64; 128] = [
+    1000, 949, 848, 797, 764, 750, 738, 728, 719, 712, 705, 698, 692, 687, 682, 677,
+    673, 669, 665, 661, 658, 654, 651, 648, 645, 642, 640, 637, 635, 632, 630, 627,
+    625, 623, 621, 619, 617, 615, 613, 611, 609, 608, 606, 604, 603, 601, 599, 598,
+    596, 595, 593, 592, 591, 589, 588, 586, 585, 584, 582, 581, 580, 579, 577, 576,
+    575, 574, 573, 572, 570, 569, 568, 567, 566, 565, 564, 563, 562, 561, 560, 559,
+    558, 557, 556, 555, 554, 553, 552, 551, 550, 549, 548, 547, 547, 546, 545, 544,
+    543, 542, 541, 540, 540, 539, 538, 537, 536, 536, 535, 534, 533, 532, 532, 531,
+    530, 529, 528, 528, 527, 526, 525, 525, 524, 523, 522, 522, 521, 520, 520, 519,
+];
+pub const G2MSM_DISCOUNT: [u64; 128] = [
+    1000, 1000, 923, 884, 855, 832, 812, 796, 782, 770, 759, 749, 740, 732, 724, 717,
+    711, 704, 699, 693, 688, 683, 679, 674, 670, 666, 663, 659, 655, 652, 649, 646,
+    643, 640, 637, 634, 632, 629, 627, 624, 622, 620, 618, 615, 613, 611, 609, 607,
+    606, 604, 602, 600, 598, 597, 595, 593, 592, 590, 589, 587, 586, 584, 583, 582,
+    580, 579, 578, 576, 575, 574, 573, 571, 570, 569, 568, 567, 566, 565, 563, 562,
+    561, 560, 559, 558, 557, 556, 555, 554, 553, 552, 552, 551, 550, 549, 548, 547,
+    546, 545, 545, 544, 543, 542, 541, 541, 540, 539, 538, 537, 537, 536, 535, 535,
+    534, 533, 532, 532, 531, 530, 530, 529, 528, 528, 527, 526, 526, 525, 524, 524,
+];
+
+// k * multiplication * discount(k) / 1000
+pub fn msm_gas(input: &[u8], pair_len: usize, multiplication: u64, discounts: &[u64]) -> u64 {
+    let k = input.len() / pair_len;
+    if k == 0 {
+        return 0;
+    }
+
+    let discount = discounts.get(k - 1).or_else(|| discounts.last()).cloned().unwrap_or(1000);
+    (k as u64).saturating_mul(multiplication).saturating_mul(discount) / 1000
+}
+
+pub fn invalid(msg: &str) -> RomeProgramError {
+    RomeProgramError::InvalidPrecompileInput(msg.to_string())
+}
+
+// the field element is 48 bytes big-endian padded with 16 zero bytes
+pub fn decode_fp(bytes: &[u8]) -> Result<Fq> {
+    if bytes.len() != FP_LEN {
+        return Err(invalid("invalid field element length"));
+    }
+    let (padding, bytes) = bytes.split_at(FP_PADDING);
+
+    if padding.iter().any(|&x| x != 0) {
+        return Err(invalid("invalid field element padding"));
+    }
+
+    let fp = Fq::from_be_bytes_mod_order(bytes);
+    if fp.into_bigint().to_bytes_be() != bytes {
+        return Err(invalid("field element is not canonical"));
+    }
+
+    Ok(fp)
+}
+
+pub fn decode_fp2(bytes: &[u8]) -> Result<Fq2> {
+    if bytes.len() < FP2_LEN {
+        return Err(invalid("invalid fp2 element length"));
+    }
+    let c0 = decode_fp(&bytes[..FP_LEN])?;
+    let c1 = decode_fp(&bytes[FP_LEN..FP2_LEN])?;
+    Ok(Fq2::new(c0, c1))
+}
+
+pub fn decode_g1(bytes: &[u8], subgroup_check: bool) -> Result<G1Affine> {
+    if bytes.len() < G1_LEN {
+        return Err(invalid("invalid G1 point length"));
+    }
+    let x = decode_fp(&bytes[..FP_LEN])?;
+    let y = decode_fp(&bytes[FP_LEN..G1_LEN])?;
+
+    if x.is_zero() && y.is_zero() {
+        return Ok(G1Affine::identity());
+    }
+
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(invalid("G1 point is not on curve"));
+    }
+    if subgroup_check && !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(invalid("G1 point is not in the subgroup"));
+    }
+
+    Ok(point)
+}
+
+pub fn decode_g2(bytes: &[u8], subgroup_check: bool) -> Result<G2Affine> {
+    if bytes.len() < G2_LEN {
+        return Err(invalid("invalid G2 point length"));
+    }
+    let x = decode_fp2(&bytes[..FP2_LEN])?;
+    let y = decode_fp2(&bytes[FP2_LEN..G2_LEN])?;
+
+    if x.is_zero() && y.is_zero() {
+        return Ok(G2Affine::identity());
+    }
+
+    let point = G2Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(invalid("G2 point is not on curve"));
+    }
+    if subgroup_check && !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(invalid("G2 point is not in the subgroup"));
+    }
+
+    Ok(point)
+}
+
+// the scalar is not required to be less than the group order
+pub fn decode_scalar(bytes: &[u8]) -> Fr {
+    Fr::from_be_bytes_mod_order(&bytes[..SCALAR_LEN])
+}
+
+fn encode_fp(fp: &Fq, into: &mut Vec<u8>) {
+    into.extend_from_slice(&[0; FP_PADDING]);
+    into.extend_from_slice(&fp.into_bigint().to_bytes_be());
+}
+
+pub fn encode_g1(point: &G1Affine) -> Vec<u8> {
+    if point.infinity {
+        return vec![0; G1_LEN];
+    }
+
+    let mut buf = Vec::with_capacity(G1_LEN);
+    encode_fp(&point.x, &mut buf);
+    encode_fp(&point.y, &mut buf);
+    buf
+}
+
+pub fn encode_g2(point: &G2Affine) -> Vec<u8> {
+    if point.infinity {
+        return vec![0; G2_LEN];
+    }
+
+    let mut buf = Vec::with_capacity(G2_LEN);
+    encode_fp(&point.x.c0, &mut buf);
+    encode_fp(&point.x.c1, &mut buf);
+    encode_fp(&point.y.c0, &mut buf);
+    encode_fp(&point.y.c1, &mut buf);
+    buf
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode_fp, msm_gas, G1MSM_DISCOUNT, G1MUL_GAS, G2MSM_DISCOUNT, G2MUL_GAS};
+
+    #[test]
+    fn fp_length() {
+        assert!(decode_fp(&[0; 48]).is_err());
+        assert!(decode_fp(&[0; 64]).is_ok());
+    }
+
+    #[test]
+    fn msm() {
+        // G1: 160 bytes per pair, G2: 288 bytes per pair
+        assert_eq!(msm_gas(&[0; 160], 160, G1MUL_GAS, &G1MSM_DISCOUNT), 12_000);
+        assert_eq!(msm_gas(&[0; 320], 160, G1MUL_GAS, &G1MSM_DISCOUNT), 2 * 12_000 * 949 / 1000);
+        assert_eq!(msm_gas(&[0; 288 * 2], 288, G2MUL_GAS, &G2MSM_DISCOUNT), 2 * 22_500);
+        // the maximal discount
+        assert_eq!(msm_gas(&vec![0; 160 * 200], 160, G1MUL_GAS, &G1MSM_DISCOUNT), 200 * 12_000 * 519 / 1000);
+        assert_eq!(msm_gas(&[], 160, G1MUL_GAS, &G1MSM_DISCOUNT), 0);
+    }
+}
@@ -0,0 +1,97 @@
+use {
+    super::{bls12_381::*, impl_contract},
+    evm::H160,
+    solana_program::msg,
+};
+
+impl_contract!(
+    Bls12G1Add,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 11,],
+    fallible,
+    gas = |_: &[u8]| G1ADD_GAS
+);
+
+// the subgroup check is not required for the addition
+fn contract(input: &[u8]) -> Result<Vec<u8>> {
+    msg!("bls12 g1add");
+    if input.len() != 2 * G1_LEN {
+        return Err(invalid("bls12 g1add input length"));
+    }
+
+    let a = decode_g1(&input[..G1_LEN], false)?;
+    let b = decode_g1(&input[G1_LEN..], false)?;
+
+    Ok(encode_g1(&(a + b).into()))
+}
+
+#[cfg(test)]
+mod test {
+    // Test vectors are from https://github.com/ethereum/EIPs/tree/master/assets/eip-2537
+
+    use crate::precompile::bls12_g1add::contract;
+
+    fn test_case(input_hex: &str, expected_hex: &str) {
+        assert_eq!(
+            contract(hex::decode(input_hex).unwrap().as_slice()).unwrap(),
+            hex::decode(expected_hex).unwrap()
+        )
+    }
+
+    fn fail_case(input_hex: &str) {
+        assert!(contract(hex::decode(input_hex).unwrap().as_slice()).is_err())
+    }
+
+    #[test]
+    fn g1_plus_p1() {
+        test_case(
+            "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+            0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1\
+            00000000000000000000000000000000112b98340eee2777cc3c14163dea3ec97977ac3dc5c70da32e6e87578f44912e902ccef9efe28d4a78b8999dfbca9426\
+            00000000000000000000000000000000186b28d92356c4dfec4b5201ad099dbdede3781f8998ddf929b4cd7756192185ca7b8f4ef7088f813270ac3d48868a21",
+            "000000000000000000000000000000000a40300ce2dec9888b60690e9a41d3004fda4886854573974fab73b046d3147ba5b7a5bde85279ffede1b45b3918d82d\
+            0000000000000000000000000000000006d3d887e9f53b9ec4eb6cedf5607226754b07c01ace7834f57f3e7315faefb739e59018e22c492006190fba4a870025",
+        )
+    }
+
+    #[test]
+    fn g1_minus_g1() {
+        test_case(
+            "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+            0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1\
+            0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+            00000000000000000000000000000000114d1d6855d545a8aa7d76c8cf2e21f267816aef1db507c96655b9d5caac42364e6f38ba0ecb751bad54dcd6b939c2ca",
+            "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000\
+            00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        )
+    }
+
+    #[test]
+    fn empty_input() {
+        // invalid input length
+        fail_case(
+            "",
+        )
+    }
+
+    #[test]
+    fn not_on_curve() {
+        // invalid point: not on curve
+        fail_case(
+            "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+            00000000000000000000000000000000186b28d92356c4dfec4b5201ad099dbdede3781f8998ddf929b4cd7756192185ca7b8f4ef7088f813270ac3d48868a21\
+            00000000000000000000000000000000112b98340eee2777cc3c14163dea3ec97977ac3dc5c70da32e6e87578f44912e902ccef9efe28d4a78b8999dfbca9426\
+            00000000000000000000000000000000186b28d92356c4dfec4b5201ad099dbdede3781f8998ddf929b4cd7756192185ca7b8f4ef7088f813270ac3d48868a21",
+        )
+    }
+
+    #[test]
+    fn top_bytes() {
+        // invalid field element top bytes
+        fail_case(
+            "1000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+            0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1\
+            00000000000000000000000000000000112b98340eee2777cc3c14163dea3ec97977ac3dc5c70da32e6e87578f44912e902ccef9efe28d4a78b8999dfbca9426\
+            00000000000000000000000000000000186b28d92356c4dfec4b5201ad099dbdede3781f8998ddf929b4cd7756192185ca7b8f4ef7088f813270ac3d48868a21",
+        )
+    }
+}
@@ -0,0 +1,111 @@
+use {
+    super::{bls12_381::*, impl_contract},
+    ark_bls12_381::G1Projective,
+    ark_ec::{CurveGroup, VariableBaseMSM},
+    evm::H160,
+    solana_program::msg,
+};
+
+impl_contract!(
+    Bls12G1Msm,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 12,],
+    fallible,
+    gas = |input: &[u8]| msm_gas(input, PAIR_LEN, G1MUL_GAS, &G1MSM_DISCOUNT)
+);
+
+const PAIR_LEN: usize = G1_LEN + SCALAR_LEN;
+
+// (point | scalar) * k
+fn contract(input: &[u8]) -> Result<Vec<u8>> {
+    msg!("bls12 g1msm");
+    if input.is_empty() || input.len() % PAIR_LEN != 0 {
+        return Err(invalid("bls12 g1msm input length"));
+    }
+
+    let mut bases = Vec::with_capacity(input.len() / PAIR_LEN);
+    let mut scalars = Vec::with_capacity(input.len() / PAIR_LEN);
+
+    for pair in input.chunks(PAIR_LEN) {
+        bases.push(decode_g1(&pair[..G1_LEN], true)?);
+        scalars.push(decode_scalar(&pair[G1_LEN..]));
+    }
+
+    let result = G1Projective::msm(&bases, &scalars)
+        .map_err(|_| invalid("bls12 g1msm length mismatch"))?;
+
+    Ok(encode_g1(&result.into_affine()))
+}
+
+#[cfg(test)]
+mod test {
+    // Test vectors are from https://github.com/ethereum/EIPs/tree/master/assets/eip-2537
+
+    use crate::precompile::bls12_g1msm::contract;
+
+    fn test_case(input_hex: &str, expected_hex: &str) {
+        assert_eq!(
+            contract(hex::decode(input_hex).unwrap().as_slice()).unwrap(),
+            hex::decode(expected_hex).unwrap()
+        )
+    }
+
+    fn fail_case(input_hex: &str) {
+        assert!(contract(hex::decode(input_hex).unwrap().as_slice()).is_err())
+    }
+
+    #[test]
+    fn two_pairs() {
+        test_case(
+            "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+            0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1\
+            000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000112b98340eee2777cc3c14163dea3ec9\
+            7977ac3dc5c70da32e6e87578f44912e902ccef9efe28d4a78b8999dfbca942600000000000000000000000000000000186b28d92356c4dfec4b5201ad099dbd\
+            ede3781f8998ddf929b4cd7756192185ca7b8f4ef7088f813270ac3d48868a210000000000000000000000000000000000000000000000000000000000000002",
+            "00000000000000000000000000000000148f92dced907361b4782ab542a75281d4b6f71f65c8abf94a5a9082388c64662d30fd6a01ced724feef3e284752038c\
+            0000000000000000000000000000000015c3634c3b67bc18e19150e12bfd8a1769306ed010f59be645a0823acb5b38f39e8e0d86e59b6353fdafc59ca971b769",
+        )
+    }
+
+    #[test]
+    fn zero_scalar() {
+        test_case(
+            "0000000000000000000000000000000017f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb\
+            0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1\
+            0000000000000000000000000000000000000000000000000000000000000000",
+            "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000\
+            00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        )
+    }
+
+    #[test]
+    fn empty_input() {
+        // invalid input length
+        fail_case(
+            "",
+        )
+    }
+
+    #[test]
+    fn invalid_field_element() {
+        // invalid fp.Element encoding
+        fail_case(
+            "0000000000000000000000000000000031f2e5916b17be2e71b10b4292f558e727dfd7d48af9cbc5087f0ce00dcca27c8b01e83eaace1aefb539f00adb227166\
+            0000000000000000000000000000000008b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1\
+            000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000112b98340eee2777cc3c14163dea3ec9\
+            7977ac3dc5c70da32e6e87578f44912e902ccef9efe28d4a78b8999dfbca942600000000000000000000000000000000186b28d92356c4dfec4b5201ad099dbd\
+            ede3781f8998ddf929b4cd7756192185ca7b8f4ef7088f813270ac3d48868a210000000000000000000000000000000000000000000000000000000000000002",
+        )
+    }
+
+    #[test]
+    fn not_in_subgroup() {
+        // g1 point is not on correct subgroup
+        fail_case(
+            "000000000000000000000000000000000123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef\
+            00000000000000000000000000000000193fb7cedb32b2c3adc06ec11a96bc0d661869316f5e4a577a9f7c179593987beb4fb2ee424dbb2f5dd891e228b46c4a\
+            000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000112b98340eee2777cc3c14163dea3ec9\
+            7977ac3dc5c70da32e6e87578f44912e902ccef9efe28d4a78b8999dfbca942600000000000000000000000000000000186b28d92356c4dfec4b5201ad099dbd\
+            ede3781f8998ddf929b4cd7756192185ca7b8f4ef7088f813270ac3d48868a210000000000000000000000000000000000000000000000000000000000000002",
+        )
+    }
+}
@@ -0,0 +1,93 @@
+use {
+    super::{bls12_381::*, impl_contract},
+    ark_bls12_381::g2,
+    ark_ec::{
+        hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurve},
+        AffineRepr,
+    },
+    evm::H160,
+    solana_program::msg,
+};
+
+impl_contract!(
+    Bls12MapFp2ToG2,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 17,],
+    fallible,
+    gas = |_: &[u8]| MAP_FP2_TO_G2_GAS
+);
+
+// simplified SWU map followed by the cofactor clearing
+fn contract(input: &[u8]) -> Result<Vec<u8>> {
+    msg!("bls12 map_fp2_to_g2");
+    if input.len() != FP2_LEN {
+        return Err(invalid("bls12 map_fp2_to_g2 input length"));
+    }
+
+    let fp2 = decode_fp2(input)?;
+    let point = WBMap::<g2::Config>::new()
+        .and_then(|map| map.map_to_curve(fp2))
+        .map_err(|e| invalid(&e.to_string()))?;
+
+    Ok(encode_g2(&point.clear_cofactor()))
+}
+
+#[cfg(test)]
+mod test {
+    // Test vectors are from https://github.com/ethereum/EIPs/tree/master/assets/eip-2537
+
+    use crate::precompile::bls12_map_fp2_to_g2::contract;
+
+    fn test_case(input_hex: &str, expected_hex: &str) {
+        assert_eq!(
+            contract(hex::decode(input_hex).unwrap().as_slice()).unwrap(),
+            hex::decode(expected_hex).unwrap()
+        )
+    }
+
+    fn fail_case(input_hex: &str) {
+        assert!(contract(hex::decode(input_hex).unwrap().as_slice()).is_err())
+    }
+
+    #[test]
+    fn empty_message() {
+        test_case(
+            "0000000000000000000000000000000007355d25caf6e7f2f0cb2812ca0e513bd026ed09dda65b177500fa31714e09ea0ded3a078b526bed3307f804d4b93b04\
+            0000000000000000000000000000000002829ce3c021339ccb5caf3e187f6370e1e2a311dec9b75363117063ab2015603ff52c3d3b98f19c2f65575e99e8b78c",
+            "0000000000000000000000000000000000e7f4568a82b4b7dc1f14c6aaa055edf51502319c723c4dc2688c7fe5944c213f510328082396515734b6612c4e7bb7\
+            00000000000000000000000000000000126b855e9e69b1f691f816e48ac6977664d24d99f8724868a184186469ddfd4617367e94527d4b74fc86413483afb35b\
+            000000000000000000000000000000000caead0fd7b6176c01436833c79d305c78be307da5f6af6c133c47311def6ff1e0babf57a0fb5539fce7ee12407b0a42\
+            000000000000000000000000000000001498aadcf7ae2b345243e281ae076df6de84455d766ab6fcdaad71fab60abb2e8b980a440043cd305db09d283c895e3d",
+        )
+    }
+
+    #[test]
+    fn abc() {
+        test_case(
+            "00000000000000000000000000000000138879a9559e24cecee8697b8b4ad32cced053138ab913b99872772dc753a2967ed50aabc907937aefb2439ba06cc50c\
+            000000000000000000000000000000000a1ae7999ea9bab1dcc9ef8887a6cb6e8f1e22566015428d220b7eec90ffa70ad1f624018a9ad11e78d588bd3617f9f2",
+            "00000000000000000000000000000000108ed59fd9fae381abfd1d6bce2fd2fa220990f0f837fa30e0f27914ed6e1454db0d1ee957b219f61da6ff8be0d6441f\
+            000000000000000000000000000000000296238ea82c6d4adb3c838ee3cb2346049c90b96d602d7bb1b469b905c9228be25c627bffee872def773d5b2a2eb57d\
+            00000000000000000000000000000000033f90f6057aadacae7963b0a0b379dd46750c1c94a6357c99b65f63b79e321ff50fe3053330911c56b6ceea08fee656\
+            00000000000000000000000000000000153606c417e59fb331b7ae6bce4fbf7c5190c33ce9402b5ebe2b70e44fca614f3f1382a3625ed5493843d0b0a652fc3f",
+        )
+    }
+
+    #[test]
+    fn long_input() {
+        // invalid input length
+        fail_case(
+            "000000000000000000000000000000000007355d25caf6e7f2f0cb2812ca0e513bd026ed09dda65b177500fa31714e09ea0ded3a078b526bed3307f804d4b93b\
+            040000000000000000000000000000000002829ce3c021339ccb5caf3e187f6370e1e2a311dec9b75363117063ab2015603ff52c3d3b98f19c2f65575e99e8b7\
+            8c",
+        )
+    }
+
+    #[test]
+    fn top_bytes() {
+        // invalid field element top bytes
+        fail_case(
+            "000000000000000000000000000000000007355d25caf6e7f2f0cb2812ca0e513bd026ed09dda65b177500fa31714e09ea0ded3a078b526bed3307f804d4b93b\
+            040000000000000000000000000000000002829ce3c021339ccb5caf3e187f6370e1e2a311dec9b75363117063ab2015603ff52c3d3b98f19c2f65575e99e8b7",
+        )
+    }
+}
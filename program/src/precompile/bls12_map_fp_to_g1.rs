@@ -0,0 +1,84 @@
+use {
+    super::{bls12_381::*, impl_contract},
+    ark_bls12_381::g1,
+    ark_ec::{
+        hashing::{curve_maps::wb::WBMap, map_to_curve_hasher::MapToCurve},
+        AffineRepr,
+    },
+    evm::H160,
+    solana_program::msg,
+};
+
+impl_contract!(
+    Bls12MapFpToG1,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 16,],
+    fallible,
+    gas = |_: &[u8]| MAP_FP_TO_G1_GAS
+);
+
+// simplified SWU map followed by the cofactor clearing
+fn contract(input: &[u8]) -> Result<Vec<u8>> {
+    msg!("bls12 map_fp_to_g1");
+    if input.len() != FP_LEN {
+        return Err(invalid("bls12 map_fp_to_g1 input length"));
+    }
+
+    let fp = decode_fp(input)?;
+    let point = WBMap::<g1::Config>::new()
+        .and_then(|map| map.map_to_curve(fp))
+        .map_err(|e| invalid(&e.to_string()))?;
+
+    Ok(encode_g1(&point.clear_cofactor()))
+}
+
+#[cfg(test)]
+mod test {
+    // Test vectors are from https://github.com/ethereum/EIPs/tree/master/assets/eip-2537
+
+    use crate::precompile::bls12_map_fp_to_g1::contract;
+
+    fn test_case(input_hex: &str, expected_hex: &str) {
+        assert_eq!(
+            contract(hex::decode(input_hex).unwrap().as_slice()).unwrap(),
+            hex::decode(expected_hex).unwrap()
+        )
+    }
+
+    fn fail_case(input_hex: &str) {
+        assert!(contract(hex::decode(input_hex).unwrap().as_slice()).is_err())
+    }
+
+    #[test]
+    fn empty_message() {
+        test_case(
+            "00000000000000000000000000000000156c8a6a2c184569d69a76be144b5cdc5141d2d2ca4fe341f011e25e3969c55ad9e9b9ce2eb833c81a908e5fa4ac5f03",
+            "00000000000000000000000000000000184bb665c37ff561a89ec2122dd343f20e0f4cbcaec84e3c3052ea81d1834e192c426074b02ed3dca4e7676ce4ce48ba\
+            0000000000000000000000000000000004407b8d35af4dacc809927071fc0405218f1401a6d15af775810e4e460064bcc9468beeba82fdc751be70476c888bf3",
+        )
+    }
+
+    #[test]
+    fn abc() {
+        test_case(
+            "00000000000000000000000000000000147e1ed29f06e4c5079b9d14fc89d2820d32419b990c1c7bb7dbea2a36a045124b31ffbde7c99329c05c559af1c6cc82",
+            "00000000000000000000000000000000009769f3ab59bfd551d53a5f846b9984c59b97d6842b20a2c565baa167945e3d026a3755b6345df8ec7e6acb6868ae6d\
+            000000000000000000000000000000001532c00cf61aa3d0ce3e5aa20c3b531a2abd2c770a790a2613818303c6b830ffc0ecf6c357af3317b9575c567f11cd2c",
+        )
+    }
+
+    #[test]
+    fn short_input() {
+        // invalid input length
+        fail_case(
+            "00000000000000000000000000000000156c8a6a2c184569d69a76be144b5cdc5141d2d2ca4fe341f011e25e3969c55ad9e9b9ce2eb833c81a908e5fa4ac5f",
+        )
+    }
+
+    #[test]
+    fn invalid_field_element() {
+        // invalid fp.Element encoding
+        fail_case(
+            "000000000000000000000000000000002f6d9c5465982c0421b61e74579709b3b5b91e57bdd4f6015742b4ff301abb7ef895b9cce00c33c7d48f8e5fa4ac09ae",
+        )
+    }
+}
@@ -3,7 +3,11 @@ use {
     super::impl_contract,
 };
 
-impl_contract!(Ecadd, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6,]);
+impl_contract!(
+    Ecadd,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6,],
+    gas = |_: &[u8]| 150
+);
 
 fn contract(input: &[u8]) -> Vec<u8> {
     msg!("ecAdd");
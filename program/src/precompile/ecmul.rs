@@ -3,7 +3,11 @@ use {
     super::impl_contract,
 };
 
-impl_contract!(Ecmul, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 7,]);
+impl_contract!(
+    Ecmul,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 7,],
+    gas = |_: &[u8]| 6_000
+);
 
 fn contract(input: &[u8]) -> Vec<u8> {
     msg!("ecMul");
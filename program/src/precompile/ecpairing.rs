@@ -3,7 +3,11 @@ use {
     super::impl_contract,
 };
 
-impl_contract!(Ecpairing, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8,]);
+impl_contract!(
+    Ecpairing,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 8,],
+    gas = |input: &[u8]| 45_000 + 34_000 * (input.len() as u64 / 192)
+);
 
 fn contract(input: &[u8]) -> Vec<u8> {
     msg!("ecPairing");
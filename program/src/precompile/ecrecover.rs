@@ -6,7 +6,11 @@ use {
     super::impl_contract,
 };
 
-impl_contract!(Ecrecover, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,]);
+impl_contract!(
+    Ecrecover,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,],
+    gas = |_: &[u8]| 3_000
+);
 
 fn contract(input: &[u8]) -> Vec<u8> {
     msg!("ecrecover");
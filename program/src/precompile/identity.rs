@@ -1,6 +1,12 @@
-use {evm::H160, solana_program::msg, super::impl_contract};
+use {
+    evm::H160, solana_program::msg, crate::state::gasometer::input_words, super::impl_contract,
+};
 
-impl_contract!(Identity, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4,]);
+impl_contract!(
+    Identity,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4,],
+    gas = |input: &[u8]| 15 + 3 * input_words(input)
+);
 
 #[must_use]
 fn contract(input: &[u8]) -> Vec<u8> {
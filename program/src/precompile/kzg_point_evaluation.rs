@@ -0,0 +1,172 @@
+use {
+    super::{bls12_381::invalid, impl_contract},
+    ark_bls12_381::{Bls12_381, Fr, G1Affine, G2Affine},
+    ark_ec::{pairing::Pairing, AffineRepr, CurveGroup},
+    ark_ff::{BigInteger, One, PrimeField},
+    ark_serialize::CanonicalDeserialize,
+    evm::H160,
+    solana_program::{hash::hash, msg},
+};
+
+impl_contract!(
+    PointEvaluation,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10,],
+    fallible,
+    gas = |_: &[u8]| POINT_EVALUATION_GAS
+);
+
+const INPUT_LEN: usize = 192;
+const POINT_EVALUATION_GAS: u64 = 50_000;
+const VERSIONED_HASH_VERSION_KZG: u8 = 1;
+
+// [τ]G2 of the Ethereum KZG ceremony, uncompressed: x.c1 | x.c0 | y.c1 | y.c0.
+// the point is trusted, it is read without the decompression and the subgroup check
+const TAU_G2: [u8; 192] = [
+    0x15, 0xbf, 0xd7, 0xdd, 0x8c, 0xde, 0xb1, 0x28, 0x84, 0x3b, 0xc2, 0x87, 0x23, 0x0a, 0xf3, 0x89,
+    0x26, 0x18, 0x70, 0x75, 0xcb, 0xfb, 0xef, 0xa8, 0x10, 0x09, 0xa2, 0xce, 0x61, 0x5a, 0xc5, 0x3d,
+    0x29, 0x14, 0xe5, 0x87, 0x0c, 0xb4, 0x52, 0xd2, 0xaf, 0xaa, 0xab, 0x24, 0xf3, 0x49, 0x9f, 0x72,
+    0x18, 0x5c, 0xbf, 0xee, 0x53, 0x49, 0x27, 0x14, 0x73, 0x44, 0x29, 0xb7, 0xb3, 0x86, 0x08, 0xe2,
+    0x39, 0x26, 0xc9, 0x11, 0xcc, 0xec, 0xea, 0xc9, 0xa3, 0x68, 0x51, 0x47, 0x7b, 0xa4, 0xc6, 0x0b,
+    0x08, 0x70, 0x41, 0xde, 0x62, 0x10, 0x00, 0xed, 0xc9, 0x8e, 0xda, 0xda, 0x20, 0xc1, 0xde, 0xf2,
+    0x16, 0x66, 0xc5, 0x4b, 0x0a, 0x32, 0x52, 0x95, 0x03, 0x43, 0x2f, 0xca, 0xe0, 0x18, 0x1b, 0x4b,
+    0xef, 0x79, 0xde, 0x09, 0xfc, 0x63, 0x67, 0x1f, 0xda, 0x5e, 0xd1, 0xba, 0x9b, 0xfa, 0x07, 0x89,
+    0x94, 0x95, 0x34, 0x6f, 0x3d, 0x7a, 0xc9, 0xcd, 0x23, 0x04, 0x8e, 0xf3, 0x0d, 0x0a, 0x15, 0x4f,
+    0x01, 0x43, 0x53, 0xbd, 0xb9, 0x6b, 0x62, 0x6d, 0xd7, 0xd5, 0xee, 0x85, 0x99, 0xd1, 0xfc, 0xa2,
+    0x13, 0x15, 0x69, 0x49, 0x0e, 0x28, 0xde, 0x18, 0xe8, 0x24, 0x51, 0xa4, 0x96, 0xa9, 0xc9, 0x79,
+    0x4c, 0xe2, 0x6d, 0x10, 0x59, 0x41, 0xf3, 0x83, 0xee, 0x68, 0x9b, 0xfb, 0xbb, 0x83, 0x2a, 0x99,
+];
+
+// FIELD_ELEMENTS_PER_BLOB | BLS_MODULUS
+const RETURN_VALUE: [u8; 64] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x10, 0,
+    0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1, 0xd8, 0x05,
+    0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x01,
+];
+
+fn scalar(bytes: &[u8]) -> Result<Fr> {
+    let fr = Fr::from_be_bytes_mod_order(bytes);
+    if fr.into_bigint().to_bytes_be() != bytes {
+        return Err(invalid("point evaluation scalar is not canonical"));
+    }
+    Ok(fr)
+}
+
+fn g1(bytes: &[u8]) -> Result<G1Affine> {
+    G1Affine::deserialize_compressed(bytes).map_err(|e| invalid(&format!("kzg point: {}", e)))
+}
+
+// versioned_hash | z | y | commitment | proof
+fn contract(input: &[u8]) -> Result<Vec<u8>> {
+    msg!("point evaluation");
+    if input.len() != INPUT_LEN {
+        return Err(invalid("point evaluation input length"));
+    }
+
+    let versioned_hash = &input[..32];
+    let z = scalar(&input[32..64])?;
+    let y = scalar(&input[64..96])?;
+    let commitment = &input[96..144];
+    let proof = &input[144..192];
+
+    let mut expected = hash(commitment).to_bytes();
+    expected[0] = VERSIONED_HASH_VERSION_KZG;
+    if versioned_hash != expected {
+        return Err(invalid("versioned hash mismatch"));
+    }
+
+    let commitment = g1(commitment)?;
+    let proof = g1(proof)?;
+    let tau = G2Affine::deserialize_uncompressed_unchecked(&TAU_G2[..])
+        .map_err(|e| invalid(&format!("kzg setup: {}", e)))?;
+
+    // e(C - [y]G1, -G2) * e(proof, [τ]G2 - [z]G2) == 1
+    let p = (commitment.into_group() - G1Affine::generator() * y).into_affine();
+    let q = (tau.into_group() - G2Affine::generator() * z).into_affine();
+    let neg_g2 = -G2Affine::generator();
+
+    if !Bls12_381::multi_pairing([p, proof], [neg_g2, q]).0.is_one() {
+        return Err(invalid("kzg proof verification failed"));
+    }
+
+    Ok(RETURN_VALUE.to_vec())
+}
+
+#[cfg(test)]
+mod test {
+    // Test vector is from https://github.com/ethereum/c-kzg-4844
+
+    use crate::precompile::kzg_point_evaluation::{contract, RETURN_VALUE, TAU_G2};
+    use {ark_bls12_381::G2Affine, ark_serialize::{CanonicalDeserialize, CanonicalSerialize}};
+    use solana_program::hash::hash;
+
+    const COMMITMENT: &str = "8f59a8d2a1a625a17f3fea0fe5eb8c896db3764f3185481bc22f91b4aaffcca25f26936857bc3a7c2539ea8ec3a952b7";
+    const Z: &str = "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000000";
+    const Y: &str = "1522a4a7f34e1ea350ae07c29c96c7e79655aa926122e95fe69fcbd932ca49e9";
+    const PROOF: &str = "a62ad71d14c5719385c0686f1871430475bf3a00f0aa3f7b8dd99a9abc2160744faf0070725e00b60ad9a026a15b1a8c";
+
+    fn input(z: &str, y: &str) -> Vec<u8> {
+        let commitment = hex::decode(COMMITMENT).unwrap();
+        let mut versioned_hash = hash(&commitment).to_bytes();
+        versioned_hash[0] = 1;
+
+        let mut input = versioned_hash.to_vec();
+        input.extend(hex::decode(z).unwrap());
+        input.extend(hex::decode(y).unwrap());
+        input.extend(commitment);
+        input.extend(hex::decode(PROOF).unwrap());
+        input
+    }
+
+    #[test]
+    fn valid_proof() {
+        assert_eq!(contract(&input(Z, Y)).unwrap(), RETURN_VALUE.to_vec());
+    }
+
+    #[test]
+    fn setup() {
+        // the compressed [τ]G2 of the ceremony
+        let compressed = hex::decode(
+            "b5bfd7dd8cdeb128843bc287230af38926187075cbfbefa81009a2ce615ac53d2914e5870cb452d2afaaab24\
+            f3499f72185cbfee53492714734429b7b38608e23926c911cceceac9a36851477ba4c60b087041de621000ed\
+            c98edada20c1def2",
+        )
+        .unwrap();
+        let tau = G2Affine::deserialize_compressed(compressed.as_slice()).unwrap();
+
+        let mut uncompressed = vec![];
+        tau.serialize_uncompressed(&mut uncompressed).unwrap();
+        assert_eq!(uncompressed, TAU_G2.to_vec());
+
+        let ret = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000001000\
+            73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001",
+        )
+        .unwrap();
+        assert_eq!(RETURN_VALUE.to_vec(), ret);
+    }
+
+    #[test]
+    fn invalid_proof() {
+        let y = "1522a4a7f34e1ea350ae07c29c96c7e79655aa926122e95fe69fcbd932ca49e8";
+        assert!(contract(&input(Z, y)).is_err());
+    }
+
+    #[test]
+    fn versioned_hash_mismatch() {
+        let mut input = input(Z, Y);
+        input[0] = 0;
+        assert!(contract(&input).is_err());
+    }
+
+    #[test]
+    fn non_canonical_scalar() {
+        let z = "73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001";
+        assert!(contract(&input(z, Y)).is_err());
+    }
+
+    #[test]
+    fn invalid_length() {
+        let input = input(Z, Y);
+        assert!(contract(&input[..191]).is_err());
+    }
+}
@@ -4,49 +4,92 @@ mod ecmul;
 mod ecpairing;
 mod ecrecover;
 mod identity;
-// mod modexp;
+mod modexp;
 mod ripemd_160;
 mod sha2_256;
+#[cfg(feature = "bls12-381")]
+mod bls12_381;
+#[cfg(feature = "bls12-381")]
+mod bls12_g1add;
+#[cfg(feature = "bls12-381")]
+mod bls12_g1msm;
+#[cfg(feature = "bls12-381")]
+mod bls12_g2add;
+#[cfg(feature = "bls12-381")]
+mod bls12_g2msm;
+#[cfg(feature = "bls12-381")]
+mod bls12_map_fp2_to_g2;
+#[cfg(feature = "bls12-381")]
+mod bls12_map_fp_to_g1;
+#[cfg(feature = "bls12-381")]
+mod bls12_pairing;
+#[cfg(feature = "bls12-381")]
+mod kzg_point_evaluation;
 
 use {
-    blake2f::*, ecadd::*, ecmul::*, ecpairing::*, ecrecover::*, identity::*, ripemd_160::*, sha2_256::*,
+    blake2f::*, ecadd::*, ecmul::*, ecpairing::*, ecrecover::*, identity::*, modexp::*, ripemd_160::*,
+    sha2_256::*,
     evm::H160,
     crate::{
-        non_evm::{Program, SplToken, ASplToken, System, Withdraw,},
+        non_evm::{Program, SplToken, ASplToken, System, Withdraw, Invoke,},
         origin::Origin,
     },
 };
 
-pub fn non_evm_program<'a, T:Origin>(address: &H160, state: &'a T) -> Option<Box<dyn Program + 'a>>  {
-    // TODO: check on-chain and enable
-    assert_ne!(
-        *address,
-        H160([0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5]),
-        "big_mod_exp call is disabled"
-    );
+#[cfg(feature = "bls12-381")]
+use {
+    bls12_g1add::*, bls12_g1msm::*, bls12_g2add::*, bls12_g2msm::*, bls12_map_fp2_to_g2::*,
+    bls12_map_fp_to_g1::*, bls12_pairing::*, kzg_point_evaluation::*,
+};
 
+pub fn non_evm_program<'a, T:Origin>(address: &H160, state: &'a T) -> Option<Box<dyn Program + 'a>>  {
     match *address {
         _ if *address == Ecrecover::ADDRESS  => Some(Box::new(Ecrecover())),
         _ if *address == Sha2::ADDRESS => Some(Box::new(Sha2())),
         _ if *address == Ripemd::ADDRESS => Some(Box::new(Ripemd())),
         _ if *address == Identity::ADDRESS => Some(Box::new(Identity())),
-        // _ if *address == Modexp::ADDRESS => Some(Box::new(Modexp()),
+        _ if *address == Modexp::ADDRESS => Some(Box::new(Modexp())),
         _ if *address == Ecadd::ADDRESS => Some(Box::new(Ecadd())),
         _ if *address == Ecmul::ADDRESS => Some(Box::new(Ecmul())),
         _ if *address == Ecpairing::ADDRESS => Some(Box::new(Ecpairing())),
         _ if *address == Blake2f::ADDRESS => Some(Box::new(Blake2f())),
+        #[cfg(feature = "bls12-381")]
+        _ if *address == PointEvaluation::ADDRESS => Some(Box::new(PointEvaluation())),
+        #[cfg(feature = "bls12-381")]
+        _ if *address == Bls12G1Add::ADDRESS => Some(Box::new(Bls12G1Add())),
+        #[cfg(feature = "bls12-381")]
+        _ if *address == Bls12G1Msm::ADDRESS => Some(Box::new(Bls12G1Msm())),
+        #[cfg(feature = "bls12-381")]
+        _ if *address == Bls12G2Add::ADDRESS => Some(Box::new(Bls12G2Add())),
+        #[cfg(feature = "bls12-381")]
+        _ if *address == Bls12G2Msm::ADDRESS => Some(Box::new(Bls12G2Msm())),
+        #[cfg(feature = "bls12-381")]
+        _ if *address == Bls12Pairing::ADDRESS => Some(Box::new(Bls12Pairing())),
+        #[cfg(feature = "bls12-381")]
+        _ if *address == Bls12MapFpToG1::ADDRESS => Some(Box::new(Bls12MapFpToG1())),
+        #[cfg(feature = "bls12-381")]
+        _ if *address == Bls12MapFp2ToG2::ADDRESS => Some(Box::new(Bls12MapFp2ToG2())),
 
         _ if *address == SplToken::<'a, T>::ADDRESS => Some(Box::new(SplToken::new(state))),
         _ if *address == ASplToken::<'a, T>::ADDRESS => Some(Box::new(ASplToken::new(state))),
         _ if *address == System::<'a, T>::ADDRESS => Some(Box::new(System::new(state))),
+        _ if *address == Invoke::<'a, T>::ADDRESS => Some(Box::new(Invoke::new(state))),
 
         _ if *address == Withdraw::<'a, T>::ADDRESS => Some(Box::new(Withdraw::new(state))),
         _ => None
     }
 }
 
+// gas: the cost of the call charged from the gas forwarded to the precompile
 macro_rules! impl_contract {
-    ($name:ident, $address:expr) => {
+    ($name:ident, $address:expr, gas = $gas:expr) => {
+        impl_contract!(@impl $name, $address, |input: &[u8]| -> Result<Vec<u8>> { Ok(contract(input)) }, $gas);
+    };
+    // the contract returns Result, the error is reported to the caller
+    ($name:ident, $address:expr, fallible, gas = $gas:expr) => {
+        impl_contract!(@impl $name, $address, contract, $gas);
+    };
+    (@impl $name:ident, $address:expr, $contract:expr, $gas:expr) => {
         use {
             crate::{
                 non_evm::{Program, NonEvmState, Bind, EvmDiff,}, state::pda::Seed, 
@@ -63,7 +106,8 @@ macro_rules! impl_contract {
 
         impl Program for $name {
             fn eth_call(&self, input: &[u8], _: &NonEvmState) -> Result<Vec<u8>> {
-                Ok(contract(input))
+                let contract = $contract;
+                contract(input)
             }
             fn found_eth_call(&self, _: &[u8]) -> bool {
                 true
@@ -77,6 +121,13 @@ macro_rules! impl_contract {
             fn transfer_allowed(&self) -> bool {
                 false
             }
+            fn gas(&self, input: &[u8]) -> u64 {
+                let gas = $gas;
+                gas(input)
+            }
+            fn is_precompile(&self) -> bool {
+                true
+            }
         }
     };
 }
@@ -1,4 +1,5 @@
 use {
+    crate::error::RomeProgramError::InvalidPrecompileInput,
     evm::{H160, U256},
     solana_program::big_mod_exp::big_mod_exp,
     solana_program::msg,
@@ -6,38 +7,220 @@ use {
     super::impl_contract,
 };
 
-impl_contract!(Modexp, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5,]);
+impl_contract!(Modexp, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 5,], fallible, gas = gas);
 
-const INPUT_LEN: usize = 96;
+const HEADER_LEN: usize = 96;
+// EIP-2565
+const MIN_GAS: u64 = 200;
+// the length limit of the sol_big_mod_exp syscall, it is enough for RSA-4096
+const MAX_LEN: usize = 512;
 
-fn contract(input: &[u8]) -> Vec<u8> {
-    msg!("modexp");
-    if input.len() < INPUT_LEN {
-        return vec![];
+// reads the input as if it were padded with zeros to the required length
+fn read(input: &[u8], offset: usize, len: usize) -> Vec<u8> {
+    let mut buf = vec![0_u8; len];
+    if offset < input.len() {
+        let end = input.len().min(offset + len);
+        buf[..end - offset].copy_from_slice(&input[offset..end]);
     }
+    buf
+}
 
-    let (base_len, rest) = input.split_at(32);
-    let Ok(base_len) = U256::from_big_endian(base_len).try_into() else {
-        return vec![];
-    };
+fn length(input: &[u8], offset: usize) -> Result<usize> {
+    let len = U256::from_big_endian(&read(input, offset, 32));
+    let len: usize = len
+        .try_into()
+        .map_err(|_| InvalidPrecompileInput(format!("modexp length is too big: {}", len)))?;
 
-    let (exponent_len, rest) = rest.split_at(32);
-    let Ok(exponent_len) = U256::from_big_endian(exponent_len).try_into() else {
-        return vec![];
+    if len > MAX_LEN {
+        return Err(InvalidPrecompileInput(format!("modexp length {} exceeds {}", len, MAX_LEN)));
+    }
+
+    Ok(len)
+}
+
+// EIP-2565: max(200, mult_complexity * iterations / 3).
+// the lengths are limited by MAX_LEN in the contract, the syscall stays within the compute budget
+// and the price is the same as on Ethereum
+fn gas(input: &[u8]) -> u64 {
+    let base_len = U256::from_big_endian(&read(input, 0, 32));
+    let exponent_len = U256::from_big_endian(&read(input, 32, 32));
+    let modulus_len = U256::from_big_endian(&read(input, 64, 32));
+
+    // the words of 8 bytes
+    let words = (base_len.max(modulus_len).saturating_add(7.into())) / 8;
+    let complexity = words.saturating_mul(words);
+
+    // the leading 32 bytes of the exponent
+    let head_len = exponent_len.min(32.into()).as_usize();
+    let offset = base_len.saturating_add(HEADER_LEN.into());
+    let head = if offset > U256::from(input.len()) {
+        U256::zero()
+    } else {
+        U256::from_big_endian(&read(input, offset.as_usize(), head_len))
     };
 
-    let (modulus_len, rest) = rest.split_at(32);
-    let Ok(modulus_len) = U256::from_big_endian(modulus_len).try_into() else {
-        return vec![];
+    let head_bits = U256::from(head.bits().saturating_sub(1));
+    let iterations = if exponent_len <= 32.into() {
+        head_bits
+    } else {
+        (exponent_len - U256::from(32)).saturating_mul(8.into()).saturating_add(head_bits)
     };
 
-    if base_len == 0 && modulus_len == 0 {
-        return vec![0; 32];
+    let gas = complexity.saturating_mul(iterations.max(U256::one())) / 3;
+    if gas > u64::MAX.into() {
+        u64::MAX
+    } else {
+        gas.as_u64().max(MIN_GAS)
+    }
+}
+
+// base_len | exponent_len | modulus_len | base | exponent | modulus
+fn contract(input: &[u8]) -> Result<Vec<u8>> {
+    msg!("modexp");
+    // EIP-198: the zero modulus returns the empty output whatever the other lengths are
+    if U256::from_big_endian(&read(input, 64, 32)).is_zero() {
+        return Ok(vec![]);
+    }
+
+    let base_len = length(input, 0)?;
+    let exponent_len = length(input, 32)?;
+    let modulus_len = length(input, 64)?;
+
+    let base = read(input, HEADER_LEN, base_len);
+    let exponent = read(input, HEADER_LEN + base_len, exponent_len);
+    let modulus = read(input, HEADER_LEN + base_len + exponent_len, modulus_len);
+
+    let result = big_mod_exp(&base, &exponent, &modulus);
+
+    // the result is left-padded to the modulus length
+    let mut output = vec![0_u8; modulus_len];
+    let len = result.len().min(modulus_len);
+    output[modulus_len - len..].copy_from_slice(&result[result.len() - len..]);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test {
+    // Test cases are from https://github.com/ethereum/EIPs/blob/master/EIPS/eip-198.md
+
+    use crate::precompile::modexp::{contract, gas};
+    use hex;
+
+    fn test_case(input_hex: &str, expected_result: Vec<u8>) {
+        assert_eq!(
+            contract(hex::decode(input_hex).unwrap().as_slice()).unwrap(),
+            expected_result
+        )
+    }
+
+    #[test]
+    fn fermat() {
+        test_case(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            03\
+            fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e\
+            fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            hex::decode("0000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap(),
+        )
     }
 
-    let (base, rest) = rest.split_at(base_len);
-    let (exponent, rest) = rest.split_at(exponent_len);
-    let (modulus, _) = rest.split_at(modulus_len);
+    #[test]
+    fn zero_base() {
+        test_case(
+            "0000000000000000000000000000000000000000000000000000000000000000\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e\
+            fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            hex::decode("0000000000000000000000000000000000000000000000000000000000000000")
+                .unwrap(),
+        )
+    }
 
-    big_mod_exp(base, exponent, modulus)
+    #[test]
+    fn implicit_padding() {
+        test_case(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+            0000000000000000000000000000000000000000000000000000000000000002\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            03\
+            ffff\
+            80",
+            hex::decode("3b01b01ac41f2d6e917c6d6a221ce793802469026d9ab7578fa2e79e4da6aaab")
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn empty_input() {
+        test_case("", vec![])
+    }
+
+    #[test]
+    fn too_big_length() {
+        let input = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000201\
+            0000000000000000000000000000000000000000000000000000000000000001\
+            0000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+
+        assert!(contract(&input).is_err());
+    }
+
+    #[test]
+    fn length_overflow() {
+        let input = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+            8000000000000000000000000000000000000000000000000000000000000000\
+            0000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+
+        assert!(contract(&input).is_err());
+    }
+
+    #[test]
+    fn zero_modulus() {
+        // the exponent length exceeds the limit, the output is empty anyway
+        let input = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+            0000000000000000000000000000000000000000000000000000000000001000\
+            0000000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+
+        assert_eq!(contract(&input).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn eip2565_gas() {
+        // EIP-2565 example: (ceil(32 / 8))^2 * 255 / 3
+        let input = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            0000000000000000000000000000000000000000000000000000000000000020\
+            03\
+            fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e\
+            fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        )
+        .unwrap();
+        assert_eq!(gas(&input), 1360);
+
+        // the minimal price
+        assert_eq!(gas(&[]), 200);
+
+        // the huge lengths saturate
+        let input = hex::decode(
+            "8000000000000000000000000000000000000000000000000000000000000000\
+            8000000000000000000000000000000000000000000000000000000000000000\
+            8000000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        assert_eq!(gas(&input), u64::MAX);
+    }
 }
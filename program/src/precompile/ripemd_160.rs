@@ -2,10 +2,14 @@ use {
     evm::H160,
     ripemd::{Digest, Ripemd160},
     solana_program::msg,
-    super::impl_contract,
+    crate::state::gasometer::input_words, super::impl_contract,
 };
 
-impl_contract!(Ripemd, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3,]);
+impl_contract!(
+    Ripemd,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 3,],
+    gas = |input: &[u8]| 600 + 120 * input_words(input)
+);
 
 fn contract(input: &[u8]) -> Vec<u8> {
     msg!("ripemd_160");
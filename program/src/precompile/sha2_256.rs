@@ -1,6 +1,13 @@
-use {evm::H160, solana_program::hash::hash, solana_program::msg, super::impl_contract,};
+use {
+    evm::H160, solana_program::hash::hash, solana_program::msg,
+    crate::state::gasometer::input_words, super::impl_contract,
+};
 
-impl_contract!(Sha2, [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,]);
+impl_contract!(
+    Sha2,
+    [0_u8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2,],
+    gas = |input: &[u8]| 60 + 12 * input_words(input)
+);
 
 #[must_use]
 fn contract(input: &[u8]) -> Vec<u8> {
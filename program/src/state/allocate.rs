@@ -57,9 +57,13 @@ impl Allocate for State<'_> {
         context.check_writable(info)?;
         context.lock_new_one(info)?;
 
-        AccountState::check_no_contract(info, address)?;
+        AccountState::check_set_code(info, address, code)?;
 
         let req = Code::offset(info) + code.len() + valids.len();
+        // the cleared delegation designator is deallocated by Origin::set_code
+        if info.data_len() > req && AccountState::is_delegated(info)? {
+            return Ok(true);
+        }
         // TODO: implement deallocation
         if info.data_len() > req {
             return Err(Unimplemented("the contract deployment space must be deallocated according to size of the contract".to_string()));
@@ -9,7 +9,7 @@ use {
     },
 };
 
-#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Default, PartialEq, Eq)]
 pub struct Account {
     pub lamports: u64,
     pub data: Vec<u8>,
@@ -1,9 +1,20 @@
 use {
-    super::pda::{Pda, Seed},
-    crate::{error::RomeProgramError::*, error::*},
+    super::{
+        pda::{Pda, Seed},
+        sysvars::{Syscalls, SysvarProvider},
+    },
+    crate::{accounts::ChainConfig, error::RomeProgramError::*, error::*},
     evm::{H160, U256},
-    solana_program::{account_info::MAX_PERMITTED_DATA_INCREASE, pubkey::Pubkey},
-    std::{cell::RefCell, rc::Rc},
+    solana_program::{
+        account_info::MAX_PERMITTED_DATA_INCREASE,
+        clock::{Clock, Slot},
+        pubkey::Pubkey,
+        rent::Rent,
+    },
+    std::{
+        cell::RefCell,
+        sync::{atomic::{AtomicU64, Ordering::Relaxed}, Arc},
+    },
 };
 
 macro_rules! impl_alloc_fn {
@@ -36,14 +47,21 @@ pub struct Base<'a> {
     alloc_payed: RefCell<usize>,
     dealloc_payed: RefCell<usize>,
     pub pda: Pda<'a>,
-    pub syscall: Rc<Syscall>,
+    pub syscall: Arc<Syscall>,
     pub lamports_fee: RefCell<u64>,
     pub lamports_refund: RefCell<u64>,
+    // loaded by State::new
+    pub config: ChainConfig,
+    sysvars: Box<dyn SysvarProvider>,
 }
 
 impl<'a> Base<'a> {
     pub fn new(program_id: &'a Pubkey, chain: u64) -> Self {
-        let syscall = Rc::new(Syscall::new());
+        Self::with_sysvars(program_id, chain, Box::new(Syscalls))
+    }
+
+    pub fn with_sysvars(program_id: &'a Pubkey, chain: u64, sysvars: Box<dyn SysvarProvider>) -> Self {
+        let syscall = Arc::new(Syscall::new());
 
         Self {
             program_id,
@@ -52,12 +70,23 @@ impl<'a> Base<'a> {
             dealloc: RefCell::new(0),
             alloc_payed: RefCell::new(0),
             dealloc_payed: RefCell::new(0),
-            pda: Pda::new(program_id, chain, Rc::clone(&syscall)),
+            pda: Pda::new(program_id, chain, Arc::clone(&syscall)),
             syscall,
             lamports_fee: RefCell::new(0),
             lamports_refund: RefCell::new(0),
+            config: ChainConfig::default(),
+            sysvars,
         }
     }
+    pub fn clock(&self) -> Result<Clock> {
+        self.sysvars.clock()
+    }
+    pub fn rent(&self) -> Result<Rent> {
+        self.sysvars.rent()
+    }
+    pub fn slot_hash_position(&self, slot: &Slot) -> Result<Option<usize>> {
+        self.sysvars.slot_hash_position(slot)
+    }
     pub fn alloc_limit(&self) -> usize {
         MAX_PERMITTED_DATA_INCREASE.saturating_sub(self.alloc())
     }
@@ -111,24 +140,24 @@ impl<'a> Base<'a> {
 
 #[derive(Clone)]
 pub struct Syscall {
-    cnt: RefCell<u64>,
+    cnt: AtomicU64,
 }
 
 impl Syscall {
     pub fn inc(&self) {
-        *self.cnt.borrow_mut() += 1;
+        self.cnt.fetch_add(1, Relaxed);
     }
     pub fn count(&self) -> u64 {
-        *self.cnt.borrow()
+        self.cnt.load(Relaxed)
     }
     pub fn new() -> Self {
         Self {
-            cnt: RefCell::new(0),
+            cnt: AtomicU64::new(0),
         }
     }
     #[cfg(not(target_os = "solana"))]
     pub fn reset(&self) {
-        *self.cnt.borrow_mut() = 0;
+        self.cnt.store(0, Relaxed);
     }
 }
 
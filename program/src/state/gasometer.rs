@@ -0,0 +1,570 @@
+use {
+    crate::tx::eip2930::AccessList,
+    borsh::{BorshDeserialize, BorshSerialize},
+    evm::{ExitError, ExitReason, Opcode, Stack, H160, U256},
+    std::collections::BTreeSet,
+};
+
+// gas schedule (Cancun)
+pub const TX_BASE: u64 = 21_000;
+pub const TX_CREATE: u64 = 32_000;
+pub const TX_DATA_ZERO: u64 = 4;
+pub const TX_DATA_NON_ZERO: u64 = 16;
+pub const INITCODE_WORD: u64 = 2;
+pub const CALL_STIPEND: u64 = 2_300;
+pub const NEW_ACCOUNT: u64 = 25_000;
+// EIP-7702 PER_EMPTY_ACCOUNT_COST
+pub const PER_AUTHORIZATION: u64 = 25_000;
+pub const CODE_DEPOSIT: u64 = 200;
+// EIP-2930
+pub const ACCESS_LIST_ADDRESS: u64 = 2_400;
+pub const ACCESS_LIST_STORAGE_KEY: u64 = 1_900;
+
+const ZERO: u64 = 0;
+const BASE: u64 = 2;
+const VERY_LOW: u64 = 3;
+const LOW: u64 = 5;
+const MID: u64 = 8;
+const HIGH: u64 = 10;
+const JUMPDEST: u64 = 1;
+const BLOCKHASH: u64 = 20;
+const EXP: u64 = 10;
+const EXP_BYTE: u64 = 50;
+const SHA3: u64 = 30;
+const SHA3_WORD: u64 = 6;
+const COPY_WORD: u64 = 3;
+const MEMORY: u64 = 3;
+const QUAD_COEFF_DIV: u64 = 512;
+const LOG: u64 = 375;
+const LOG_TOPIC: u64 = 375;
+const LOG_DATA: u64 = 8;
+const CREATE: u64 = 32_000;
+const CALL_VALUE: u64 = 9_000;
+const SELFDESTRUCT: u64 = 5_000;
+const WARM_ACCESS: u64 = 100;
+const COLD_ACCOUNT_ACCESS: u64 = 2_600;
+const COLD_SLOAD: u64 = 2_100;
+const SSTORE_SET: u64 = 20_000;
+const SSTORE_RESET: u64 = 5_000 - COLD_SLOAD;
+const SSTORE_CLEARS_REFUND: i64 = 4_800;
+const MAX_REFUND_QUOTIENT: u64 = 5;
+// the address of the last precompile
+#[cfg(not(feature = "bls12-381"))]
+const PRECOMPILES: u64 = 0x09;
+#[cfg(feature = "bls12-381")]
+const PRECOMPILES: u64 = 0x11;
+
+/// Gas meter of a single call frame
+#[derive(BorshSerialize, BorshDeserialize, Default, Clone, Debug)]
+pub struct Gasometer {
+    pub limit: u64,
+    pub used: u64,
+    pub refund: i64,
+    pub memory_words: u64,
+}
+
+impl Gasometer {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            ..Default::default()
+        }
+    }
+
+    pub fn remaining(&self) -> u64 {
+        self.limit - self.used
+    }
+
+    pub fn charge(&mut self, cost: u64) -> Result<(), ExitError> {
+        let used = self.used.checked_add(cost).ok_or(ExitError::OutOfGas)?;
+        if used > self.limit {
+            return Err(ExitError::OutOfGas);
+        }
+        self.used = used;
+        Ok(())
+    }
+
+    pub fn return_gas(&mut self, gas: u64) {
+        self.used = self.used.saturating_sub(gas);
+    }
+
+    // charges the memory expansion up to [offset, offset + len)
+    pub fn expand_memory(&mut self, offset: U256, len: U256) -> Result<(), ExitError> {
+        if len.is_zero() {
+            return Ok(());
+        }
+
+        let end = offset.checked_add(len).ok_or(ExitError::OutOfGas)?;
+        if end > U256::from(u32::MAX) {
+            return Err(ExitError::OutOfGas);
+        }
+        let words = (end.as_u64() + 31) / 32;
+
+        if words > self.memory_words {
+            let cost = memory_cost(words) - memory_cost(self.memory_words);
+            self.charge(cost)?;
+            self.memory_words = words;
+        }
+
+        Ok(())
+    }
+}
+
+fn memory_cost(words: u64) -> u64 {
+    MEMORY * words + words * words / QUAD_COEFF_DIV
+}
+
+fn words(len: U256) -> Result<u64, ExitError> {
+    if len > U256::from(u32::MAX) {
+        return Err(ExitError::OutOfGas);
+    }
+    Ok((len.as_u64() + 31) / 32)
+}
+
+/// Tx-wide gas accounting: the stack of the frame meters and the EIP-2929 access sets
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug)]
+pub struct Gas {
+    pub frames: Vec<Gasometer>,
+    pub intrinsic: u64,
+    pub used: u64,
+    pub addresses: BTreeSet<H160>,
+    pub slots: BTreeSet<(H160, U256)>,
+}
+
+impl Gas {
+    pub fn frame(&mut self) -> Result<&mut Gasometer, ExitError> {
+        self.frames.last_mut().ok_or(ExitError::OutOfGas)
+    }
+
+    pub fn left(&self) -> Option<u64> {
+        self.frames.last().map(|frame| frame.remaining())
+    }
+
+    // returns the cost of the account access and marks the account as warm
+    pub fn access_address(&mut self, address: &H160) -> u64 {
+        if self.addresses.insert(*address) {
+            COLD_ACCOUNT_ACCESS
+        } else {
+            WARM_ACCESS
+        }
+    }
+
+    pub fn access_slot(&mut self, address: &H160, slot: &U256) -> u64 {
+        if self.slots.insert((*address, *slot)) {
+            COLD_SLOAD
+        } else {
+            WARM_ACCESS
+        }
+    }
+
+    // SSTORE and SELFDESTRUCT pay only the cold access surcharge
+    pub fn sstore_access(&mut self, address: &H160, slot: &U256) -> u64 {
+        if self.slots.insert((*address, *slot)) {
+            COLD_SLOAD
+        } else {
+            0
+        }
+    }
+
+    pub fn selfdestruct_access(&mut self, address: &H160) -> u64 {
+        if self.addresses.insert(*address) {
+            COLD_ACCOUNT_ACCESS
+        } else {
+            0
+        }
+    }
+
+    // EIP-2930: the addresses and the slots of the access list are warm from the tx start
+    pub fn warm_access_list(&mut self, list: &AccessList) {
+        for item in list.0.iter() {
+            self.addresses.insert(item.address);
+            for key in item.storage_keys.iter() {
+                self.slots.insert((item.address, U256::from_big_endian(key.as_bytes())));
+            }
+        }
+    }
+
+    // EIP-2929: the precompiles are warm from the tx start
+    pub fn warm_precompiles(&mut self) {
+        for address in 1..=PRECOMPILES {
+            self.addresses.insert(H160::from_low_u64_be(address));
+        }
+    }
+
+    pub fn push_frame(&mut self, limit: u64) {
+        self.frames.push(Gasometer::new(limit));
+    }
+
+    // the unused gas of the successful or reverted frame is returned to the caller,
+    // the refund counter is merged only if the frame is succeeded.
+    pub fn pop_frame(&mut self, reason: &ExitReason) {
+        let child = self.frames.pop().expect("gas frame expected");
+
+        if let Some(parent) = self.frames.last_mut() {
+            match reason {
+                ExitReason::Succeed(_) => {
+                    parent.return_gas(child.remaining());
+                    parent.refund += child.refund;
+                }
+                ExitReason::Revert(_) => parent.return_gas(child.remaining()),
+                _ => {}
+            }
+        } else {
+            // EIP-3529: the refund is capped by 1/5 of the gas used by the tx, the intrinsic gas included
+            self.used = match reason {
+                ExitReason::Succeed(_) => {
+                    let used = self.intrinsic.saturating_add(child.used);
+                    let refund = (child.refund.max(0) as u64).min(used / MAX_REFUND_QUOTIENT);
+                    used - refund
+                }
+                ExitReason::Revert(_) => self.intrinsic.saturating_add(child.used),
+                _ => self.intrinsic.saturating_add(child.limit),
+            };
+        }
+    }
+
+    // the precompile is paid from the gas forwarded to the call, the unused gas is returned.
+    // the failed precompile consumes all the forwarded gas
+    pub fn exit_precompile(&mut self, limit: u64, cost: u64, reason: &ExitReason) {
+        let used = if reason.is_succeed() || reason.is_revert() {
+            cost
+        } else {
+            limit
+        };
+
+        match self.frames.last_mut() {
+            Some(frame) => frame.return_gas(limit - used),
+            None => self.used = self.intrinsic.saturating_add(used),
+        }
+    }
+
+    // gas available for the nested call according to the "all but one 64th" rule (EIP-150)
+    pub fn call_limit(&mut self, target: Option<u64>) -> Result<u64, ExitError> {
+        let frame = match self.frames.last_mut() {
+            Some(frame) => frame,
+            None => return Ok(target.unwrap_or(u64::MAX)),
+        };
+
+        let available = frame.remaining() - frame.remaining() / 64;
+        let limit = target.map_or(available, |target| target.min(available));
+        frame.charge(limit)?;
+
+        Ok(limit)
+    }
+}
+
+pub fn intrinsic_gas(data: &[u8], is_create: bool) -> u64 {
+    let zeros = data.iter().filter(|&&x| x == 0).count() as u64;
+    let non_zeros = data.len() as u64 - zeros;
+
+    let mut gas = TX_BASE + zeros * TX_DATA_ZERO + non_zeros * TX_DATA_NON_ZERO;
+    if is_create {
+        gas += TX_CREATE + INITCODE_WORD * ((data.len() as u64 + 31) / 32);
+    }
+
+    gas
+}
+
+pub fn access_list_gas(list: &AccessList) -> u64 {
+    list.0.iter().fold(0, |gas, item| {
+        let keys = item.storage_keys.len() as u64;
+        gas.saturating_add(ACCESS_LIST_ADDRESS)
+            .saturating_add(keys.saturating_mul(ACCESS_LIST_STORAGE_KEY))
+    })
+}
+
+// the word count of the precompile input
+pub fn input_words(input: &[u8]) -> u64 {
+    (input.len() as u64 + 31) / 32
+}
+
+pub fn peek(stack: &Stack, n: usize) -> Result<U256, ExitError> {
+    let value = stack.peek(n)?;
+    Ok(U256::from_big_endian(value.as_bytes()))
+}
+
+pub fn peek_address(stack: &Stack, n: usize) -> Result<H160, ExitError> {
+    let value = stack.peek(n)?;
+    Ok(H160::from(value))
+}
+
+/// Static part of the opcode cost; the dynamic part is calculated by the handler
+pub fn static_cost(opcode: Opcode) -> Option<u64> {
+    let cost = match opcode.0 {
+        // STOP, RETURN, REVERT, INVALID
+        0x00 | 0xf3 | 0xfd | 0xfe => ZERO,
+        // ADDRESS, ORIGIN, CALLER, CALLVALUE, CALLDATASIZE, CODESIZE, GASPRICE, RETURNDATASIZE
+        0x30 | 0x32..=0x34 | 0x36 | 0x38 | 0x3a | 0x3d => BASE,
+        // COINBASE, TIMESTAMP, NUMBER, PREVRANDAO, GASLIMIT, CHAINID, BASEFEE, BLOBBASEFEE
+        0x41..=0x46 | 0x48 | 0x4a => BASE,
+        // POP, PC, MSIZE, GAS, PUSH0
+        0x50 | 0x58 | 0x59 | 0x5a | 0x5f => BASE,
+        // ADD, SUB, comparison and bitwise operations, BYTE, SHL, SHR, SAR
+        0x01 | 0x03 | 0x10..=0x1d => VERY_LOW,
+        // CALLDATALOAD, MLOAD, MSTORE, MSTORE8, PUSH1..PUSH32, DUP1..DUP16, SWAP1..SWAP16
+        0x35 | 0x51..=0x53 | 0x60..=0x9f => VERY_LOW,
+        // MUL, DIV, SDIV, MOD, SMOD, SIGNEXTEND, SELFBALANCE
+        0x02 | 0x04..=0x07 | 0x0b | 0x47 => LOW,
+        // ADDMOD, MULMOD, JUMP
+        0x08 | 0x09 | 0x56 => MID,
+        0x57 => HIGH,
+        0x5b => JUMPDEST,
+        0x40 => BLOCKHASH,
+        0x0a => EXP,
+        0x20 => SHA3,
+        // CALLDATACOPY, CODECOPY, RETURNDATACOPY, MCOPY
+        0x37 | 0x39 | 0x3e | 0x5e => VERY_LOW,
+        // BLOBHASH
+        0x49 => VERY_LOW,
+        // TLOAD, TSTORE
+        0x5c | 0x5d => WARM_ACCESS,
+        // BALANCE, EXTCODESIZE, EXTCODECOPY, EXTCODEHASH, SLOAD, SSTORE
+        0x31 | 0x3b | 0x3c | 0x3f | 0x54 | 0x55 => ZERO,
+        // LOG0..LOG4
+        0xa0..=0xa4 => LOG + LOG_TOPIC * (opcode.0 - 0xa0) as u64,
+        // CREATE, CREATE2
+        0xf0 | 0xf5 => CREATE,
+        // CALL, CALLCODE, DELEGATECALL, STATICCALL
+        0xf1 | 0xf2 | 0xf4 | 0xfa => ZERO,
+        0xff => SELFDESTRUCT,
+        _ => return None,
+    };
+
+    Some(cost)
+}
+
+/// Dynamic part of the opcode cost that depends only on the stack and the frame memory
+pub fn dynamic_cost(opcode: Opcode, stack: &Stack, frame: &mut Gasometer) -> Result<u64, ExitError> {
+    let cost = match opcode.0 {
+        // EXP
+        0x0a => {
+            let power = peek(stack, 1)?;
+            EXP_BYTE * ((power.bits() as u64 + 7) / 8)
+        }
+        // SHA3
+        0x20 => {
+            let len = peek(stack, 1)?;
+            frame.expand_memory(peek(stack, 0)?, len)?;
+            SHA3_WORD * words(len)?
+        }
+        // CALLDATACOPY, CODECOPY, RETURNDATACOPY
+        0x37 | 0x39 | 0x3e => {
+            let len = peek(stack, 2)?;
+            frame.expand_memory(peek(stack, 0)?, len)?;
+            COPY_WORD * words(len)?
+        }
+        // EXTCODECOPY
+        0x3c => {
+            let len = peek(stack, 3)?;
+            frame.expand_memory(peek(stack, 1)?, len)?;
+            COPY_WORD * words(len)?
+        }
+        // MCOPY
+        0x5e => {
+            let len = peek(stack, 2)?;
+            frame.expand_memory(peek(stack, 0)?, len)?;
+            frame.expand_memory(peek(stack, 1)?, len)?;
+            COPY_WORD * words(len)?
+        }
+        // MLOAD, MSTORE
+        0x51 | 0x52 => {
+            frame.expand_memory(peek(stack, 0)?, 32.into())?;
+            0
+        }
+        // MSTORE8
+        0x53 => {
+            frame.expand_memory(peek(stack, 0)?, 1.into())?;
+            0
+        }
+        // LOG0..LOG4
+        0xa0..=0xa4 => {
+            let len = peek(stack, 1)?;
+            frame.expand_memory(peek(stack, 0)?, len)?;
+            if len > U256::from(u32::MAX) {
+                return Err(ExitError::OutOfGas);
+            }
+            LOG_DATA * len.as_u64()
+        }
+        // CREATE
+        0xf0 => {
+            let len = peek(stack, 2)?;
+            frame.expand_memory(peek(stack, 1)?, len)?;
+            INITCODE_WORD * words(len)?
+        }
+        // CREATE2
+        0xf5 => {
+            let len = peek(stack, 2)?;
+            frame.expand_memory(peek(stack, 1)?, len)?;
+            (INITCODE_WORD + SHA3_WORD) * words(len)?
+        }
+        // CALL, CALLCODE
+        0xf1 | 0xf2 => {
+            frame.expand_memory(peek(stack, 3)?, peek(stack, 4)?)?;
+            frame.expand_memory(peek(stack, 5)?, peek(stack, 6)?)?;
+            if peek(stack, 2)?.is_zero() { 0 } else { CALL_VALUE }
+        }
+        // DELEGATECALL, STATICCALL
+        0xf4 | 0xfa => {
+            frame.expand_memory(peek(stack, 2)?, peek(stack, 3)?)?;
+            frame.expand_memory(peek(stack, 4)?, peek(stack, 5)?)?;
+            0
+        }
+        // RETURN, REVERT
+        0xf3 | 0xfd => {
+            frame.expand_memory(peek(stack, 0)?, peek(stack, 1)?)?;
+            0
+        }
+        _ => 0,
+    };
+
+    Ok(cost)
+}
+
+/// SSTORE cost and refund according to EIP-2200, EIP-2929 and EIP-3529
+pub fn sstore_cost(original: U256, current: U256, new: U256) -> (u64, i64) {
+    if current == new {
+        return (WARM_ACCESS, 0);
+    }
+
+    if original == current {
+        if original.is_zero() {
+            return (SSTORE_SET, 0);
+        }
+        let refund = if new.is_zero() { SSTORE_CLEARS_REFUND } else { 0 };
+        return (SSTORE_RESET, refund);
+    }
+
+    let mut refund = 0;
+    if !original.is_zero() {
+        if current.is_zero() {
+            refund -= SSTORE_CLEARS_REFUND;
+        } else if new.is_zero() {
+            refund += SSTORE_CLEARS_REFUND;
+        }
+    }
+    if original == new {
+        if original.is_zero() {
+            refund += (SSTORE_SET - WARM_ACCESS) as i64;
+        } else {
+            refund += (SSTORE_RESET - WARM_ACCESS) as i64;
+        }
+    }
+
+    (WARM_ACCESS, refund)
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, evm::H256};
+
+    #[test]
+    fn intrinsic() {
+        assert_eq!(intrinsic_gas(&[], false), 21_000);
+        assert_eq!(intrinsic_gas(&[0, 1, 0, 2], false), 21_000 + 2 * 4 + 2 * 16);
+        assert_eq!(intrinsic_gas(&[1; 33], true), 21_000 + 33 * 16 + 32_000 + 2 * 2);
+    }
+
+    #[test]
+    fn access_list() {
+        use crate::tx::eip2930::AccessListItem;
+
+        let address = H160::repeat_byte(1);
+        let list = AccessList(vec![
+            AccessListItem {
+                address,
+                storage_keys: vec![H256::zero(), H256::repeat_byte(2)],
+            },
+            AccessListItem {
+                address: H160::repeat_byte(3),
+                storage_keys: vec![],
+            },
+        ]);
+        assert_eq!(access_list_gas(&list), 2 * 2_400 + 2 * 1_900);
+
+        let mut gas = Gas::default();
+        gas.warm_access_list(&list);
+        assert_eq!(gas.access_address(&address), 100);
+        assert_eq!(gas.access_slot(&address, &U256::zero()), 100);
+        assert_eq!(gas.access_slot(&address, &U256::one()), 2_100);
+    }
+
+    #[test]
+    fn precompile() {
+        let mut gas = Gas::default();
+        gas.push_frame(10_000);
+        let limit = gas.call_limit(Some(5_000)).unwrap();
+        gas.exit_precompile(limit, 3_000, &ExitReason::Succeed(evm::ExitSucceed::Returned));
+        assert_eq!(gas.left(), Some(7_000));
+
+        let limit = gas.call_limit(Some(5_000)).unwrap();
+        gas.exit_precompile(limit, 3_000, &ExitReason::Error(ExitError::OutOfGas));
+        assert_eq!(gas.left(), Some(2_000));
+
+        let mut gas = Gas {
+            intrinsic: 21_000,
+            ..Default::default()
+        };
+        gas.exit_precompile(50_000, 60, &ExitReason::Succeed(evm::ExitSucceed::Returned));
+        assert_eq!(gas.used, 21_060);
+    }
+
+    #[test]
+    fn memory_expansion() {
+        let mut frame = Gasometer::new(1_000_000);
+        frame.expand_memory(0.into(), 32.into()).unwrap();
+        assert_eq!(frame.used, 3);
+        // no expansion
+        frame.expand_memory(0.into(), 32.into()).unwrap();
+        assert_eq!(frame.used, 3);
+        frame.expand_memory(32.into(), 1024.into()).unwrap();
+        assert_eq!(frame.memory_words, 33);
+        assert_eq!(frame.used, memory_cost(33));
+
+        let mut frame = Gasometer::new(10);
+        assert_eq!(
+            frame.expand_memory(0.into(), 1024.into()),
+            Err(ExitError::OutOfGas)
+        );
+    }
+
+    #[test]
+    fn all_but_one_64th() {
+        let mut gas = Gas::default();
+        assert_eq!(gas.call_limit(Some(1000)), Ok(1000));
+
+        gas.push_frame(6400);
+        assert_eq!(gas.call_limit(None), Ok(6300));
+        assert_eq!(gas.left(), Some(100));
+
+        let mut gas = Gas::default();
+        gas.push_frame(6400);
+        assert_eq!(gas.call_limit(Some(100)), Ok(100));
+        assert_eq!(gas.left(), Some(6300));
+    }
+
+    #[test]
+    fn sstore() {
+        let (zero, one, two) = (U256::zero(), U256::one(), U256::from(2));
+
+        assert_eq!(sstore_cost(zero, zero, one), (20_000, 0));
+        assert_eq!(sstore_cost(one, one, two), (2_900, 0));
+        assert_eq!(sstore_cost(one, one, zero), (2_900, 4_800));
+        assert_eq!(sstore_cost(one, one, one), (100, 0));
+        assert_eq!(sstore_cost(zero, one, zero), (100, 19_900));
+        assert_eq!(sstore_cost(one, zero, one), (100, -4_800 + 2_800));
+    }
+
+    #[test]
+    fn refund_cap() {
+        let mut gas = Gas {
+            intrinsic: 21_000,
+            ..Default::default()
+        };
+        gas.push_frame(100_000);
+        gas.frame().unwrap().charge(50_000).unwrap();
+        gas.frame().unwrap().refund = 20_000;
+        gas.pop_frame(&ExitReason::Succeed(evm::ExitSucceed::Stopped));
+        // (21_000 + 50_000) / 5
+        assert_eq!(gas.used, 21_000 + 50_000 - 14_200);
+    }
+}
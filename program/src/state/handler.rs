@@ -1,17 +1,25 @@
 use {
     super::{aux::Ix, JournaledState},
     crate::{
+        error::RomeProgramError,
         origin::Origin,
         precompile::{ non_evm_program,},
-        state::{Allocate, Diff},
+        state::{
+            gasometer::{
+                dynamic_cost, peek, peek_address, sstore_cost, static_cost, CALL_STIPEND,
+                NEW_ACCOUNT,
+            },
+            tracer::{CallKind, TraceCall},
+            Allocate, Diff,
+        },
         non_evm::Program,
+        vm::encode_error,
+        BLOCK_GAS_LIMIT,
     },
     evm::{
         Capture, Context, CreateScheme, ExitError, ExitReason, Handler, Machine, Opcode, Stack,
-        Transfer, H160, H256, U256, ExitSucceed::Returned,
-        ExitFatal::{self, TransferProhibited, NonEvmCallError, NonEvmStaticModeViolation,
-                    DelegateCallProhibited
-        },
+        Transfer, H160, H256, U256, ExitSucceed::Returned, ExitRevert::Reverted,
+        ExitFatal::{self, NonEvmCallError},
     },
     solana_program::{keccak::hash, msg,},
     std::convert::Infallible,
@@ -22,7 +30,9 @@ pub struct CallInterrupt {
     pub transfer: Option<Transfer>,
     pub input: Vec<u8>,
     pub is_static: bool,
+    pub kind: CallKind,
     pub context: Context,
+    pub gas_limit: u64,
 }
 
 pub struct CreateInterrupt {
@@ -30,6 +40,8 @@ pub struct CreateInterrupt {
     pub transfer: Option<Transfer>,
     pub address: H160,
     pub init_code: Vec<u8>,
+    pub gas_limit: u64,
+    pub is_create2: bool,
 }
 
 impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
@@ -112,12 +124,13 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
     }
 
     fn gas_left(&self) -> U256 {
-        // todo!()
-        U256::max_value()
+        self.gas
+            .left()
+            .map_or_else(|| self.gas_limit.unwrap_or_default(), U256::from)
     }
 
     fn gas_price(&self) -> U256 {
-        U256::one() // todo!()
+        self.gas_price.unwrap_or_default()
     }
 
     fn origin(&self) -> H160 {
@@ -138,7 +151,7 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
     }
 
     fn block_coinbase(&self) -> H160 {
-        H160::default()
+        self.block_coinbase
     }
 
     fn block_timestamp(&self) -> U256 {
@@ -149,11 +162,11 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
         U256::zero()
     }
     fn block_gas_limit(&self) -> U256 {
-        U256::max_value()
+        BLOCK_GAS_LIMIT.into()
     }
 
     fn chain_id(&self) -> U256 {
-        U256::from(self.state.base().chain)
+        self.chain_id
     }
 
     fn set_storage(&mut self, address: H160, index: U256, value: U256) -> Result<(), ExitError> {
@@ -172,6 +185,9 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
             return Err(ExitError::StaticModeViolation);
         }
 
+        if let Some(tracer) = self.state.log_tracer() {
+            tracer.log(address, &topics, &data);
+        }
         self.journal
             .get_mut(&address)
             .push(Diff::Event { topics, data });
@@ -208,7 +224,7 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
         scheme: CreateScheme,
         value: U256,
         init_code: Vec<u8>,
-        _target_gas: Option<u64>,
+        target_gas: Option<u64>,
     ) -> Capture<(ExitReason, Option<H160>, Vec<u8>), Self::CreateInterrupt> {
         if !self.mutable {
             return Capture::Exit((
@@ -220,6 +236,7 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
         if !value.is_zero() && self.balance(caller) < value {
             return Capture::Exit((ExitReason::Error(ExitError::OutOfFund), None, vec![]));
         }
+        let is_create2 = matches!(scheme, CreateScheme::Create2 { .. });
         let new_addr = self.build_address(scheme);
 
         if new_addr.is_err() {
@@ -227,6 +244,12 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
             return Capture::Exit(res);
         }
         let new_addr = new_addr.unwrap();
+        self.gas.addresses.insert(new_addr);
+
+        let gas_limit = match self.gas.call_limit(target_gas) {
+            Ok(gas_limit) => gas_limit,
+            Err(e) => return Capture::Exit((ExitReason::Error(e), None, vec![])),
+        };
 
         let context = evm::Context {
             address: new_addr,
@@ -249,6 +272,8 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
             transfer,
             address: new_addr,
             init_code,
+            gas_limit,
+            is_create2,
         };
 
         Capture::Trap(create)
@@ -259,12 +284,14 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
         code_address: H160,
         transfer: Option<Transfer>,
         input: Vec<u8>,
-        _target_gas: Option<u64>,
+        target_gas: Option<u64>,
         is_static: bool,
         context: Context,
     ) -> Capture<(ExitReason, Vec<u8>), Self::CallInterrupt> {
 
         let static_call = !self.mutable || is_static;
+        // the transaction and eth_call enter the contract by CALL
+        let kind = self.call_kind.take().unwrap_or(CallKind::Call);
 
         if let Some(transfer) = transfer.as_ref() {
             if !transfer.value.is_zero() && static_call {
@@ -280,17 +307,52 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
         }
 
         if let Some(program) =  non_evm_program(&code_address, self.state) {
-            let (reason, value) =
-                self.non_evm_call(program, &code_address, &transfer, &input, static_call, &context);
+            let gas_limit = match self.gas.call_limit(target_gas) {
+                Ok(gas_limit) => gas_limit,
+                Err(e) => return Capture::Exit((ExitReason::Error(e), vec![])),
+            };
+            let cost = program.gas(&input);
+
+            let (reason, value) = if cost > gas_limit {
+                (ExitReason::Error(ExitError::OutOfGas), vec![])
+            } else {
+                self.non_evm_call(
+                    program, &code_address, &transfer, &input, static_call, kind, &context,
+                )
+            };
+            self.gas.exit_precompile(gas_limit, cost, &reason);
+
             return Capture::Exit((reason, value))
         }
 
+        let is_nested = !self.gas.frames.is_empty();
+        // EIP-7702: the access to the delegation target is charged to the caller
+        if let Some(delegate) = self.delegation(code_address) {
+            let cost = self.gas.access_address(&delegate);
+            if is_nested {
+                if let Err(e) = self.gas.frame().and_then(|frame| frame.charge(cost)) {
+                    return Capture::Exit((ExitReason::Error(e), vec![]));
+                }
+            }
+        }
+
+        let mut gas_limit = match self.gas.call_limit(target_gas) {
+            Ok(gas_limit) => gas_limit,
+            Err(e) => return Capture::Exit((ExitReason::Error(e), vec![])),
+        };
+
+        if is_nested && transfer.as_ref().map_or(false, |x| !x.value.is_zero()) {
+            gas_limit = gas_limit.saturating_add(CALL_STIPEND);
+        }
+
         let call = CallInterrupt {
             code_address,
             transfer,
             input,
             is_static: static_call,
+            kind,
             context,
+            gas_limit,
         };
 
         Capture::Trap(call)
@@ -298,11 +360,19 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
 
     fn pre_validate(
         &mut self,
-        _context: &Context,
-        _opcode: Opcode,
-        _stack: &Stack,
+        context: &Context,
+        opcode: Opcode,
+        stack: &Stack,
     ) -> Result<(), ExitError> {
-        Ok(())
+        self.call_kind = match opcode.0 {
+            0xf1 => Some(CallKind::Call),
+            0xf2 => Some(CallKind::CallCode),
+            0xf4 => Some(CallKind::DelegateCall),
+            0xfa => Some(CallKind::StaticCall),
+            _ => None,
+        };
+        let cost = self.opcode_cost(context, opcode, stack)?;
+        self.gas.frame()?.charge(cost)
     }
 
     fn call_feedback(&mut self, _feedback: Self::CallFeedback) -> Result<(), ExitError> {
@@ -340,6 +410,76 @@ impl<T: Origin + Allocate> Handler for JournaledState<'_, T> {
 }
 
 impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
+    fn opcode_cost(
+        &mut self,
+        context: &Context,
+        opcode: Opcode,
+        stack: &Stack,
+    ) -> Result<u64, ExitError> {
+        let cost = match static_cost(opcode) {
+            Some(cost) => cost,
+            None => return Ok(0), // unknown opcode is handled by Handler::other()
+        };
+        let dynamic = dynamic_cost(opcode, stack, self.gas.frame()?)?;
+        let address = context.address;
+
+        let access = match opcode.0 {
+            // BALANCE, EXTCODESIZE, EXTCODECOPY, EXTCODEHASH
+            0x31 | 0x3b | 0x3c | 0x3f => self.gas.access_address(&peek_address(stack, 0)?),
+            // SLOAD
+            0x54 => self.gas.access_slot(&address, &peek(stack, 0)?),
+            // SSTORE
+            0x55 => {
+                if self.gas.left().unwrap_or_default() <= CALL_STIPEND {
+                    return Err(ExitError::OutOfGas);
+                }
+
+                let index = peek(stack, 0)?;
+                let new = peek(stack, 1)?;
+                let current = self.storage(address, index);
+                let original = self
+                    .state
+                    .storage(&address, &index)
+                    .map_or(U256::zero(), |opt| opt.unwrap_or_default());
+
+                let (cost, refund) = sstore_cost(original, current, new);
+                self.gas.frame()?.refund += refund;
+                cost + self.gas.sstore_access(&address, &index)
+            }
+            // CALL, CALLCODE, DELEGATECALL, STATICCALL
+            0xf1 | 0xf2 | 0xf4 | 0xfa => {
+                let target = peek_address(stack, 1)?;
+                let access = self.gas.access_address(&target);
+
+                if opcode.0 == 0xf1 && !peek(stack, 2)?.is_zero() && self.is_empty(target) {
+                    access + NEW_ACCOUNT
+                } else {
+                    access
+                }
+            }
+            // SELFDESTRUCT
+            0xff => {
+                let target = peek_address(stack, 0)?;
+                let access = self.gas.selfdestruct_access(&target);
+
+                if !self.balance(address).is_zero() && self.is_empty(target) {
+                    access + NEW_ACCOUNT
+                } else {
+                    access
+                }
+            }
+            _ => 0,
+        };
+
+        Ok(cost + dynamic + access)
+    }
+
+    fn is_empty(&self, address: H160) -> bool {
+        self.nonce(address).is_zero()
+            && self.balance(address).is_zero()
+            && self.code_size(address).is_zero()
+    }
+
     pub fn non_evm_call(
         &mut self,
         program: Box<dyn Program + 'a>,
@@ -347,9 +487,17 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
         transfer: &Option<Transfer>,
         input: &[u8],
         static_call: bool,
+        kind: CallKind,
         context: &Context,
     ) -> (ExitReason, Vec<u8>) {
 
+        if let Some(tracer) = self.state.tracer() {
+            tracer.enter(TraceCall::new(code_address, transfer, input, kind, context, 0));
+        }
+        if let Some(tracer) = self.state.log_tracer() {
+            tracer.enter();
+        }
+
         // TODO: exclude a creation of a new_page for eth_call.
         // Currently it is necessary to save the origin's NonceInc
         // in case of a call without snapshot
@@ -359,22 +507,21 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
             // precompiled contract doesn't have special method "receive() external payable {}"
             // => it should not be possible to send funds to such contracts.
             // TODO: check this assumption
-            if let Some(transfer) = transfer {
-                if !transfer.value.is_zero() {
-                    // TODO: replace by revert for single_state
-                    msg!("TransferProhibited");
-                    return (ExitReason::Fatal(TransferProhibited), vec![])
-                }
-            }
-
-            // TODO: no need to clone the non_evm_state for eth_call
-            let non_evm_state = self.journal.non_evm_state();
-
-            match program.eth_call(input, non_evm_state) {
-                Ok(val) => (ExitReason::Succeed(Returned), val),
-                Err(e) => {
-                    msg!("non-evm call error: {}", e.to_string());
-                    (ExitReason::Fatal(NonEvmCallError), vec![])
+            let transfer_prohibited = transfer
+                .as_ref()
+                .map(|transfer| !transfer.value.is_zero())
+                .unwrap_or(false);
+
+            if transfer_prohibited {
+                non_evm_revert(RomeProgramError::TransferProhibited(*code_address).to_string())
+            } else {
+                // TODO: no need to clone the non_evm_state for eth_call
+                let non_evm_state = self.journal.non_evm_state();
+
+                match program.eth_call(input, non_evm_state) {
+                    Ok(val) => (ExitReason::Succeed(Returned), val),
+                    Err(e) if program.is_precompile() => precompile_error(e),
+                    Err(e) => non_evm_error("non-evm call error", e),
                 }
             }
         } else {
@@ -387,10 +534,21 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
             // TODO: overwrite the previous non-evm-state instead of saving the new one
             self.journal.merge_page();
         }
+        self.trace_non_evm_exit(&reason, &val);
 
         (reason, val)
     }
 
+    // non-evm programs don't consume the evm gas
+    fn trace_non_evm_exit(&self, reason: &ExitReason, output: &[u8]) {
+        if let Some(tracer) = self.state.tracer() {
+            tracer.exit(reason, output, 0);
+        }
+        if let Some(tracer) = self.state.log_tracer() {
+            tracer.exit(reason.is_succeed());
+        }
+    }
+
     pub fn non_evm_tx(
         &mut self,
         code_address: &H160,
@@ -402,32 +560,27 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
     ) -> (ExitReason, Vec<u8>) {
 
         if is_static {
-            msg!("StaticModeViolation");
-            return (ExitReason::Fatal(NonEvmStaticModeViolation), vec![])
+            return non_evm_revert(RomeProgramError::NonEvmStaticModeViolation(*code_address).to_string())
         }
 
         if context.address != *code_address {
-            msg!("DelegateCallProhibited");
-            return (ExitReason::Fatal(DelegateCallProhibited), vec![])
+            return non_evm_revert(RomeProgramError::DelegateCallProhibited(*code_address).to_string())
         }
 
         if let Some(transfer) = transfer {
             if !transfer.value.is_zero() && !program.transfer_allowed() {
-                msg!("TransferProhibited");
-                return (ExitReason::Fatal(TransferProhibited), vec![])
+                return non_evm_revert(RomeProgramError::TransferProhibited(*code_address).to_string())
             }
         }
 
         let (ix, seed, evm_diff) = match program.ix_from_abi(input, context) {
             Ok(x) => x,
-            Err(e) => {
-                msg!("error to parse non-evm tx: {}", e.to_string());
-                return (ExitReason::Fatal(NonEvmCallError), vec![])
-            }
+            Err(e) => return non_evm_error("error to parse non-evm tx", e),
         };
 
         let non_evm_state = self.journal.non_evm_state();
 
+        // the accounts of the instruction are not visible to the evm, the tx can't be executed without them
         let mut binds = match non_evm_state.ix_accounts_mut(self.state, &ix) {
             Ok(binds) => binds,
             Err(e) => {
@@ -436,13 +589,9 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
             }
         };
 
-        match program.emulate(&ix, &mut binds) {
-            Ok(x) => x,
-            Err(e) => {
-                msg!("error to emulate non-evm tx: {}", e.to_string());
-                return (ExitReason::Fatal(NonEvmCallError), vec![])
-            }
-        };
+        if let Err(e) = program.emulate(&ix, &mut binds) {
+            return non_evm_error("error to emulate non-evm tx", e)
+        }
 
         for (addr, diff) in evm_diff {
             self.journal.get_mut(&addr).push(diff);
@@ -454,3 +603,74 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
         (ExitReason::Succeed(Returned), vec![])
     }
 }
+
+// the failure of the non-evm program is revertible: the caller receives Error(string) and can
+// handle it, ExitReason::Fatal is reserved for the violations of the invariants of the vm
+fn non_evm_revert(message: String) -> (ExitReason, Vec<u8>) {
+    msg!("{}", message);
+    (ExitReason::Revert(Reverted), encode_error(&message))
+}
+
+// the failed standard precompile fails as the evm call: the output is empty, the forwarded gas is consumed
+fn precompile_error(e: RomeProgramError) -> (ExitReason, Vec<u8>) {
+    msg!("precompile error: {}", e);
+    (ExitReason::Error(ExitError::Other(e.to_string().into())), vec![])
+}
+
+// the missing accounts and the allocation limits are the errors of the Solana transaction,
+// not of the state observed by the evm, they are not revertible
+fn non_evm_error(context: &str, e: RomeProgramError) -> (ExitReason, Vec<u8>) {
+    match e {
+        RomeProgramError::AccountNotFound(..)
+        | RomeProgramError::PdaAccountNotFound(..)
+        | RomeProgramError::AllocationError(..)
+        | RomeProgramError::AccountLocked(..)
+        | RomeProgramError::AttemptWriteRoAccount(..)
+        | RomeProgramError::TooManyAccounts(..) => {
+            msg!("{}: {}", context, e.to_string());
+            (ExitReason::Fatal(NonEvmCallError), vec![])
+        }
+        e => non_evm_revert(format!("{}: {}", context, e)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{non_evm_error, precompile_error},
+        crate::{error::RomeProgramError::*, vm::RevertInfo, AccountType},
+        evm::{ExitFatal::NonEvmCallError, ExitReason},
+        solana_program::pubkey::Pubkey,
+    };
+
+    #[test]
+    fn solana_errors_are_fatal() {
+        let key = Pubkey::new_unique();
+        let errors = vec![
+            AccountNotFound(key),
+            PdaAccountNotFound(key, AccountType::Balance),
+            AllocationError("limit".to_string()),
+        ];
+
+        for e in errors {
+            let (reason, value) = non_evm_error("test", e);
+            assert_eq!(reason, ExitReason::Fatal(NonEvmCallError));
+            assert!(value.is_empty());
+        }
+    }
+
+    #[test]
+    fn program_errors_revert() {
+        let (reason, value) = non_evm_error("test", InvalidNonEvmInstructionData);
+        assert!(reason.is_revert());
+        let reason = RevertInfo::decode(&value).reason.unwrap();
+        assert!(reason.starts_with("test: "));
+    }
+
+    #[test]
+    fn precompile_errors_fail() {
+        let (reason, value) = precompile_error(InvalidPrecompileInput("test".to_string()));
+        assert!(reason.is_error());
+        assert!(value.is_empty());
+    }
+}
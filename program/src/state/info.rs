@@ -85,7 +85,7 @@ pub trait Info {
 
         assert_eq!(valids.len(), evm::Valids::size_needed(code.len()));
 
-        AccountState::check_no_contract(info, address)?;
+        // the account is checked by AccountState::check_set_code and resized by the caller
         {
             let mut code_mut = Code::from_account_mut(info)?;
             assert!(code_mut.len() == code.len());
@@ -98,7 +98,7 @@ pub trait Info {
         }
         {
             let mut state = AccountState::from_account_mut(info)?;
-            state.is_contract = true;
+            state.is_contract = !code.is_empty();
         }
         Ok(())
     }
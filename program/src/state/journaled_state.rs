@@ -1,16 +1,15 @@
 use {
-    super::{Diff, Journal},
+    super::{gasometer::Gas, tracer::CallKind, Diff, Journal},
     crate::{
         context::AccountLock, error::RomeProgramError::*, error::*, origin::Origin,
         pda::Seed, state::Allocate, NUMBER_ALLOC_DIFF_PER_TX,
+        tx::eip7702::{delegation, DELEGATION_LEN},
     },
     borsh::{BorshDeserialize, BorshSerialize},
     evm::{Handler, H160, H256, U256},
     solana_program::{
-        clock::Clock,
         keccak::{hash, hashv, Hash},
         pubkey::Pubkey,
-        sysvar::Sysvar,
     },
     std::collections::{BTreeMap, BTreeSet, HashMap, HashSet,},
 };
@@ -31,17 +30,22 @@ pub struct JournaledState<'a, T: Origin + Allocate> {
     pub block_number: U256,
     pub block_timestamp: U256,
     pub slot: u64,
+    pub block_coinbase: H160,
+    pub chain_id: U256,
     pub origin: Option<H160>,
     pub gas_limit: Option<U256>,
     pub gas_price: Option<U256>,
     pub gas_recipient: Option<H160>,
     pub merged_slots: BTreeMap<H160, HashSet<U256>>,
+    pub gas: Gas,
+    // the kind of the call opcode being executed, the Handler::call doesn't receive the opcode
+    pub call_kind: Option<CallKind>,
 }
 
 impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
     #[allow(dead_code)]
     pub fn new(state: &'a T) -> Result<Self> {
-        let clock = Clock::get()?;
+        let clock = state.base().clock()?;
         let journaled_state = Self {
             state,
             journal: Journal::new(),
@@ -49,11 +53,15 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
             block_number: clock.slot.into(),
             block_timestamp: clock.unix_timestamp.into(),
             slot: clock.slot,
+            block_coinbase: H160::default(),
+            chain_id: state.base().chain.into(),
             origin: None,
             gas_limit: None,
             gas_price: None,
             gas_recipient: None,
             merged_slots: BTreeMap::new(),
+            gas: Gas::default(),
+            call_kind: None,
         };
 
         Ok(journaled_state)
@@ -82,6 +90,12 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
         self.journal = Journal::new()
     }
 
+    // reverts the pages of the call frames, the base page keeps the changes made before the top-level
+    // call, e.g. the EIP-7702 authorizations
+    pub fn revert_frames(&mut self) {
+        self.journal = self.journal.revert_page(1)
+    }
+
     pub fn get_and_revert_all(&mut self) -> Journal {
         let mut journal = Journal::new();
         std::mem::swap(&mut self.journal, &mut journal);
@@ -93,6 +107,22 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
         self.journal.get_mut(&address).push(diff);
     }
 
+    // EIP-7702: returns the delegation target if the account code is the delegation designator
+    pub fn delegation(&self, address: H160) -> Option<H160> {
+        if self.code_size(address) != DELEGATION_LEN.into() {
+            return None;
+        }
+
+        delegation(&self.code(address))
+    }
+
+    // the code of the delegated account is loaded from the delegation target,
+    // EXTCODESIZE, EXTCODECOPY and EXTCODEHASH observe the designator itself
+    pub fn executable_code(&self, address: H160) -> (Vec<u8>, Vec<u8>) {
+        let address = self.delegation(address).unwrap_or(address);
+        (self.code(address), self.valids(address))
+    }
+
     pub fn build_address(&self, scheme: evm::CreateScheme) -> Result<H160> {
         let address = match scheme {
             evm::CreateScheme::Legacy { caller } => {
@@ -148,6 +178,7 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
         self.gas_price.serialize(into)?;
         self.gas_recipient.serialize(into)?;
         self.merged_slots.serialize(into)?;
+        self.gas.serialize(into)?;
         Ok(())
     }
 
@@ -162,6 +193,7 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
         let gas_price: Option<U256> = BorshDeserialize::deserialize(from)?;
         let gas_recipient: Option<H160> = BorshDeserialize::deserialize(from)?;
         let merged_slots: BTreeMap<H160, HashSet<U256>> = BorshDeserialize::deserialize(from)?;
+        let gas: Gas = BorshDeserialize::deserialize(from)?;
 
         Ok(Self {
             state,
@@ -175,6 +207,8 @@ impl<'a, T: Origin + Allocate> JournaledState<'a, T> {
             gas_price,
             gas_recipient,
             merged_slots,
+            gas,
+            call_kind: None,
         })
     }
 
@@ -1,6 +1,7 @@
 pub mod allocate;
 pub mod aux;
 pub mod base;
+pub mod gasometer;
 pub mod handler;
 pub mod info;
 mod journal;
@@ -9,6 +10,8 @@ pub mod origin;
 pub mod pda;
 #[allow(clippy::module_inception)]
 mod state;
+pub mod sysvars;
+pub mod tracer;
 
 pub use allocate::*;
 pub use aux::Account;
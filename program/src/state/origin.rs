@@ -1,15 +1,16 @@
 use {
     crate::{
-        accounts::{AccountState, Data},
+        accounts::{AccountState, ChainConfig, Data},
         context::AccountLock,
         error::{Result, RomeProgramError::*},
         info::Info,
-        state::{base::Base, State},
-        Code, Account, EVENT_LOG, pda::Seed,
+        state::{base::Base, tracer::{LogTracer, StepTracer, Tracer}, State},
+        Code, Account, pda::Seed,
+        log_data::log_event,
     },
     evm::{H160, H256, U256},
     solana_program::{
-        clock::Slot, instruction::Instruction, log::sol_log_data, program::{
+        clock::Slot, instruction::Instruction, program::{
             invoke_signed, invoke,
         },
         pubkey::Pubkey, sysvar::recent_blockhashes,
@@ -53,51 +54,32 @@ pub trait Origin {
     ) -> Result<()>;
     fn block_hash(&self, block: U256, slot: Slot) -> Result<H256>;
     fn set_logs(&self, address: &H160, topics: &[H256], data: &[u8]) -> Result<()> {
-        match topics.len() {
-            0 => sol_log_data(&[EVENT_LOG, address.as_bytes(), &[0_u8], data]),
-            1 => sol_log_data(&[
-                EVENT_LOG,
-                address.as_bytes(),
-                &[1_u8],
-                topics[0].as_bytes(),
-                data,
-            ]),
-            2 => sol_log_data(&[
-                EVENT_LOG,
-                address.as_bytes(),
-                &[2_u8],
-                topics[0].as_bytes(),
-                topics[1].as_bytes(),
-                data,
-            ]),
-            3 => sol_log_data(&[
-                EVENT_LOG,
-                address.as_bytes(),
-                &[3_u8],
-                topics[0].as_bytes(),
-                topics[1].as_bytes(),
-                topics[2].as_bytes(),
-                data,
-            ]),
-            4 => sol_log_data(&[
-                EVENT_LOG,
-                address.as_bytes(),
-                &[4_u8],
-                topics[0].as_bytes(),
-                topics[1].as_bytes(),
-                topics[2].as_bytes(),
-                topics[3].as_bytes(),
-                data,
-            ]),
-            _ => panic!("vm fault, event logs topics.len > 4"),
-        }
-
+        log_event(address, topics, data);
         Ok(())
     }
     fn base(&self) -> &Base;
+    fn config(&self) -> &ChainConfig {
+        &self.base().config
+    }
     fn account(&self, key: &Pubkey) -> Result<Account>;
     fn invoke_signed(&self, ix: &Instruction, seed: &Seed, refund_to_signer: bool) -> Result<()>;
     fn signer(&self) -> Pubkey;
+    // the declared post-state of the account changed by the program invoked by the generic CPI,
+    // the emulator does not execute the program. On-chain the program is invoked for real
+    fn invoke_hint(&self, _program_id: &Pubkey, _key: &Pubkey) -> Option<Account> {
+        None
+    }
+    // the program is called by the generic CPI, the emulator applies the hints to its instructions only
+    fn track_invoke(&self, _program_id: &Pubkey) {}
+    fn tracer(&self) -> Option<&dyn Tracer> {
+        None
+    }
+    fn step_tracer(&self) -> Option<&dyn StepTracer> {
+        None
+    }
+    fn log_tracer(&self) -> Option<&dyn LogTracer> {
+        None
+    }
 }
 
 impl Origin for State<'_> {
@@ -158,7 +140,7 @@ impl Origin for State<'_> {
         context: &L,
     ) -> Result<()> {
         let info = self.info_addr(address, true)?;
-        AccountState::check_no_contract(info, address)?;
+        AccountState::check_set_code(info, address, code)?;
 
         let len = info.data_len();
         let offset = Code::offset(info);
@@ -169,6 +151,10 @@ impl Origin for State<'_> {
             Less => {
                 self.realloc(info, required)?;
             }
+            // EIP-7702: the delegation designator is cleared
+            Greater if AccountState::is_delegated(info)? => {
+                self.realloc(info, required)?;
+            }
             Greater => {
                 // TODO: implement deallocation of the unused contract space
                 return Err(Unimplemented(
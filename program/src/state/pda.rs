@@ -1,13 +1,14 @@
 use {
     crate::{
         error::Result, state::base::Syscall, AccountState, AccountType, Data, AltId,
-        OwnerInfo, RoLock, StateHolder, Storage, TxHolder, ACCOUNT_SEED, OWNER_INFO, RO_LOCK_SEED,
-        STATE_HOLDER_SEED, STORAGE_LEN, TX_HOLDER_SEED, CONTRACT_SOL_WALLET, ALT_SLOTS,
+        OwnerInfo, RoLock, RollupConfig, StateHolder, Storage, TxHolder, ACCOUNT_SEED, OWNER_INFO,
+        RO_LOCK_SEED, STATE_HOLDER_SEED, STORAGE_LEN, TX_HOLDER_SEED, CONTRACT_SOL_WALLET, ALT_SLOTS,
+        ROLLUP_CONFIG,
     },
     borsh::{BorshDeserialize, BorshSerialize},
     evm::{H160, U256},
     solana_program::{account_info::AccountInfo, pubkey::Pubkey},
-    std::{cell::RefCell, collections::HashMap, rc::Rc},
+    std::{cell::RefCell, collections::HashMap, sync::Arc},
 };
 
 #[derive(BorshSerialize, BorshDeserialize, Default, Clone)]
@@ -44,11 +45,11 @@ pub struct Pda<'a> {
     pub balance: RefCell<HashMap<H160, (Pubkey, Seed)>>,
     pub storage: RefCell<HashMap<BaseIndex, (Pubkey, Seed)>>,
     pub ro_lock: RefCell<HashMap<Pubkey, (Pubkey, Seed)>>,
-    pub syscall: Rc<Syscall>,
+    pub syscall: Arc<Syscall>,
 }
 
 impl<'a> Pda<'a> {
-    pub fn new(program_id: &'a Pubkey, chain: u64, syscall: Rc<Syscall>) -> Self {
+    pub fn new(program_id: &'a Pubkey, chain: u64, syscall: Arc<Syscall>) -> Self {
         Self {
             chain: chain.to_le_bytes().to_vec(),
             program_id,
@@ -66,7 +67,7 @@ impl<'a> Pda<'a> {
             balance: RefCell::new(HashMap::new()),
             storage: RefCell::new(HashMap::new()),
             ro_lock: RefCell::new(HashMap::new()),
-            syscall: Rc::new(Syscall::new()),
+            syscall: Arc::new(Syscall::new()),
         }
     }
 
@@ -183,6 +184,15 @@ impl<'a> Pda<'a> {
         (key, seed)
     }
 
+    pub fn rollup_config_key(&self) -> (Pubkey, Seed) {
+        let mut seed = Seed {
+            items: vec![self.chain.clone(), ROLLUP_CONFIG.to_vec()],
+        };
+        let (key, bump_seed) = self.find_pda(&seed);
+        seed.add(bump_seed);
+        (key, seed)
+    }
+
     pub fn owner_info_key(&self) -> (Pubkey, Seed) {
         let mut seed = Seed {
             items: vec![OWNER_INFO.to_vec()],
@@ -202,6 +212,7 @@ impl<'a> Pda<'a> {
             AccountType::RoLock => RoLock::init(info),
             AccountType::OwnerInfo => OwnerInfo::init(info),
             AccountType::AltSlots => AltId::init(info),
+            AccountType::RollupConfig => RollupConfig::init(info),
         }
     }
     pub fn empty_size(info: &AccountInfo, typ: &AccountType) -> usize {
@@ -214,6 +225,7 @@ impl<'a> Pda<'a> {
             AccountType::RoLock => RoLock::offset(info),
             AccountType::OwnerInfo => OwnerInfo::offset(info),
             AccountType::AltSlots => AltId::offset(info) + AltId::size(info),
+            AccountType::RollupConfig => RollupConfig::offset(info) + RollupConfig::size(info),
         }
     }
     pub fn serialize(&self, into: &mut &mut [u8]) -> Result<()> {
@@ -4,11 +4,11 @@ use {
         pda::{Pda, Seed},
         origin::Origin,
     },
-    crate::{error::RomeProgramError::*, error::*, AccountType, OwnerInfo, Data},
+    crate::{error::RomeProgramError::*, error::*, AccountType, ChainConfig, OwnerInfo, Data},
     evm::{H160, U256},
     solana_program::{
-        account_info::AccountInfo, pubkey::Pubkey, rent::Rent,
-        system_program, sysvar::recent_blockhashes, sysvar::Sysvar,
+        account_info::AccountInfo, pubkey::Pubkey,
+        system_program, sysvar::recent_blockhashes,
     },
     solana_system_interface::instruction::{
         create_account, transfer
@@ -36,9 +36,10 @@ impl<'a> State<'a> {
         accounts: &'a [AccountInfo<'a>],
         chain: u64,
     ) -> Result<Self> {
-        let state = Self::new_unchecked(program_id, accounts, chain)?;
+        let mut state = Self::new_unchecked(program_id, accounts, chain)?;
         let info = state.info_owner_reg(false)?;
         OwnerInfo::check_chain(info, chain)?;
+        state.base.config = state.load_config()?;
 
         Ok(state)
     }
@@ -92,6 +93,27 @@ impl<'a> State<'a> {
         let (key, seed) = self.pda.alt_slots_key(self.signer.key, index);
         self.info_pda(&key, &seed, AccountType::AltSlots, or_create)
     }
+    pub fn info_rollup_config(&self, or_create: bool) -> Result<&'a AccountInfo<'a>> {
+        let (key, seed) = self.pda.rollup_config_key();
+        self.info_pda(&key, &seed, AccountType::RollupConfig, or_create)
+    }
+    // the config account must be passed to the instruction,
+    // the defaults are used if the account is not created by set_rollup_config
+    fn load_config(&self) -> Result<ChainConfig> {
+        let (key, _) = self.pda.rollup_config_key();
+        let info = self
+            .all
+            .get(&key)
+            .cloned()
+            .ok_or(PdaAccountNotFound(key, AccountType::RollupConfig))?;
+
+        if system_program::check_id(info.owner) {
+            return Ok(ChainConfig::default());
+        }
+
+        let info = self.info_rollup_config(false)?;
+        ChainConfig::from_account(info)
+    }
     pub fn info_pda(
         &self,
         key: &Pubkey,
@@ -130,7 +152,7 @@ impl<'a> State<'a> {
             assert!(info.is_writable);
             assert_eq!(*info.owner, system_program::ID);
 
-            let rent = Rent::get()?.minimum_balance(0);
+            let rent = self.rent()?.minimum_balance(0);
             let ix = &create_account(
                 self.signer.key,
                 info.key,
@@ -180,7 +202,7 @@ impl<'a> State<'a> {
 
         info.realloc(len, false)?;
 
-        let rent = Rent::get()?.minimum_balance(info.data_len());
+        let rent = self.rent()?.minimum_balance(info.data_len());
         let is_paid = AccountType::from_account(info)?.is_paid();
 
         match rent.cmp(&info.lamports()) {
@@ -214,7 +236,7 @@ impl<'a> State<'a> {
         assert_eq!(*pda.owner, system_program::ID);
 
         let len = Pda::empty_size(pda, typ);
-        let rent = Rent::get()?.minimum_balance(len);
+        let rent = self.rent()?.minimum_balance(len);
 
     let ix = create_account(
             self.signer.key,
@@ -0,0 +1,32 @@
+#![allow(deprecated)] // TODO: remove and replace by PodSlotHashes
+use {
+    crate::error::Result,
+    solana_program::{
+        clock::{Clock, Slot},
+        rent::Rent,
+        sysvar::{slot_hashes::SlotHashesSysvar, Sysvar},
+    },
+};
+
+/// The source of the Solana sysvars. The program reads them by the syscalls,
+/// the emulator provides the sysvars loaded together with the accounts of the emulation.
+pub trait SysvarProvider: Send + Sync {
+    fn clock(&self) -> Result<Clock>;
+    fn rent(&self) -> Result<Rent>;
+    // the position of the slot in the SlotHashes sysvar
+    fn slot_hash_position(&self, slot: &Slot) -> Result<Option<usize>>;
+}
+
+pub struct Syscalls;
+
+impl SysvarProvider for Syscalls {
+    fn clock(&self) -> Result<Clock> {
+        Ok(Clock::get()?)
+    }
+    fn rent(&self) -> Result<Rent> {
+        Ok(Rent::get()?)
+    }
+    fn slot_hash_position(&self, slot: &Slot) -> Result<Option<usize>> {
+        Ok(SlotHashesSysvar::position(slot)?)
+    }
+}
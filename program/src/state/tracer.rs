@@ -0,0 +1,199 @@
+use {
+    super::handler::{CallInterrupt, CreateInterrupt},
+    evm::{Context, ExitReason, Transfer, H160, H256, U256},
+    std::collections::BTreeMap,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallKind {
+    Call,
+    StaticCall,
+    DelegateCall,
+    CallCode,
+    Create,
+    Create2,
+}
+
+/// The call frame entered by the vm
+#[derive(Clone, Debug)]
+pub struct TraceCall {
+    pub kind: CallKind,
+    pub from: H160,
+    pub to: H160,
+    pub value: U256,
+    pub gas: u64,
+    pub input: Vec<u8>,
+}
+
+impl TraceCall {
+    pub fn new(
+        code_address: &H160,
+        transfer: &Option<Transfer>,
+        input: &[u8],
+        kind: CallKind,
+        context: &Context,
+        gas: u64,
+    ) -> Self {
+        // CALLCODE and DELEGATECALL execute the foreign code in the context of the caller
+        let from = match kind {
+            CallKind::CallCode | CallKind::DelegateCall => context.address,
+            _ => context.caller,
+        };
+
+        let value = match kind {
+            CallKind::DelegateCall => context.apparent_value,
+            _ => transfer.as_ref().map(|x| x.value).unwrap_or_default(),
+        };
+
+        Self {
+            kind,
+            from,
+            to: *code_address,
+            value,
+            gas,
+            input: input.to_vec(),
+        }
+    }
+
+    pub fn from_call(call: &CallInterrupt) -> Self {
+        Self::new(
+            &call.code_address,
+            &call.transfer,
+            &call.input,
+            call.kind,
+            &call.context,
+            call.gas_limit,
+        )
+    }
+
+    pub fn from_create(create: &CreateInterrupt) -> Self {
+        Self {
+            kind: if create.is_create2 { CallKind::Create2 } else { CallKind::Create },
+            from: create.context.caller,
+            to: create.address,
+            value: create.context.apparent_value,
+            gas: create.gas_limit,
+            input: create.init_code.clone(),
+        }
+    }
+}
+
+/// The state of the account touched by the transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TraceAccount {
+    pub balance: U256,
+    pub nonce: u64,
+    pub code: Vec<u8>,
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// Execution hooks, the emulator implements them to build the call and prestate traces.
+/// The hooks are not invoked on-chain, Origin::tracer() returns None for the program state.
+pub trait Tracer {
+    // the vm starts the transaction from the beginning
+    fn reset(&self);
+    fn enter(&self, call: TraceCall);
+    fn exit(&self, reason: &ExitReason, output: &[u8], gas_used: u64);
+    // the touched accounts before and after the transaction
+    fn state(&self, pre: BTreeMap<H160, TraceAccount>, post: BTreeMap<H160, TraceAccount>);
+}
+
+/// The vm state before the opcode execution
+#[derive(Clone, Debug)]
+pub struct TraceStep {
+    pub pc: usize,
+    pub opcode: u8,
+    pub gas: u64,
+    pub gas_cost: u64,
+    pub depth: usize,
+    pub stack: Option<Vec<H256>>,
+    pub memory: Option<Vec<u8>>,
+    // the slot accessed by SLOAD or SSTORE: contract | slot | value
+    pub storage: Option<(H160, U256, U256)>,
+}
+
+/// Opcode-level hook, the vm executes the opcodes one by one if the step tracer is installed
+pub trait StepTracer {
+    fn reset(&self);
+    fn capture_stack(&self) -> bool;
+    fn capture_memory(&self) -> bool;
+    fn step(&self, step: TraceStep);
+}
+
+/// Collects the logs in the order of the emission, the frames delimit the logs of the reverted calls
+pub trait LogTracer {
+    fn reset(&self);
+    fn enter(&self);
+    fn log(&self, address: H160, topics: &[H256], data: &[u8]);
+    fn exit(&self, succeed: bool);
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::{CallKind, TraceCall},
+        evm::{Context, Transfer, H160, U256},
+    };
+
+    fn call(code_address: H160, transfer: Option<Transfer>, kind: CallKind) -> TraceCall {
+        let context = Context {
+            address: H160::repeat_byte(2),
+            caller: H160::repeat_byte(1),
+            apparent_value: U256::from(7),
+        };
+        TraceCall::new(&code_address, &transfer, &[], kind, &context, 100)
+    }
+
+    #[test]
+    fn call_kind() {
+        let transfer = Transfer {
+            source: H160::repeat_byte(1),
+            target: H160::repeat_byte(2),
+            value: U256::from(5),
+        };
+
+        let trace = call(H160::repeat_byte(2), Some(transfer), CallKind::Call);
+        assert_eq!(trace.kind, CallKind::Call);
+        assert_eq!(trace.from, H160::repeat_byte(1));
+        assert_eq!(trace.value, U256::from(5));
+
+        let trace = call(H160::repeat_byte(2), None, CallKind::StaticCall);
+        assert_eq!(trace.kind, CallKind::StaticCall);
+        assert_eq!(trace.from, H160::repeat_byte(1));
+        assert_eq!(trace.value, U256::zero());
+    }
+
+    #[test]
+    fn foreign_code() {
+        let trace = call(H160::repeat_byte(3), None, CallKind::DelegateCall);
+        assert_eq!(trace.kind, CallKind::DelegateCall);
+        assert_eq!(trace.from, H160::repeat_byte(2));
+        assert_eq!(trace.to, H160::repeat_byte(3));
+        assert_eq!(trace.value, U256::from(7));
+
+        let transfer = Transfer {
+            source: H160::repeat_byte(2),
+            target: H160::repeat_byte(2),
+            value: U256::from(5),
+        };
+        let trace = call(H160::repeat_byte(3), Some(transfer), CallKind::CallCode);
+        assert_eq!(trace.kind, CallKind::CallCode);
+        assert_eq!(trace.from, H160::repeat_byte(2));
+        assert_eq!(trace.value, U256::from(5));
+    }
+
+    // the kind is not derived from the context
+    #[test]
+    fn opcode_kind() {
+        // DELEGATECALL of the own code
+        let trace = call(H160::repeat_byte(2), None, CallKind::DelegateCall);
+        assert_eq!(trace.kind, CallKind::DelegateCall);
+        assert_eq!(trace.value, U256::from(7));
+
+        // CALL without the value transfer
+        let trace = call(H160::repeat_byte(3), None, CallKind::Call);
+        assert_eq!(trace.kind, CallKind::Call);
+        assert_eq!(trace.from, H160::repeat_byte(1));
+        assert_eq!(trace.value, U256::zero());
+    }
+}
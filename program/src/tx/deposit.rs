@@ -7,7 +7,6 @@ use {
     evm::{H160, H256, U256},
     rlp::Rlp,
 };
-#[cfg(test)]
 use crate::tx::eip2930::AccessList;
 
 #[derive(Debug, Clone)]
@@ -55,9 +54,11 @@ impl Base for Deposit {
     fn set_from(&mut self, _: H160) {
         unreachable!()
     }
-    #[cfg(test)]
     fn access_list(&self) -> Option<&AccessList> {
-        unreachable!()
+        None
+    }
+    fn authorization_list(&self) -> Option<&super::eip7702::AuthorizationList> {
+        None
     }
     fn mint(&self) -> U256 {
         self.mint
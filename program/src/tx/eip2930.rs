@@ -24,7 +24,6 @@ pub struct Eip2930 {
     pub to: Option<H160>,
     pub value: U256,
     pub data: Option<Vec<u8>>,
-    #[allow(dead_code)]
     pub access_list: AccessList,
     pub recovery_id: u8,
     pub r: U256,
@@ -72,10 +71,12 @@ impl Base for Eip2930 {
     fn set_from(&mut self, from: H160) {
         self.from = from;
     }
-    #[cfg(test)]
     fn access_list(&self) -> Option<&AccessList> {
         Some(&self.access_list)
     }
+    fn authorization_list(&self) -> Option<&super::eip7702::AuthorizationList> {
+        None
+    }
     fn mint(&self) -> U256 {
         unreachable!()
     }
@@ -0,0 +1,191 @@
+use {
+    super::{eip2930::AccessList, fix, rlp_at, rlp_header, tx::Tx, Base},
+    crate::{
+        error::{Result, RomeProgramError::*},
+        tx::{check_rlp, decode_to},
+    },
+    evm::{H160, H256, U256},
+    rlp::{Rlp, RlpStream},
+    solana_program::keccak::hashv,
+};
+
+// the prefix of the message signed by the authority
+pub const AUTHORIZATION_MAGIC: u8 = 0x05;
+// the code of the delegated account: 0xef0100 || address
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+pub const DELEGATION_LEN: usize = 23;
+// secp256k1n / 2
+const SECP256K1_HALF_N: [u8; 32] = [
+    0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+#[derive(Debug, Clone, rlp::RlpEncodable, rlp::RlpDecodable, PartialEq)]
+pub struct Authorization {
+    pub chain_id: U256,
+    pub address: H160,
+    pub nonce: u64,
+    pub y_parity: u8,
+    pub r: U256,
+    pub s: U256,
+}
+
+#[derive(Debug, Clone, rlp::RlpEncodableWrapper, rlp::RlpDecodableWrapper, PartialEq)]
+pub struct AuthorizationList(pub Vec<Authorization>);
+
+impl Authorization {
+    // keccak(0x05 || rlp([chain_id, address, nonce]))
+    pub fn hash_unsign(&self) -> H256 {
+        let mut rlp = RlpStream::new_list(3);
+        rlp.append(&self.chain_id);
+        rlp.append(&self.address);
+        rlp.append(&self.nonce);
+
+        H256::from(hashv(&[&[AUTHORIZATION_MAGIC], &rlp.out()]).to_bytes())
+    }
+
+    // recovers the account that delegates its code
+    pub fn authority(&self) -> Result<H160> {
+        if self.y_parity > 1 {
+            return Err(InvalidEthereumSignature("authorization y_parity".to_string()));
+        }
+        // EIP-2: the signature malleability
+        let half_n = U256::from_big_endian(&SECP256K1_HALF_N);
+        if self.s > half_n {
+            return Err(InvalidEthereumSignature("authorization s value".to_string()));
+        }
+
+        Tx::recover(self.hash_unsign().as_bytes(), self.y_parity, self.r, self.s)
+    }
+
+    pub fn designator(&self) -> Vec<u8> {
+        let mut code = DELEGATION_PREFIX.to_vec();
+        code.extend_from_slice(self.address.as_bytes());
+        code
+    }
+}
+
+// returns the address of the delegated code
+pub fn delegation(code: &[u8]) -> Option<H160> {
+    if code.len() == DELEGATION_LEN && code.starts_with(&DELEGATION_PREFIX) {
+        Some(H160::from_slice(&code[DELEGATION_PREFIX.len()..]))
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Eip7702 {
+    pub chain_id: U256,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub gas_limit: U256,
+    pub to: H160,
+    pub value: U256,
+    pub data: Option<Vec<u8>>,
+    pub access_list: AccessList,
+    pub authorization_list: AuthorizationList,
+    pub recovery_id: u8,
+    pub r: U256,
+    pub s: U256,
+    pub from: H160,
+}
+
+impl Base for Eip7702 {
+    fn nonce(&self) -> u64 {
+        self.nonce
+    }
+    fn to(&self) -> Option<H160> {
+        Some(self.to)
+    }
+    fn value(&self) -> U256 {
+        self.value
+    }
+    fn data(&mut self) -> Option<Vec<u8>> {
+        self.data.take()
+    }
+    fn gas_limit(&self) -> U256 {
+        self.gas_limit
+    }
+    // the effective gas price min(max_fee_per_gas, base_fee + max_priority_fee_per_gas),
+    // the base fee of the rollup is zero
+    fn gas_price(&self) -> U256 {
+        self.max_priority_fee_per_gas.min(self.max_fee_per_gas)
+    }
+    fn hash_unsign(&self, rlp: &Rlp) -> Result<H256> {
+        let rlp2 = rlp_at(rlp, 10)?;
+        let rlp1 = rlp_header(rlp2.len());
+
+        Ok(H256::from(hashv(&[&[4], &rlp1, rlp2]).to_bytes()))
+    }
+    fn rs(&self) -> (U256, U256) {
+        (self.r, self.s)
+    }
+    fn recovery_id(&self) -> Result<u8> {
+        Ok(self.recovery_id)
+    }
+    fn chain_id(&self) -> u64 {
+        self.chain_id.as_u64()
+    }
+    fn from(&self) -> H160 {
+        self.from
+    }
+    fn set_from(&mut self, from: H160) {
+        self.from = from;
+    }
+    fn access_list(&self) -> Option<&AccessList> {
+        Some(&self.access_list)
+    }
+    fn authorization_list(&self) -> Option<&AuthorizationList> {
+        Some(&self.authorization_list)
+    }
+    fn mint(&self) -> U256 {
+        unreachable!()
+    }
+}
+impl Eip7702 {
+    pub fn rlp_at_chain_id(rlp: &rlp::Rlp) -> Result<U256> {
+        let chain = fix(rlp, 0)?;
+        Ok(chain)
+    }
+    pub fn from_rlp(rlp: &rlp::Rlp) -> Result<Self> {
+        check_rlp(rlp, 13)?;
+
+        let chain_id = Eip7702::rlp_at_chain_id(rlp)?;
+        let nonce: u64 = rlp.val_at(1)?;
+        let max_priority_fee_per_gas = fix(rlp, 2)?;
+        let max_fee_per_gas = fix(rlp, 3)?;
+        let gas_limit = fix(rlp, 4)?;
+        let to = decode_to(rlp, 5)?
+            .ok_or(Custom("RLP: set-code transaction can not create a contract".to_string()))?;
+        let value = fix(rlp, 6)?;
+        let data = rlp.val_at(7)?;
+        let access_list = rlp.val_at(8)?;
+        let authorization_list: AuthorizationList = rlp.val_at(9)?;
+        let recovery_id: u8 = rlp.at(10)?.as_val()?;
+        let r = fix(rlp, 11)?;
+        let s = fix(rlp, 12)?;
+
+        if authorization_list.0.is_empty() {
+            return Err(Custom("RLP: empty authorization list".to_string()));
+        }
+
+        Ok(Eip7702 {
+            chain_id,
+            nonce,
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit,
+            to,
+            value,
+            data: Some(data),
+            access_list,
+            authorization_list,
+            recovery_id,
+            r,
+            s,
+            from: H160::default(),
+        })
+    }
+}
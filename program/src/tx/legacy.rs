@@ -78,10 +78,12 @@ impl Base for Legacy {
     fn set_from(&mut self, from: H160) {
         self.from = from;
     }
-    #[cfg(test)]
     fn access_list(&self) -> Option<&super::eip2930::AccessList> {
         None
     }
+    fn authorization_list(&self) -> Option<&super::eip7702::AuthorizationList> {
+        None
+    }
     fn mint(&self) -> U256 {
         unreachable!()
     }
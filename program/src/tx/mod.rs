@@ -1,5 +1,6 @@
 mod eip1559;
-mod eip2930;
+pub mod eip2930;
+pub mod eip7702;
 pub mod legacy;
 #[allow(clippy::module_inception)]
 pub mod tx;
@@ -25,8 +26,8 @@ pub trait Base {
     fn chain_id(&self) -> u64;
     fn from(&self) -> H160;
     fn set_from(&mut self, from: H160);
-    #[cfg(test)]
     fn access_list(&self) -> Option<&eip2930::AccessList>;
+    fn authorization_list(&self) -> Option<&eip7702::AuthorizationList>;
     fn mint(&self) -> U256;
 }
 
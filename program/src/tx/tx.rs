@@ -2,9 +2,9 @@ use {
     super::{Base, Legacy},
     crate::{
         error::{Result, RomeProgramError::*},
-        tx::{eip1559::Eip1559, eip2930::Eip2930},
+        tx::{eip1559::Eip1559, eip2930::Eip2930, eip7702::Eip7702},
     },
-    evm::H160,
+    evm::{H160, U256},
     rlp::Rlp,
     solana_program::{keccak::hash, msg, secp256k1_recover::secp256k1_recover},
     std::ops::{Deref, DerefMut},
@@ -14,6 +14,7 @@ pub enum TxType<'a> {
     Legacy(Rlp<'a>),
     Eip2930(Rlp<'a>),
     Eip1559(Rlp<'a>),
+    Eip7702(Rlp<'a>),
     Deposit(Rlp<'a>),
 }
 
@@ -63,6 +64,8 @@ impl Tx {
             match first {
                 0x01 => Ok(TxType::Eip2930(rlp)),
                 0x02 => Ok(TxType::Eip1559(rlp)),
+                0x03 => Err(BlobTxNotSupported),
+                0x04 => Ok(TxType::Eip7702(rlp)),
                 0x7e => Ok(TxType::Deposit(rlp)),
                 _ => Err(Custom(format!("RLP: invalid tx type {first}"))),
             }
@@ -85,6 +88,10 @@ impl Tx {
                 let eip1559 = Eip1559::from_rlp(&rlp)?;
                 (Box::new(eip1559), rlp)
             },
+            TxType::Eip7702(rlp) => {
+                let eip7702 = Eip7702::from_rlp(&rlp)?;
+                (Box::new(eip7702), rlp)
+            },
             TxType::Deposit(_) => return Err(IncorrectRlpType)
         };
 
@@ -101,6 +108,7 @@ impl Tx {
             TxType::Legacy(rlp) => Legacy::rlp_at_chain_id(&rlp)?,
             TxType::Eip2930(rlp) => Eip2930::rlp_at_chain_id(&rlp)?,
             TxType::Eip1559(rlp) => Eip1559::rlp_at_chain_id(&rlp)?,
+            TxType::Eip7702(rlp) => Eip7702::rlp_at_chain_id(&rlp)?,
             TxType::Deposit(_) => return Err(IncorrectRlpType),
         };
 
@@ -108,16 +116,18 @@ impl Tx {
     }
 
     fn recovery_from(tx: &dyn Base, rlp: &Rlp) -> Result<H160> {
-        let mut rs = [0_u8; 64];
-
         let (r, s) = tx.rs();
-        r.to_big_endian(&mut rs[0..32]);
-        s.to_big_endian(&mut rs[32..64]);
-
         let recovery_id = tx.recovery_id()?;
         let hash = tx.hash_unsign(rlp)?;
 
-        let pub_key = Tx::syscall(hash.as_bytes(), recovery_id, &rs)?;
+        Tx::recover(hash.as_bytes(), recovery_id, r, s)
+    }
+    pub fn recover(hash: &[u8], recovery_id: u8, r: U256, s: U256) -> Result<H160> {
+        let mut rs = [0_u8; 64];
+        r.to_big_endian(&mut rs[0..32]);
+        s.to_big_endian(&mut rs[32..64]);
+
+        let pub_key = Tx::syscall(hash, recovery_id, &rs)?;
         let from = H160::from_slice(&pub_key[12..]);
 
         Ok(from)
@@ -132,8 +142,7 @@ impl Tx {
         Ok(pub_key)
     }
 
-    #[cfg(test)]
-    fn access_list(&self) -> Option<&super::eip2930::AccessList> {
+    pub fn access_list(&self) -> Option<&super::eip2930::AccessList> {
         self.tx.access_list()
     }
     #[cfg(test)]
@@ -147,9 +156,13 @@ impl Tx {
 #[cfg(test)]
 mod tests {
     use {
-        crate::tx::{
-            eip2930::{AccessList, AccessListItem},
-            tx::Tx,
+        crate::{
+            error::RomeProgramError::BlobTxNotSupported,
+            tx::{
+                eip2930::{AccessList, AccessListItem},
+                eip7702::delegation,
+                tx::Tx,
+            },
         },
         evm::{H160, U256},
     };
@@ -299,6 +312,65 @@ mod tests {
         assert_eq!(tx.to(), Some(H160::from_slice(&to)));
     }
 
+    #[test]
+    fn eip7702() {
+        let raw_tx = hex::decode("04f901280107843b9aca008477359400830186a09435353535353535353535353535353535353535358084d09de08ac0f8b7f859019442424242424242424242424242424242424242428001a06a3c8acd727e3524036793627114cd486d50b3a33f106f0cf3ea22916ee163be9f9cd9a53e82e20cf5fb2d985ad0dacf1e5ca9d8db484d00520b9250d98a6964f85a809442424242424242424242424242424242424242420180a002b876d9b6ab9f97dcf20119688319079b63efe6e817a63fe077632c3bcd83f6a067a0797f51713b72b06fa879ac3239a783524b761f82378e3e1adb4fd1a06cff80a0f6d677388c9e6d7898f1ed387fd1a507f0c48f5aa74ac4afc0cc72abd60b2b11a03022b30cd537a6d3f5873707786a688e2d770d46dcf8b242ffc6f1d269b86a65").unwrap();
+        let mut tx = Tx::from_instruction(&raw_tx).unwrap();
+        let from = hex::decode("2c7536e3605d9c16a7a3d7b1898e529396a65c23").unwrap();
+        let to = hex::decode("3535353535353535353535353535353535353535").unwrap();
+        let authority = hex::decode("63fac9201494f0bd17b9892b9fae4d52fe3bd377").unwrap();
+        let delegate = hex::decode("4242424242424242424242424242424242424242").unwrap();
+
+        assert_eq!(tx.from(), H160::from_slice(&from));
+        assert_eq!(tx.to(), Some(H160::from_slice(&to)));
+        assert_eq!(tx.chain_id(), 1_u64);
+        assert_eq!(tx.nonce(), 7_u64);
+        assert_eq!(tx.gas_limit(), 100_000_u64.into());
+        assert_eq!(tx.gas_price(), 1_000_000_000_u64.into());
+        assert_eq!(tx.data().unwrap(), hex::decode("d09de08a").unwrap());
+        assert_eq!(tx.access_list().unwrap().0.len(), 0);
+
+        let list = tx.authorization_list().unwrap();
+        assert_eq!(list.0.len(), 2);
+        assert_eq!(list.0[0].chain_id, 1_u64.into());
+        assert_eq!(list.0[0].nonce, 0);
+        assert_eq!(list.0[1].chain_id, 0_u64.into());
+        assert_eq!(list.0[1].nonce, 1);
+
+        for auth in list.0.iter() {
+            assert_eq!(auth.address, H160::from_slice(&delegate));
+            assert_eq!(auth.authority().unwrap(), H160::from_slice(&authority));
+
+            let code = auth.designator();
+            assert_eq!(code.len(), 23);
+            assert_eq!(delegation(&code), Some(auth.address));
+        }
+
+        // the high s value is rejected
+        let mut auth = list.0[0].clone();
+        auth.s = U256::MAX - auth.s;
+        assert!(auth.authority().is_err());
+
+        assert_eq!(Tx::chain_id_from_rlp(&raw_tx).unwrap(), 1_u64);
+    }
+
+    #[test]
+    fn delegation_designator() {
+        let mut code = hex::decode("ef01004242424242424242424242424242424242424242").unwrap();
+        assert!(delegation(&code).is_some());
+        code.push(0);
+        assert!(delegation(&code).is_none());
+        assert!(delegation(&code[1..23]).is_none());
+        assert!(delegation(&[]).is_none());
+    }
+
+    #[test]
+    fn blob_tx() {
+        let raw_tx = hex::decode("03c0").unwrap();
+        assert!(matches!(Tx::tx_type(&raw_tx), Err(BlobTxNotSupported)));
+        assert!(matches!(Tx::from_instruction(&raw_tx), Err(BlobTxNotSupported)));
+    }
+
     #[test]
     #[should_panic]
     fn unknown_tx_type() {
@@ -1,9 +1,11 @@
+mod revert;
 mod snapshot;
 #[allow(clippy::module_inception)]
 mod vm;
 pub mod vm_atomic;
 pub mod vm_iterative;
 
+pub use revert::*;
 pub use snapshot::*;
 pub use vm::*;
 pub use vm_atomic::*;
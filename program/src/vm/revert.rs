@@ -0,0 +1,166 @@
+use {
+    crate::config::{REVERT_ERROR, REVERT_PANIC},
+    evm::U256,
+    std::mem::size_of,
+};
+
+/// The decoded return value of the reverted transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RevertInfo {
+    pub data: Vec<u8>,
+    // Error(string)
+    pub reason: Option<String>,
+    // Panic(uint256)
+    pub panic_code: Option<U256>,
+    pub panic_reason: Option<&'static str>,
+    // the selector of the custom error
+    pub selector: Option<[u8; 4]>,
+    // the signature of the custom error, the emulator resolves it using the contract ABI
+    pub custom_error: Option<String>,
+}
+
+impl RevertInfo {
+    pub fn decode(data: &[u8]) -> Self {
+        let mut info = Self {
+            data: data.to_vec(),
+            ..Self::default()
+        };
+
+        if let Some(msg) = data.strip_prefix(REVERT_ERROR) {
+            info.reason = error_string(msg);
+        } else if let Some(msg) = data.strip_prefix(REVERT_PANIC) {
+            if msg.len() == size_of::<U256>() {
+                let code = U256::from_big_endian(msg);
+                info.panic_code = Some(code);
+                info.panic_reason = Some(panic_reason(code));
+            }
+        } else if data.len() >= 4 {
+            let mut selector = [0_u8; 4];
+            selector.copy_from_slice(&data[..4]);
+            info.selector = Some(selector);
+        }
+
+        info
+    }
+}
+
+// Error(string): selector | offset | len | bytes
+pub fn encode_error(reason: &str) -> Vec<u8> {
+    let word = size_of::<U256>();
+    let mut data = REVERT_ERROR.to_vec();
+    let mut push = |value: usize| {
+        let mut buf = [0_u8; 32];
+        U256::from(value).to_big_endian(&mut buf);
+        data.extend_from_slice(&buf);
+    };
+    push(word);
+    push(reason.len());
+
+    let mut msg = reason.as_bytes().to_vec();
+    msg.resize((reason.len() + word - 1) / word * word, 0);
+    data.extend(msg);
+    data
+}
+
+// abi-encoded string: offset | len | bytes
+fn error_string(msg: &[u8]) -> Option<String> {
+    let word = size_of::<U256>();
+
+    let offset = msg.get(..word).map(U256::from_big_endian)?;
+    if offset != word.into() {
+        return None;
+    }
+    let len = msg.get(word..2 * word).map(U256::from_big_endian)?;
+    if len > U256::from(msg.len()) {
+        return None;
+    }
+    let msg = msg.get(2 * word..2 * word + len.as_usize())?;
+
+    Some(String::from_utf8_lossy(msg).to_string())
+}
+
+// https://docs.soliditylang.org/en/latest/control-structures.html#panic-via-assert-and-error-via-require
+pub fn panic_reason(code: U256) -> &'static str {
+    if code > u8::MAX.into() {
+        return "unknown panic code";
+    }
+
+    match code.as_u32() {
+        0x00 => "generic compiler panic",
+        0x01 => "assert(false)",
+        0x11 => "arithmetic overflow or underflow",
+        0x12 => "division or modulo by zero",
+        0x21 => "conversion to invalid enum value",
+        0x22 => "incorrectly encoded storage byte array",
+        0x31 => "pop() on empty array",
+        0x32 => "array index out of bounds",
+        0x41 => "out of memory",
+        0x51 => "call to zero-initialized function",
+        _ => "unknown panic code",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::RevertInfo,
+        crate::config::{REVERT_ERROR, REVERT_PANIC},
+        evm::U256,
+    };
+
+    fn word(value: usize) -> Vec<u8> {
+        let mut word = [0_u8; 32];
+        U256::from(value).to_big_endian(&mut word);
+        word.to_vec()
+    }
+
+    #[test]
+    fn error_string() {
+        let mut data = REVERT_ERROR.to_vec();
+        data.extend(word(32));
+        data.extend(word(4));
+        let mut msg = b"fail".to_vec();
+        msg.resize(32, 0);
+        data.extend(msg);
+
+        let info = RevertInfo::decode(&data);
+        assert_eq!(info.reason.as_deref(), Some("fail"));
+        assert_eq!(info.selector, None);
+        assert_eq!(info.data, data);
+    }
+
+    #[test]
+    fn panic() {
+        let mut data = REVERT_PANIC.to_vec();
+        data.extend(word(0x11));
+
+        let info = RevertInfo::decode(&data);
+        assert_eq!(info.panic_code, Some(U256::from(0x11)));
+        assert_eq!(info.panic_reason, Some("arithmetic overflow or underflow"));
+        assert_eq!(info.reason, None);
+    }
+
+    #[test]
+    fn custom_error() {
+        let mut data = vec![0xcf, 0x47, 0x91, 0x81];
+        data.extend(word(1));
+
+        let info = RevertInfo::decode(&data);
+        assert_eq!(info.selector, Some([0xcf, 0x47, 0x91, 0x81]));
+        assert_eq!(info.reason, None);
+        assert_eq!(info.panic_code, None);
+
+        assert_eq!(RevertInfo::decode(&[0x01, 0x02]).selector, None);
+    }
+
+    #[test]
+    fn encode_error_string() {
+        let data = super::encode_error("insufficient funds");
+        assert_eq!(data.len(), 4 + 3 * 32);
+        assert_eq!(RevertInfo::decode(&data).reason.as_deref(), Some("insufficient funds"));
+
+        let data = super::encode_error("");
+        assert_eq!(data.len(), 4 + 2 * 32);
+        assert_eq!(RevertInfo::decode(&data).reason.as_deref(), Some(""));
+    }
+}
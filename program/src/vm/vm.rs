@@ -1,22 +1,22 @@
 use {
     super::Snapshot,
     crate::{
-        config::{
-            EXIT_REASON, GAS_RECIPIENT, GAS_VALUE, REVERT_ERROR, REVERT_PANIC, RSOL_DECIMALS,
-            GAS_PRICE,
-        },
         error::{Result, RomeProgramError::*},
+        log_data::{log_exit, log_gas, log_gas_used},
         origin::Origin,
         state::{
+            gasometer::{access_list_gas, intrinsic_gas, Gas, CODE_DEPOSIT, PER_AUTHORIZATION},
             handler::{CallInterrupt, CreateInterrupt},
+            tracer::{TraceAccount, TraceCall, TraceStep},
             Allocate, Diff, JournaledState,
         },
-        tx::tx::Tx,
-        vm::Reason,
+        precompile::non_evm_program,
+        tx::{eip7702::{delegation, AuthorizationList}, tx::Tx},
+        vm::{Reason, RevertInfo},
     },
-    evm::{Capture, ExitReason, Handler, Resolve, H160, U256},
-    solana_program::{log::sol_log_data, msg},
-    std::mem::size_of,
+    evm::{Capture, ExitError, ExitReason, Handler, Resolve, H160, U256},
+    solana_program::msg,
+    std::collections::BTreeMap,
 };
 
 pub enum Trap {
@@ -54,7 +54,12 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
         true
     }
 
-    pub fn call_from_tx(&mut self, tx: &mut Tx) -> Capture<(ExitReason, Vec<u8>), CallInterrupt> {
+    pub fn call_from_tx(
+        &mut self,
+        tx: &Tx,
+        input: Vec<u8>,
+        gas_limit: u64,
+    ) -> Capture<(ExitReason, Vec<u8>), CallInterrupt> {
         let to = tx.to().unwrap();
         let context = evm::Context {
             address: to,
@@ -72,11 +77,9 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
             None
         };
 
-        let input = tx.data().unwrap();
-
         self
             .handler
-            .call(to, transfer, input, None, false, context)
+            .call(to, transfer, input, Some(gas_limit), false, context)
     }
 
     pub fn push_call_snapshot(&mut self, call: CallInterrupt) {
@@ -85,8 +88,13 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
             &hex::encode(call.context.caller),
             &hex::encode(call.context.address)
         );
-        let code = self.handler.code(call.code_address);
-        let valids = self.handler.valids(call.code_address);
+        if let Some(tracer) = self.handler.state.tracer() {
+            tracer.enter(TraceCall::from_call(&call));
+        }
+        if let Some(tracer) = self.handler.state.log_tracer() {
+            tracer.enter();
+        }
+        let (code, valids) = self.handler.executable_code(call.code_address);
         let runtime = evm::Runtime::new(code, valids, call.input, call.context);
 
         if self.snapshot.is_none() {
@@ -96,6 +104,8 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
         if let Some(transfer) = call.transfer {
             self.handler.transfer(&transfer.source, &transfer.target, &transfer.value);
         }
+        self.handler.gas.push_frame(call.gas_limit);
+
         let snapshot = Snapshot {
             evm: runtime,
             reason: Reason::Call,
@@ -112,6 +122,12 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
             &hex::encode(create.context.caller),
             &hex::encode(create.context.address)
         );
+        if let Some(tracer) = self.handler.state.tracer() {
+            tracer.enter(TraceCall::from_create(&create));
+        }
+        if let Some(tracer) = self.handler.state.log_tracer() {
+            tracer.enter();
+        }
         let valids = evm::Valids::compute(&create.init_code);
         let to = create.address;
         let caller = create.context.caller;
@@ -125,6 +141,8 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
         if let Some(transfer) = create.transfer {
             self.handler.transfer(&transfer.source, &transfer.target, &transfer.value);
         }
+        self.handler.gas.push_frame(create.gas_limit);
+
         let snapshot = Snapshot {
             evm: runtime,
             reason: Reason::Create(to),
@@ -158,11 +176,67 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
         None
     }
 
+    // EIP-7702: applies the authorizations of the set-code transaction, the invalid ones are skipped.
+    // TODO: refund PER_EMPTY_ACCOUNT_COST - PER_AUTH_BASE_COST for the existing authorities
+    fn apply_authorizations(&mut self, list: &AuthorizationList) {
+        let chain_id = self.handler.chain_id();
+        let origin = self.handler.origin.unwrap();
+
+        for auth in list.0.iter() {
+            if !auth.chain_id.is_zero() && auth.chain_id != chain_id {
+                continue;
+            }
+            if auth.nonce == u64::MAX {
+                continue;
+            }
+            let authority = match auth.authority() {
+                Ok(authority) => authority,
+                Err(e) => {
+                    msg!("authorization is skipped: {}", e);
+                    continue;
+                }
+            };
+            self.handler.gas.addresses.insert(authority);
+
+            // the contract code is immutable, the delegation designator can be replaced or cleared
+            let code = self.handler.code(authority);
+            if !code.is_empty() && delegation(&code).is_none() {
+                continue;
+            }
+            // the nonce of the origin is incremented before the authorizations are applied
+            let mut nonce = self.handler.nonce(authority);
+            if authority == origin {
+                nonce += U256::one();
+            }
+            if nonce != auth.nonce.into() {
+                continue;
+            }
+
+            if !auth.address.is_zero() {
+                self.handler.set_code(authority, auth.designator());
+            } else if !code.is_empty() {
+                // the zero address resets the account code to empty
+                self.handler.set_code(authority, vec![]);
+            }
+            self.handler.journal.get_mut(&authority).push(Diff::NonceChange);
+            msg!(
+                "authority {} delegates to {}",
+                &hex::encode(authority),
+                &hex::encode(auth.address)
+            );
+        }
+    }
+
     pub fn verify_gas_price(&self) -> Result<()> {
-        if self.handler.gas_recipient.is_some() {
-            if self.handler.gas_price.unwrap() < U256::exp10(RSOL_DECIMALS - 9) {
+        if let Some(recipient) = self.handler.gas_recipient {
+            let config = self.handler.state.config();
+
+            if self.handler.gas_price.unwrap() < config.min_gas_price {
                 return Err(InvalidGasPrice)
             }
+            if !config.is_fee_recipient_allowed(&recipient) {
+                return Err(FeeRecipientNotAllowed(recipient))
+            }
         }
 
         Ok(())
@@ -178,6 +252,16 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
         let from = tx.from();
         msg!("from {}", &hex::encode(from));
 
+        if let Some(tracer) = self.handler.state.tracer() {
+            tracer.reset();
+        }
+        if let Some(tracer) = self.handler.state.step_tracer() {
+            tracer.reset();
+        }
+        if let Some(tracer) = self.handler.state.log_tracer() {
+            tracer.reset();
+        }
+
         // TODO add test to eliminate the possibility of repeated transaction execution
         if check_nonce {
             let nonce = self.handler.nonce(from);
@@ -191,8 +275,36 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
         self.handler.gas_recipient = fee_recipient;
         self.verify_gas_price()?;
 
+        let data = tx.data().unwrap_or_default();
+        let authorizations = tx.authorization_list().map_or(0, |list| list.0.len() as u64);
+        let intrinsic = intrinsic_gas(&data, tx.to().is_none())
+            .saturating_add(authorizations.saturating_mul(PER_AUTHORIZATION))
+            .saturating_add(tx.access_list().map_or(0, access_list_gas));
+        let gas_limit = if tx.gas_limit() > u64::MAX.into() {
+            u64::MAX
+        } else {
+            tx.gas_limit().as_u64()
+        };
+        if gas_limit < intrinsic {
+            return Err(InsufficientGas(tx.gas_limit(), intrinsic.into()));
+        }
+
+        self.handler.gas.intrinsic = intrinsic;
+        self.handler.gas.used = intrinsic;
+        self.handler.gas.addresses.insert(from);
+        self.handler.gas.warm_precompiles();
+        if let Some(to) = tx.to() {
+            self.handler.gas.addresses.insert(to);
+        }
+        if let Some(list) = tx.access_list() {
+            self.handler.gas.warm_access_list(list);
+        }
+        if let Some(list) = tx.authorization_list() {
+            self.apply_authorizations(list);
+        }
+
         let trap = if tx.to().is_some() {
-            match self.call_from_tx(tx) {
+            match self.call_from_tx(tx, data, gas_limit - intrinsic) {
                 Capture::Trap(call) => Trap::Call(call),
                 Capture::Exit((reason, value)) => Trap::ExitNoShapshot(value, reason)
             }
@@ -203,8 +315,8 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
                     tx.from(),
                     evm::CreateScheme::Legacy { caller: tx.from() },
                     tx.value(),
-                    tx.data().unwrap(),
-                    None,
+                    data,
+                    Some(gas_limit - intrinsic),
                 );
 
             match capture {
@@ -221,12 +333,83 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
         snapshot: Box<Snapshot>,
         reason: ExitReason,
     ) -> Option<(Vec<u8>, ExitReason)> {
+        self.trace_exit(&snapshot, &reason);
+
         match snapshot.reason {
             Reason::Call => self.commit_exit_call(snapshot, reason),
             Reason::Create(address) => self.commit_exit_create(snapshot, reason, address),
         }
     }
 
+    fn trace_exit(&self, snapshot: &Snapshot, reason: &ExitReason) {
+        if let Some(tracer) = self.handler.state.tracer() {
+            // the failed frame consumes all the gas
+            let gas_used = self.handler.gas.frames.last().map_or(0, |frame| {
+                if reason.is_succeed() || reason.is_revert() {
+                    frame.used
+                } else {
+                    frame.limit
+                }
+            });
+            tracer.exit(reason, &snapshot.evm.machine().return_value(), gas_used);
+        }
+        if let Some(tracer) = self.handler.state.log_tracer() {
+            tracer.exit(reason.is_succeed());
+        }
+    }
+
+    // reports the touched accounts to the tracer before the journal is committed
+    fn trace_state(&self) {
+        let tracer = match self.handler.state.tracer() {
+            Some(tracer) => tracer,
+            None => return,
+        };
+        let state = self.handler.state;
+        let mut pre = BTreeMap::new();
+        let mut post = BTreeMap::new();
+
+        for address in self.handler.gas.addresses.iter() {
+            if non_evm_program(address, state).is_some() {
+                continue;
+            }
+
+            let slots = self
+                .handler
+                .gas
+                .slots
+                .range((*address, U256::zero())..=(*address, U256::MAX))
+                .map(|(_, slot)| *slot)
+                .collect::<Vec<_>>();
+
+            let account = TraceAccount {
+                balance: state.balance(address).unwrap_or_default(),
+                nonce: state.nonce(address).unwrap_or_default(),
+                code: state.code(address).unwrap_or_default(),
+                storage: slots
+                    .iter()
+                    .map(|slot| {
+                        let value = state.storage(address, slot).ok().flatten();
+                        (*slot, value.unwrap_or_default())
+                    })
+                    .collect(),
+            };
+            pre.insert(*address, account);
+
+            let account = TraceAccount {
+                balance: self.handler.balance(*address),
+                nonce: self.handler.nonce(*address).as_u64(),
+                code: self.handler.code(*address),
+                storage: slots
+                    .iter()
+                    .map(|slot| (*slot, self.handler.storage(*address, *slot)))
+                    .collect(),
+            };
+            post.insert(*address, account);
+        }
+
+        tracer.state(pre, post);
+    }
+
     pub fn commit_exit_call(
         &mut self,
         snapshot: Box<Snapshot>,
@@ -315,20 +498,9 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
             ExitReason::StepLimitReached => panic!("vm state machine fault: StepLimitReached"),
         };
 
-        let mut return_value = &vec![];
-        if let Some(value) = self.return_value.as_ref() {
-            return_value = value;
-        };
-
         let msg = format!("{:?}", exit_reason);
-        let msg_len = msg.len();
-        sol_log_data(&[
-            EXIT_REASON,
-            &[code],
-            &msg_len.to_le_bytes(),
-            msg.as_bytes(),
-            return_value,
-        ]);
+        let return_value = self.return_value.as_deref().unwrap_or_default();
+        log_exit(code, &msg, return_value);
         Ok(())
     }
 
@@ -339,30 +511,15 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
             return Ok(());
         };
 
-        if return_value.starts_with(REVERT_ERROR) {
-            let msg = &return_value[REVERT_ERROR.len()..];
-            let left = 64_usize;
-            let offset = 32_usize;
-
-            if msg.len() >= left {
-                let found = U256::from_big_endian(&msg[0..size_of::<U256>()]) == offset.into();
-                if found {
-                    let len = U256::from_big_endian(&msg[offset..left]).as_usize();
-                    let right = left.checked_add(len).ok_or(CalculationOverflow)?;
-                    if let Some(msg) = msg.get(left..right) {
-                        let msg = std::str::from_utf8(msg).unwrap_or("str::from_utf8() error");
-                        msg!("Revert: {:?}", msg);
-                    }
-                }
-            }
-        } else if return_value.starts_with(REVERT_PANIC) {
-            let msg = &return_value[REVERT_ERROR.len()..];
-            let len = size_of::<U256>();
-
-            if msg.len() == len {
-                let msg = U256::from_big_endian(&msg[0..len]);
-                msg!("Revert panic: {:?})", msg);
-            }
+        let info = RevertInfo::decode(return_value);
+        if let Some(reason) = info.reason.as_ref() {
+            msg!("Revert: {:?}", reason);
+        }
+        if let (Some(code), Some(reason)) = (info.panic_code, info.panic_reason) {
+            msg!("Revert panic: {:#x} ({})", code, reason);
+        }
+        if let Some(selector) = info.selector {
+            msg!("Revert custom error: 0x{}", hex::encode(selector));
         }
 
         Ok(())
@@ -401,15 +558,20 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
             }
             Trap::ExitFromSnapshot(reason) => {
                 let snapshot = self.pop_snapshot().expect("vm fault");
+                let reason = self.charge_code_deposit(&snapshot, reason);
                 let exit = self.commit_exit(snapshot, reason);
+                self.handler.gas.pop_frame(&reason);
 
                 if let Some((_, reason_)) = exit.as_ref() {
-                    if !reason_.is_succeed() {
-                        self.handler.revert_all();  // fatal error or there is no parent snapshot
-                    }
-                    if reason.is_revert() {
+                    // fatal error or there is no parent snapshot
+                    if reason_.is_fatal() {
+                        self.handler.revert_all();
+                    } else if !reason_.is_succeed() {
+                        // the reverted and the failed tx are committed, the fatal one is not
+                        self.handler.revert_frames();
                         self.inc_origin_nonce();
                     }
+                    self.trace_state();
                     return exit
                 }
 
@@ -422,22 +584,49 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
                 exit
             }
             Trap::ExitNoShapshot(value, reason) => {
-                if reason.is_succeed() || reason.is_revert() {  // revert is never reached from non-evm programs
+                // the top-level call of the non-evm program or the precompile, the failed non-evm program reverts
+                if !reason.is_fatal() {
                     self.inc_origin_nonce();
                 }
-                // no need to revert diff, it was done in handler.call()
-                
+                // no need to revert diff, it was done in handler.call().
+                // gas.used is the intrinsic gas and the cost of the precompile if it is called
+                self.trace_state();
+
                 Some((value, reason))
             }
         }
     }
 
+    fn charge_code_deposit(&mut self, snapshot: &Snapshot, reason: ExitReason) -> ExitReason {
+        if let (Reason::Create(_), true) = (&snapshot.reason, reason.is_succeed()) {
+            let len = snapshot.evm.machine().return_value().len() as u64;
+            if len > self.handler.state.config().max_code_size {
+                return ExitReason::Error(ExitError::CreateContractLimit);
+            }
+            let charged = self
+                .handler
+                .gas
+                .frame()
+                .and_then(|frame| frame.charge(CODE_DEPOSIT * len));
+
+            if let Err(e) = charged {
+                return ExitReason::Error(e);
+            }
+        }
+
+        reason
+    }
+
     pub fn inc_origin_nonce(&mut self) {
         let from = self.handler.origin.unwrap();
         self.handler.journal.get_mut(&from).push(Diff::NonceChange);
     }
 
     pub fn execute(&mut self, steps: u64) -> Option<(Vec<u8>, ExitReason)> {
+        if self.handler.state.step_tracer().is_some() {
+            return self.execute_traced(steps);
+        }
+
         let snapshot = self.snapshot.as_mut().expect("vm fault");
         let (steps, capture) = snapshot.evm.run(steps, &mut self.handler);
         self.steps_executed += steps;
@@ -445,16 +634,91 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
         Self::to_trap(capture).and_then(|trap| self.trap(trap))
     }
  
+    // executes the opcodes one by one and reports the vm state to the step tracer
+    fn execute_traced(&mut self, steps: u64) -> Option<(Vec<u8>, ExitReason)> {
+        for _ in 0..steps {
+            let step = self.trace_step();
+            let frame = self.handler.gas.frames.len().checked_sub(1);
+            let used = |gas: &Gas| frame.and_then(|ix| gas.frames.get(ix)).map_or(0, |x| x.used);
+            let before = used(&self.handler.gas);
+
+            let snapshot = self.snapshot.as_mut().expect("vm fault");
+            let (executed, capture) = snapshot.evm.run(1, &mut self.handler);
+            self.steps_executed += executed;
+
+            if let (Some(mut step), Some(tracer)) = (step, self.handler.state.step_tracer()) {
+                // CALL and CREATE charge the gas passed to the callee
+                step.gas_cost = used(&self.handler.gas).saturating_sub(before);
+                tracer.step(step);
+            }
+
+            if let Some(exit) = Self::to_trap(capture).and_then(|trap| self.trap(trap)) {
+                return Some(exit);
+            }
+        }
+
+        None
+    }
+
+    fn trace_step(&self) -> Option<TraceStep> {
+        let tracer = self.handler.state.step_tracer()?;
+        let snapshot = self.snapshot.as_ref()?;
+        let machine = snapshot.evm.machine();
+
+        let pc = *machine.position().as_ref().ok()?;
+        let (opcode, stack) = machine.inspect()?;
+
+        let address = snapshot.evm.context().address;
+        let storage = match opcode.0 {
+            // SLOAD
+            0x54 => stack.peek(0).ok().map(|key| {
+                let key = U256::from_big_endian(key.as_bytes());
+                (address, key, self.handler.storage(address, key))
+            }),
+            // SSTORE
+            0x55 => match (stack.peek(0), stack.peek(1)) {
+                (Ok(key), Ok(value)) => Some((
+                    address,
+                    U256::from_big_endian(key.as_bytes()),
+                    U256::from_big_endian(value.as_bytes()),
+                )),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        let step = TraceStep {
+            pc,
+            opcode: opcode.0,
+            gas: self.handler.gas.left().unwrap_or_default(),
+            gas_cost: 0,
+            depth: snapshot.depth(),
+            stack: tracer.capture_stack().then(|| stack.data().clone()),
+            memory: tracer.capture_memory().then(|| machine.memory().data().clone()),
+            storage,
+        };
+
+        Some(step)
+    }
+
     pub fn gas_transfer(&mut self, fee:u64, refund: u64) -> Result<()> {
-        let mut buf_limit = [0_u8; 32];
-        let mut buf_price = [0_u8; 32];
+        // the failed tx consumes all the gas
+        let gas_used = match (self.exit_reason, self.handler.gas_limit) {
+            (Some(reason), Some(limit)) if reason.is_error() => limit.low_u64(),
+            _ => self.handler.gas.used,
+        };
+        log_gas_used(gas_used);
 
         if let Some(to) = self.handler.gas_recipient {
             let gas_limit = self.handler.gas_limit.unwrap();
             let gas_price = self.handler.gas_price.unwrap();
 
             let from = self.handler.origin.unwrap();
-            let lamports: U256 = fee.saturating_sub(refund).into();
+            // the failed tx consumes all the gas
+            let lamports: U256 = match self.exit_reason {
+                Some(reason) if reason.is_error() => gas_limit,
+                _ => fee.saturating_sub(refund).into(),
+            };
 
             if lamports > gas_limit {
                 return Err(InsufficientGas(gas_limit, lamports))
@@ -463,14 +727,10 @@ impl<'a, T: Origin + Allocate> Vm<'a, T> {
             let wei = lamports.checked_mul(gas_price).ok_or(CalculationOverflow)?;
             self.handler.transfer(&from, &to, &wei);
 
-            lamports.to_big_endian(&mut buf_limit);
-            gas_price.to_big_endian(&mut buf_price);
-            
-            sol_log_data(&[GAS_RECIPIENT, to.as_bytes()]);
+            log_gas(Some(&to), &lamports, &gas_price);
+        } else {
+            log_gas(None, &U256::zero(), &U256::zero());
         }
-        
-        sol_log_data(&[GAS_VALUE, &buf_limit]);
-        sol_log_data(&[GAS_PRICE, &buf_price]);
 
         Ok(())
     }
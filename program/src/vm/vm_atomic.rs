@@ -64,10 +64,11 @@ impl<T: Origin + Allocate, L: AccountLock> Execute<MachineAt> for VmAt<'_, T, L>
                 msg!("Init");
                 if let Some((value, reason)) = self.vm.init(&mut self.tx, true, self.fee_addr)? {
                     self.vm.set_exit_reason(reason, value);
-                    if reason.is_succeed() || reason.is_revert() {
-                        Commit
-                    } else {
+                    // the journal of the failed tx is reverted, the nonce and the fee are committed
+                    if reason.is_fatal() {
                         Exit
+                    } else {
+                        Commit
                     }
                 } else {
                     Execute
@@ -77,10 +78,10 @@ impl<T: Origin + Allocate, L: AccountLock> Execute<MachineAt> for VmAt<'_, T, L>
                 msg!("Execute");
                 if let Some((return_value, reason)) = self.vm.execute(u64::MAX) {
                     self.vm.set_exit_reason(reason, return_value);
-                    if reason.is_succeed() || reason.is_revert() {
-                        Commit 
-                    } else {
+                    if reason.is_fatal() {
                         Exit
+                    } else {
+                        Commit
                     }
                 } else {
                     Execute
@@ -2,7 +2,7 @@ use {
     super::{vm::Vm, Execute},
     crate::{
         accounts::Iterations,
-        config::{NUMBER_OPCODES_PER_TX, SIG_VERIFY_COST, HASH},
+        config::{SIG_VERIFY_COST, HASH},
         context::{AccountLock, Context},
         error::{Result, RomeProgramError::*},
         origin::Origin,
@@ -179,10 +179,11 @@ impl<T: Origin + Allocate, L: AccountLock + Context> Execute<MachineIt> for VmIt
 
                 let state =  if let Some((value, reason)) = self.vm.init(&mut tx, check_nonce, fee_addr)? {
                     self.vm.set_exit_reason(reason, value);
-                    if reason.is_succeed() || reason.is_revert() {
-                        Commit
-                    } else {
+                    // the journal of the failed tx is reverted, the nonce and the fee are committed
+                    if reason.is_fatal() {
                         UnlockFailedTx // skip Commit
+                    } else {
+                        Commit
                     }
                 } else {
                     Execute
@@ -223,22 +224,21 @@ impl<T: Origin + Allocate, L: AccountLock + Context> Execute<MachineIt> for VmIt
             }
             IntoTrap => {
                 msg!("IntoTrap");
-                let steps_left = NUMBER_OPCODES_PER_TX.saturating_sub(self.vm.steps_executed);
+                let budget = self.vm.handler.state.config().opcodes_per_iteration;
+                let steps_left = budget.saturating_sub(self.vm.steps_executed);
 
                 if let Some((return_value, reason)) = self.vm.execute(steps_left) {
                     self.vm.set_exit_reason(reason, return_value);
                     let next_step = if reason.is_succeed() {
                         Allocate
+                    } else if reason.is_fatal() {
+                        UnlockFailedTx // skip Commit
                     } else {
-                        if reason.is_revert() {
-                            Commit // skip Allocate
-                        } else {
-                            UnlockFailedTx // skip Commit
-                        }
+                        Commit // skip Allocate
                     };
                     Serialize(Box::new(next_step))
 
-                } else if NUMBER_OPCODES_PER_TX.saturating_sub(self.vm.steps_executed) > 0 {
+                } else if budget.saturating_sub(self.vm.steps_executed) > 0 {
                     IntoTrap
                 } else {
                     Serialize(Box::new(Execute))
@@ -0,0 +1,56 @@
+use {solana_program::pubkey::Pubkey, std::str::FromStr};
+
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:8545";
+
+const USAGE: &str =
+    "usage: rome-emulator-rpc --rpc-url <url> --program-id <pubkey> --chain-id <id> [--listen <addr>]";
+
+/// The settings of the server
+#[derive(Clone, Debug)]
+pub struct Config {
+    // Solana RPC, the source of the accounts
+    pub rpc_url: String,
+    pub program_id: Pubkey,
+    pub chain_id: u64,
+    pub listen: String,
+}
+
+impl Config {
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut rpc_url = None;
+        let mut program_id = None;
+        let mut chain_id = None;
+        let mut listen = DEFAULT_LISTEN.to_string();
+
+        let mut args = args;
+        while let Some(arg) = args.next() {
+            let value = args
+                .next()
+                .ok_or(format!("the value of {} expected\n{}", arg, USAGE))?;
+
+            match arg.as_str() {
+                "--rpc-url" => rpc_url = Some(value),
+                "--program-id" => {
+                    let key = Pubkey::from_str(&value)
+                        .map_err(|e| format!("invalid program id {}: {}", value, e))?;
+                    program_id = Some(key)
+                }
+                "--chain-id" => {
+                    let chain = value
+                        .parse::<u64>()
+                        .map_err(|e| format!("invalid chain id {}: {}", value, e))?;
+                    chain_id = Some(chain)
+                }
+                "--listen" => listen = value,
+                _ => return Err(format!("unknown argument {}\n{}", arg, USAGE)),
+            }
+        }
+
+        Ok(Self {
+            rpc_url: rpc_url.ok_or(format!("--rpc-url expected\n{}", USAGE))?,
+            program_id: program_id.ok_or(format!("--program-id expected\n{}", USAGE))?,
+            chain_id: chain_id.ok_or(format!("--chain-id expected\n{}", USAGE))?,
+            listen,
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use {
+    crate::error::RpcError,
+    rome_evm::{tx::legacy::Legacy, H160, U256},
+    serde_json::Value,
+};
+
+// Ethereum JSON-RPC encoding: the quantities are hex without leading zeros, the data is even-length hex
+
+pub fn quantity(value: U256) -> String {
+    format!("{:#x}", value)
+}
+
+pub fn data(value: &[u8]) -> String {
+    format!("0x{}", hex::encode(value))
+}
+
+pub fn word(value: U256) -> String {
+    let mut buf = [0_u8; 32];
+    value.to_big_endian(&mut buf);
+    data(&buf)
+}
+
+fn hex_str<'a>(value: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+    value
+        .as_str()
+        .and_then(|x| x.strip_prefix("0x"))
+        .ok_or(RpcError::invalid_params(format!("{}: 0x-prefixed hex string expected", name)))
+}
+
+pub fn parse_quantity(value: &Value, name: &str) -> Result<U256, RpcError> {
+    let hex = hex_str(value, name)?;
+    if hex.is_empty() || hex.len() > 64 {
+        return Err(RpcError::invalid_params(format!("{}: invalid quantity", name)));
+    }
+
+    U256::from_str_radix(hex, 16)
+        .map_err(|_| RpcError::invalid_params(format!("{}: invalid quantity", name)))
+}
+
+pub fn parse_u64(value: &Value, name: &str) -> Result<u64, RpcError> {
+    let quantity = parse_quantity(value, name)?;
+    if quantity > u64::MAX.into() {
+        return Err(RpcError::invalid_params(format!("{}: u64 overflow", name)));
+    }
+
+    Ok(quantity.as_u64())
+}
+
+pub fn parse_data(value: &Value, name: &str) -> Result<Vec<u8>, RpcError> {
+    let hex = hex_str(value, name)?;
+    hex::decode(hex).map_err(|e| RpcError::invalid_params(format!("{}: {}", name, e)))
+}
+
+pub fn parse_address(value: &Value, name: &str) -> Result<H160, RpcError> {
+    let bin = parse_data(value, name)?;
+    if bin.len() != 20 {
+        return Err(RpcError::invalid_params(format!("{}: invalid address", name)));
+    }
+
+    Ok(H160::from_slice(&bin))
+}
+
+// the requests are emulated against the latest state, the historical blocks are not supported
+pub fn check_block_tag(value: Option<&Value>) -> Result<(), RpcError> {
+    match value {
+        None | Some(Value::Null) => Ok(()),
+        Some(Value::String(tag)) if tag == "latest" || tag == "pending" => Ok(()),
+        Some(tag) => Err(RpcError::invalid_params(format!(
+            "block: {} is not supported, latest or pending expected",
+            tag
+        ))),
+    }
+}
+
+fn field<'a>(object: &'a Value, name: &str) -> Option<&'a Value> {
+    object.get(name).filter(|x| !x.is_null())
+}
+
+// the transaction call object of eth_call and eth_estimateGas
+pub fn call_object(object: &Value, chain_id: u64) -> Result<Legacy, RpcError> {
+    if !object.is_object() {
+        return Err(RpcError::invalid_params("transaction call object expected"));
+    }
+
+    let optional = |name: &str| field(object, name).map(|x| parse_quantity(x, name)).transpose();
+
+    let from = field(object, "from")
+        .map(|x| parse_address(x, "from"))
+        .transpose()?
+        .unwrap_or_default();
+    let to = field(object, "to").map(|x| parse_address(x, "to")).transpose()?;
+    // the input field replaces the data field since the London fork
+    let input = field(object, "input")
+        .or_else(|| field(object, "data"))
+        .map(|x| parse_data(x, "input"))
+        .transpose()?
+        .unwrap_or_default();
+    let gas_price = match optional("gasPrice")? {
+        Some(price) => price,
+        None => optional("maxFeePerGas")?.unwrap_or_default(),
+    };
+    let nonce = field(object, "nonce")
+        .map(|x| parse_u64(x, "nonce"))
+        .transpose()?
+        .unwrap_or_default();
+
+    Ok(Legacy {
+        nonce,
+        gas_price,
+        gas_limit: optional("gas")?.unwrap_or_default(),
+        to,
+        value: optional("value")?.unwrap_or_default(),
+        data: Some(input),
+        v: U256::zero(),
+        r: U256::zero(),
+        s: U256::zero(),
+        chain_id: chain_id.into(),
+        from,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        serde_json::json,
+    };
+
+    #[test]
+    fn quantities() {
+        assert_eq!(quantity(U256::zero()), "0x0");
+        assert_eq!(quantity(U256::from(1024)), "0x400");
+        assert_eq!(parse_quantity(&json!("0x400"), "x").unwrap(), U256::from(1024));
+
+        assert!(parse_quantity(&json!("0x"), "x").is_err());
+        assert!(parse_quantity(&json!("400"), "x").is_err());
+        assert!(parse_quantity(&json!(1024), "x").is_err());
+    }
+
+    #[test]
+    fn data_and_words() {
+        assert_eq!(data(&[]), "0x");
+        assert_eq!(data(&[0x0a, 0xff]), "0x0aff");
+        assert_eq!(parse_data(&json!("0x0aff"), "x").unwrap(), vec![0x0a, 0xff]);
+        assert!(parse_data(&json!("0xaff"), "x").is_err());
+
+        assert_eq!(word(U256::one()), format!("0x{}1", "0".repeat(63)));
+    }
+
+    #[test]
+    fn call() {
+        let object = json!({
+            "from": "0x1111111111111111111111111111111111111111",
+            "to": "0x2222222222222222222222222222222222222222",
+            "gas": "0x5208",
+            "maxFeePerGas": "0x10",
+            "value": "0x1",
+            "data": "0xd09de08a",
+        });
+
+        let legacy = call_object(&object, 1001).unwrap();
+        assert_eq!(legacy.from, H160::repeat_byte(0x11));
+        assert_eq!(legacy.to, Some(H160::repeat_byte(0x22)));
+        assert_eq!(legacy.gas_limit, U256::from(21_000));
+        assert_eq!(legacy.gas_price, U256::from(16));
+        assert_eq!(legacy.value, U256::one());
+        assert_eq!(legacy.data, Some(vec![0xd0, 0x9d, 0xe0, 0x8a]));
+        assert_eq!(legacy.chain_id, U256::from(1001));
+
+        let legacy = call_object(&json!({"to": null}), 1001).unwrap();
+        assert_eq!(legacy.to, None);
+        assert_eq!(legacy.from, H160::zero());
+
+        assert!(call_object(&json!("0x"), 1001).is_err());
+        assert!(call_object(&json!({"to": "0x22"}), 1001).is_err());
+    }
+}
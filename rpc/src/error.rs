@@ -0,0 +1,48 @@
+use {
+    rome_evm::error::RomeProgramError,
+    serde_json::{json, Value},
+};
+
+// JSON-RPC 2.0
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+// EIP-1474
+pub const SERVER_ERROR: i64 = -32000;
+// geth: the revert data is returned in the data field
+pub const EXECUTION_REVERTED: i64 = 3;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<String>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    pub fn invalid_params(message: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, message)
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self.data.as_ref() {
+            Some(data) => json!({"code": self.code, "message": self.message, "data": data}),
+            None => json!({"code": self.code, "message": self.message}),
+        }
+    }
+}
+
+impl From<RomeProgramError> for RpcError {
+    fn from(e: RomeProgramError) -> Self {
+        Self::new(SERVER_ERROR, e.to_string())
+    }
+}
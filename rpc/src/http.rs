@@ -0,0 +1,130 @@
+use {
+    crate::server::Rpc,
+    std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    },
+};
+
+// the limit of the request body
+const MAX_BODY: usize = 16 * 1024 * 1024;
+// the limit of the connections served at the same time, the rest are rejected with 503
+const MAX_CONNECTIONS: usize = 64;
+// the slow clients do not hold the threads
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+// the number of the connections being served, decremented on drop
+struct Slot(Arc<AtomicUsize>);
+
+impl Slot {
+    fn take(active: &Arc<AtomicUsize>) -> Option<Self> {
+        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            active.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+
+        Some(Self(Arc::clone(active)))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// each connection is served by its own thread, the emulations do not share any state
+pub fn serve(rpc: Arc<Rpc>, listen: &str) -> std::io::Result<()> {
+    let listener = TcpListener::bind(listen)?;
+    println!("rome-emulator-rpc: listening on {}", listen);
+    let active = Arc::new(AtomicUsize::new(0));
+
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                println!("connection error: {}", e);
+                continue;
+            }
+        };
+
+        let slot = match Slot::take(&active) {
+            Some(slot) => slot,
+            None => {
+                let _ = write(&mut stream, "503 Service Unavailable", "");
+                continue;
+            }
+        };
+
+        let rpc = Arc::clone(&rpc);
+        thread::spawn(move || {
+            let _slot = slot;
+            if let Err(e) = handle_connection(&rpc, stream) {
+                println!("connection error: {}", e);
+            }
+        });
+    }
+
+    Ok(())
+}
+
+fn handle_connection(rpc: &Rpc, mut stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let method = request_line.split_whitespace().next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or_default();
+            }
+        }
+    }
+
+    match method.as_str() {
+        "POST" if content_length <= MAX_BODY => {
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            let body = String::from_utf8_lossy(&body);
+
+            match rpc.handle(&body) {
+                Some(response) => write(&mut stream, "200 OK", &response),
+                None => write(&mut stream, "204 No Content", ""),
+            }
+        }
+        "POST" => write(&mut stream, "413 Payload Too Large", ""),
+        "OPTIONS" => write(&mut stream, "204 No Content", ""),
+        _ => write(&mut stream, "405 Method Not Allowed", ""),
+    }
+}
+
+fn write(stream: &mut TcpStream, status: &str, body: &str) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\n\
+         Access-Control-Allow-Headers: content-type\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+
+    stream.write_all(response.as_bytes())?;
+    stream.flush()
+}
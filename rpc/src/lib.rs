@@ -0,0 +1,9 @@
+pub mod config;
+pub mod encoding;
+pub mod error;
+pub mod http;
+pub mod server;
+
+pub use config::Config;
+pub use error::RpcError;
+pub use server::Rpc;
@@ -0,0 +1,23 @@
+use {
+    rome_emulator_rpc::{http::serve, Config, Rpc},
+    solana_client::rpc_client::RpcClient,
+    std::{process::exit, sync::Arc},
+};
+
+fn main() {
+    let config = match Config::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            exit(1);
+        }
+    };
+
+    let client = RpcClient::new(config.rpc_url.clone());
+    let rpc = Arc::new(Rpc::new(config.program_id, config.chain_id, Arc::new(client)));
+
+    if let Err(e) = serve(rpc, &config.listen) {
+        eprintln!("{}", e);
+        exit(1);
+    }
+}
@@ -0,0 +1,295 @@
+use {
+    crate::{
+        encoding::{
+            call_object, check_block_tag, data, parse_address, parse_quantity, quantity, word,
+        },
+        error::*,
+    },
+    emulator::{
+        eth_call, eth_estimate_gas, eth_get_balance, eth_get_code, eth_get_storage_at,
+        eth_get_tx_count, retry_on_slot_mismatch as retry, AccountSource, Emulation,
+    },
+    serde_json::{json, Value},
+    solana_program::pubkey::Pubkey,
+    std::sync::Arc,
+};
+
+/// JSON-RPC 2.0 handler of the Ethereum methods, the requests are emulated against the latest state
+pub struct Rpc {
+    pub program_id: Pubkey,
+    pub chain_id: u64,
+    pub source: Arc<dyn AccountSource>,
+}
+
+impl Rpc {
+    pub fn new(program_id: Pubkey, chain_id: u64, source: Arc<dyn AccountSource>) -> Self {
+        Self {
+            program_id,
+            chain_id,
+            source,
+        }
+    }
+
+    // None: the body contains the notifications only
+    pub fn handle(&self, body: &str) -> Option<String> {
+        let request = match serde_json::from_str::<Value>(body) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = RpcError::new(PARSE_ERROR, e.to_string());
+                return Some(response(Value::Null, Err(error)).to_string());
+            }
+        };
+
+        let response = match request {
+            Value::Array(batch) if batch.is_empty() => {
+                Some(response(Value::Null, Err(RpcError::new(INVALID_REQUEST, "empty batch"))))
+            }
+            Value::Array(batch) => {
+                let responses = batch
+                    .iter()
+                    .filter_map(|request| self.handle_request(request))
+                    .collect::<Vec<_>>();
+
+                if responses.is_empty() {
+                    None
+                } else {
+                    Some(Value::Array(responses))
+                }
+            }
+            request => self.handle_request(&request),
+        };
+
+        response.map(|x| x.to_string())
+    }
+
+    fn handle_request(&self, request: &Value) -> Option<Value> {
+        let id = request.get("id").cloned();
+        let method = request.get("method").and_then(|x| x.as_str());
+
+        if request.get("jsonrpc").and_then(|x| x.as_str()) != Some("2.0") || method.is_none() {
+            let error = RpcError::new(INVALID_REQUEST, "invalid request");
+            return Some(response(id.unwrap_or(Value::Null), Err(error)));
+        }
+
+        let params = match request.get("params") {
+            None | Some(Value::Null) => vec![],
+            Some(Value::Array(params)) => params.clone(),
+            Some(_) => {
+                let error = RpcError::invalid_params("params array expected");
+                return Some(response(id.unwrap_or(Value::Null), Err(error)));
+            }
+        };
+
+        let result = self.call(method.unwrap(), &params);
+
+        // the notification does not have the response
+        id.map(|id| response(id, result))
+    }
+
+    pub fn call(&self, method: &str, params: &[Value]) -> Result<Value, RpcError> {
+        let param = |ix: usize, name: &str| {
+            params
+                .get(ix)
+                .ok_or(RpcError::invalid_params(format!("missing value for required argument {}", name)))
+        };
+        let source = || Arc::clone(&self.source);
+        let block = |ix: usize| check_block_tag(params.get(ix));
+
+        let result = match method {
+            "eth_chainId" => json!(quantity(self.chain_id.into())),
+            "eth_call" => {
+                let legacy = call_object(param(0, "transaction")?, self.chain_id)?;
+                block(1)?;
+                let emulation = retry(|| eth_call(&self.program_id, legacy.clone(), source()))?;
+                json!(data(&output(&emulation)?))
+            }
+            "eth_estimateGas" => {
+                let legacy = call_object(param(0, "transaction")?, self.chain_id)?;
+                block(1)?;
+                let emulation = retry(|| eth_estimate_gas(&self.program_id, legacy.clone(), source()))?;
+                output(&emulation)?;
+                json!(quantity(emulation.gas.into()))
+            }
+            "eth_getBalance" => {
+                let address = parse_address(param(0, "address")?, "address")?;
+                block(1)?;
+                let balance = retry(|| eth_get_balance(&self.program_id, &address, source(), self.chain_id))?;
+                json!(quantity(balance))
+            }
+            "eth_getCode" => {
+                let address = parse_address(param(0, "address")?, "address")?;
+                block(1)?;
+                let code = retry(|| eth_get_code(&self.program_id, &address, source(), self.chain_id))?;
+                json!(data(&code))
+            }
+            "eth_getStorageAt" => {
+                let address = parse_address(param(0, "address")?, "address")?;
+                let slot = parse_quantity(param(1, "slot")?, "slot")?;
+                block(2)?;
+                let value = retry(|| {
+                    eth_get_storage_at(&self.program_id, &address, &slot, source(), self.chain_id)
+                })?;
+                json!(word(value))
+            }
+            "eth_getTransactionCount" => {
+                let address = parse_address(param(0, "address")?, "address")?;
+                block(1)?;
+                let nonce = retry(|| eth_get_tx_count(&self.program_id, &address, source(), self.chain_id))?;
+                json!(quantity(nonce.into()))
+            }
+            _ => {
+                return Err(RpcError::new(
+                    METHOD_NOT_FOUND,
+                    format!("the method {} does not exist/is not available", method),
+                ))
+            }
+        };
+
+        Ok(result)
+    }
+}
+
+// the return value of the succeeded call, the revert data is reported in the geth format
+fn output(emulation: &Emulation) -> Result<Vec<u8>, RpcError> {
+    let vm = emulation
+        .vm
+        .as_ref()
+        .ok_or(RpcError::new(SERVER_ERROR, "vm result expected"))?;
+    let value = vm.return_value.clone().unwrap_or_default();
+
+    if vm.exit_reason.is_succeed() {
+        return Ok(value);
+    }
+
+    let error = match vm.revert.as_ref() {
+        Some(revert) => {
+            let message = match (revert.reason.as_ref(), revert.panic_reason) {
+                (Some(reason), _) => format!("execution reverted: {}", reason),
+                (None, Some(reason)) => format!("execution reverted: panic: {}", reason),
+                (None, None) => "execution reverted".to_string(),
+            };
+
+            RpcError {
+                code: EXECUTION_REVERTED,
+                message,
+                data: Some(data(&value)),
+            }
+        }
+        None => RpcError::new(SERVER_ERROR, format!("{:?}", vm.exit_reason)),
+    };
+
+    Err(error)
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+        Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error.to_json()}),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::Rpc,
+        emulator::{
+            fixture::{Fixture, CHAIN, RETURN_42},
+            AccountDump,
+        },
+        rome_evm::{H160, U256},
+        serde_json::{json, Value},
+        solana_program::pubkey::Pubkey,
+        std::sync::Arc,
+    };
+
+    fn rpc() -> Rpc {
+        Rpc::new(Pubkey::new_unique(), 1001, Arc::new(AccountDump::new()))
+    }
+
+    fn handle(rpc: &Rpc, body: Value) -> Value {
+        let response = rpc.handle(&body.to_string()).unwrap();
+        serde_json::from_str(&response).unwrap()
+    }
+
+    #[test]
+    fn chain_id() {
+        let response = handle(&rpc(), json!({"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"}));
+        assert_eq!(response, json!({"jsonrpc": "2.0", "id": 1, "result": "0x3e9"}));
+    }
+
+    #[test]
+    fn errors() {
+        let rpc = rpc();
+        let response: Value = serde_json::from_str(&rpc.handle("{").unwrap()).unwrap();
+        assert_eq!(response["error"]["code"], -32700);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = handle(&rpc, json!({"jsonrpc": "2.0", "id": 2, "method": "eth_foo"}));
+        assert_eq!(response["error"]["code"], -32601);
+
+        let response = handle(&rpc, json!({"id": 3, "method": "eth_chainId"}));
+        assert_eq!(response["error"]["code"], -32600);
+
+        let response = handle(&rpc, json!({"jsonrpc": "2.0", "id": 4, "method": "eth_getBalance", "params": []}));
+        assert_eq!(response["error"]["code"], -32602);
+
+        let response = handle(&rpc, json!([]));
+        assert_eq!(response["error"]["code"], -32600);
+    }
+
+    #[test]
+    fn batch() {
+        let rpc = rpc();
+        let response = handle(&rpc, json!([
+            {"jsonrpc": "2.0", "id": 1, "method": "eth_chainId"},
+            {"jsonrpc": "2.0", "method": "eth_chainId"},
+            {"jsonrpc": "2.0", "id": "two", "method": "eth_foo"},
+        ]));
+
+        let response = response.as_array().unwrap();
+        assert_eq!(response.len(), 2);
+        assert_eq!(response[0]["result"], "0x3e9");
+        assert_eq!(response[1]["id"], "two");
+        assert_eq!(response[1]["error"]["code"], -32601);
+
+        let notification = json!({"jsonrpc": "2.0", "method": "eth_chainId"});
+        assert_eq!(rpc.handle(&notification.to_string()), None);
+    }
+
+    #[test]
+    fn get_balance() {
+        let mut fixture = Fixture::new();
+        let address = H160::repeat_byte(1);
+        fixture.balance(&address, U256::from(1_000));
+        let rpc = Rpc::new(fixture.program_id, CHAIN, fixture.source());
+
+        let request = |params: Value| json!({"jsonrpc": "2.0", "id": 1, "method": "eth_getBalance", "params": params});
+        let address = format!("{:?}", address);
+
+        let response = handle(&rpc, request(json!([address, "latest"])));
+        assert_eq!(response["result"], "0x3e8");
+        let response = handle(&rpc, request(json!([format!("{:?}", H160::repeat_byte(2))])));
+        assert_eq!(response["result"], "0x0");
+
+        // the historical blocks are not supported
+        for tag in [json!("earliest"), json!("0x1"), json!({"blockHash": "0x00"})] {
+            let response = handle(&rpc, request(json!([address, tag])));
+            assert_eq!(response["error"]["code"], -32602);
+        }
+    }
+
+    #[test]
+    fn call() {
+        let mut fixture = Fixture::new();
+        let contract = H160::repeat_byte(0xcc);
+        fixture.contract(&contract, U256::zero(), &RETURN_42);
+        let rpc = Rpc::new(fixture.program_id, CHAIN, fixture.source());
+
+        let call = json!({"to": format!("{:?}", contract)});
+        let response = handle(&rpc, json!({"jsonrpc": "2.0", "id": 1, "method": "eth_call", "params": [call, "pending"]}));
+        assert_eq!(response["result"], format!("0x{:064x}", 42));
+
+        let response = handle(&rpc, json!({"jsonrpc": "2.0", "id": 1, "method": "eth_call", "params": [call, "earliest"]}));
+        assert_eq!(response["error"]["code"], -32602);
+    }
+}
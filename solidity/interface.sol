@@ -18,12 +18,29 @@ interface ISystemProgram {
     function transfer_(bytes32 to, uint64 amount) external;
 }
 
+struct AccountMeta {
+    bytes32 pubkey;
+    bool is_signer;
+    bool is_writable;
+}
+
+// the instruction is signed by the balance PDA of the caller, it is the only allowed signer.
+// the program must be executable and must be in the accounts of the Solana transaction.
+// the call is not executed inside the EVM: it returns immediately and the instruction is invoked
+// after the EVM execution, when the state is applied. The contract can't observe the results
+// of the program in the same transaction. The emulator applies the declared account effects (the hints)
+interface ICpiProgram {
+    function invoke(bytes32 program_id, AccountMeta[] calldata metas, bytes calldata data) external;
+}
+
 address constant spl_token_address = address(0xff00000000000000000000000000000000000005);
 address constant aspl_token_address = address(0xFF00000000000000000000000000000000000006);
 address constant system_program_address = address(0xfF00000000000000000000000000000000000007);
+address constant cpi_program_address = address(0xFF00000000000000000000000000000000000008);
 
 ISplToken constant SplProgram = ISplToken(spl_token_address);
 IAssociatedSplToken constant ASplProgram = IAssociatedSplToken(aspl_token_address);
 ISystemProgram constant SystemProgram = ISystemProgram(system_program_address);
+ICpiProgram constant CpiProgram = ICpiProgram(cpi_program_address);
 
 